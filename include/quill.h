@@ -0,0 +1,51 @@
+#ifndef QUILL_H
+#define QUILL_H
+
+/* Generated with cbindgen from src/ffi.rs by `make header`, don't edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Signs an ICP transfer like `quill transfer` and sets `*out` to the JSON of
+// the signed messages. `fee` and `memo` may be null for the defaults.
+//
+// # Safety
+//
+// The strings must be null or valid null-terminated strings, and `out` must
+// be a valid pointer.
+int quill_sign_transfer(const char *pem,
+                        const char *to,
+                        const char *amount,
+                        const char *fee,
+                        const char *memo,
+                        char **out);
+
+// Signs a neuron command like `quill neuron-manage`, given as a JSON object
+// like `{"neuron_id": "<neuron-id>", "start_dissolving": true}`, and sets
+// `*out` to the JSON of the signed messages.
+//
+// # Safety
+//
+// The strings must be null or valid null-terminated strings, and `out` must
+// be a valid pointer.
+int quill_sign_neuron_command(const char *pem, const char *command, char **out);
+
+// Verifies signed messages like `quill verify` and sets `*out` to one line
+// per checked envelope, or to the problems found.
+//
+// # Safety
+//
+// `message` must be null or a valid null-terminated string, and `out` must be
+// a valid pointer.
+int quill_verify_message(const char *message, char **out);
+
+// Frees a string returned by the functions above.
+//
+// # Safety
+//
+// `s` must be null or a string returned by quill, which wasn't freed before.
+void quill_free_string(char *s);
+
+#endif /* QUILL_H */
@@ -0,0 +1,41 @@
+//! A local book mapping friendly names to values, stored as JSON in the config directory.
+
+use crate::{get_config_dir, AnyhowResult};
+use anyhow::{anyhow, Context};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub struct AliasBook {
+    path: PathBuf,
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl AliasBook {
+    /// Loads the book from the given file in the config directory; a missing file yields an empty book.
+    pub fn load(file_name: &str) -> AnyhowResult<Self> {
+        let path = get_config_dir()?.join(file_name);
+        let aliases = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Couldn't read {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Couldn't parse {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self { path, aliases })
+    }
+
+    pub fn save(&self) -> AnyhowResult {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.aliases)?)
+            .with_context(|| format!("Couldn't write {}", self.path.display()))
+    }
+
+    pub fn get(&self, name: &str) -> AnyhowResult<&String> {
+        self.aliases
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown alias: {}", name))
+    }
+}
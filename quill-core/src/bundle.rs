@@ -0,0 +1,331 @@
+//! Versioned bundles of signed messages, which are sent in order.
+
+use crate::{
+    crypto::sha256,
+    dfx::DfxMessage,
+    error::QuillError,
+    sign::signed_message::{
+        BundleLink, Ingress, IngressWithRequestId, RequestStatus, SignedMessageV2,
+    },
+    to_canonical_json, AnyhowResult,
+};
+use chrono::{Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+// The replica only accepts messages which expire at most this many minutes
+// after their submission.
+const MAX_INGRESS_EXPIRY_MINUTES: i64 = 5;
+
+/// Signed messages which are sent in order, as printed with `--bundle`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Bundle {
+    pub version: u32,
+    pub messages: Vec<BundledMessage>,
+    /// The hex-encoded SHA-256 hash of the canonical JSON of the messages, i.e.
+    /// with sorted keys and without whitespace.
+    pub checksum: String,
+}
+
+/// Copies of the same bundle, signed with staggered expiries.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct Copies {
+    pub copies: Vec<Bundle>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct BundledMessage {
+    /// The position of the message in the bundle, starting at 0.
+    pub index: usize,
+    pub canister_id: String,
+    pub method_name: String,
+    /// The indices of the messages which have to be sent before this one.
+    pub depends_on: Vec<usize>,
+    pub ingress: Ingress,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_status: Option<RequestStatus>,
+}
+
+impl Bundle {
+    /// Bundles the messages in the given order, each depending on the previous one.
+    pub fn new(messages: Vec<(Ingress, Option<RequestStatus>)>) -> AnyhowResult<Self> {
+        Self::with_dependencies(
+            messages
+                .into_iter()
+                .enumerate()
+                .map(|(index, (ingress, request_status))| {
+                    let depends_on = index.checked_sub(1).into_iter().collect();
+                    (ingress, request_status, depends_on)
+                })
+                .collect(),
+        )
+    }
+
+    /// Bundles the messages in the given order, each depending only on the
+    /// messages at the given earlier indices. Messages without dependencies,
+    /// e.g. transfers to different accounts, can be sent at the same time.
+    pub fn with_dependencies(
+        messages: Vec<(Ingress, Option<RequestStatus>, Vec<usize>)>,
+    ) -> AnyhowResult<Self> {
+        let messages = messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, (ingress, request_status, depends_on))| {
+                let (_, _, canister_id, method_name, _) = ingress.inspect()?;
+                Ok(BundledMessage {
+                    index,
+                    canister_id: canister_id.to_text(),
+                    method_name,
+                    depends_on,
+                    ingress,
+                    request_status,
+                })
+            })
+            .collect::<AnyhowResult<Vec<_>>>()?;
+        let checksum = checksum(&messages)?;
+        let bundle = Self {
+            version: BUNDLE_VERSION,
+            messages,
+            checksum,
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    /// Parses a bundle, or one of the plain lists of messages printed by
+    /// earlier versions, which are bundled in their order, or a message file
+    /// of dfx. Of several copies of a bundle, the first one which can be sent
+    /// now is returned.
+    pub fn from_json(json: &str) -> AnyhowResult<Self> {
+        // Checked first, since a message of dfx also parses as an `Ingress`.
+        if let Ok(message) = serde_json::from_str::<DfxMessage>(json) {
+            return Self::new(vec![message.to_quill()?]);
+        }
+        if let Ok(copies) = serde_json::from_str::<Copies>(json) {
+            return copies.select();
+        }
+        if let Ok(bundle) = serde_json::from_str::<Bundle>(json) {
+            bundle.validate()?;
+            return Ok(bundle);
+        }
+        match serde_json::from_str::<Vec<SignedMessageV2>>(json) {
+            Ok(messages) if !messages.is_empty() => return Self::from_v2(messages),
+            _ => {}
+        }
+        let messages = if let Ok(val) = serde_json::from_str::<Ingress>(json) {
+            vec![(val, None)]
+        } else if let Ok(vals) = serde_json::from_str::<Vec<Ingress>>(json) {
+            vals.into_iter().map(|msg| (msg, None)).collect()
+        } else if let Ok(vals) = serde_json::from_str::<Vec<IngressWithRequestId>>(json) {
+            vals.into_iter()
+                .map(|tx| (tx.ingress, Some(tx.request_status)))
+                .collect()
+        } else {
+            return Err(QuillError::Validation("Invalid JSON content".to_string()).into());
+        };
+        Self::new(messages)
+    }
+
+    /// Returns the messages in the V2 format, linked to this bundle.
+    pub fn to_v2(&self) -> AnyhowResult<Vec<SignedMessageV2>> {
+        self.messages
+            .iter()
+            .map(|message| {
+                SignedMessageV2::new(
+                    message.ingress.clone(),
+                    message.request_status.clone(),
+                    BundleLink::new(
+                        self.checksum.clone(),
+                        message.index,
+                        message.depends_on.clone(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    // Rebuilds the bundle which the V2 messages were signed with.
+    fn from_v2(messages: Vec<SignedMessageV2>) -> AnyhowResult<Self> {
+        let mut checksums = Vec::new();
+        let mut bundled = Vec::new();
+        for message in messages {
+            message.validate()?;
+            checksums.push(message.bundle.checksum);
+            bundled.push(BundledMessage {
+                index: message.bundle.index,
+                canister_id: message.summary.canister_id,
+                method_name: message.summary.method_name,
+                depends_on: message.bundle.depends_on,
+                ingress: message.ingress,
+                request_status: message.request_status,
+            });
+        }
+        checksums.dedup();
+        if checksums.len() > 1 {
+            return Err(QuillError::Validation(
+                "The messages were signed in different bundles".to_string(),
+            )
+            .into());
+        }
+        let bundle = Self {
+            version: BUNDLE_VERSION,
+            messages: bundled,
+            checksum: checksums.pop().unwrap_or_default(),
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    fn validate(&self) -> AnyhowResult {
+        if self.version != BUNDLE_VERSION {
+            return Err(QuillError::Validation(format!(
+                "Unsupported bundle version {}",
+                self.version
+            ))
+            .into());
+        }
+        if checksum(&self.messages)? != self.checksum
+            && legacy_checksum(&self.messages)? != self.checksum
+        {
+            return Err(QuillError::Validation(
+                "The checksum of the bundle doesn't match its messages".to_string(),
+            )
+            .into());
+        }
+        for (i, message) in self.messages.iter().enumerate() {
+            if message.index != i || message.depends_on.iter().any(|dep| *dep >= i) {
+                return Err(QuillError::Validation(format!(
+                    "Invalid index or dependencies of message {} in the bundle",
+                    i
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Copies {
+    /// Collects copies of the same bundle, of which `send` picks the first one
+    /// which can be sent.
+    pub fn new(copies: Vec<Bundle>) -> Self {
+        Self { copies }
+    }
+
+    fn select(self) -> AnyhowResult<Bundle> {
+        let now = Utc::now();
+        let latest = now + Duration::minutes(MAX_INGRESS_EXPIRY_MINUTES);
+        let mut next_valid = None;
+        for bundle in self.copies {
+            bundle.validate()?;
+            let expiries = bundle
+                .messages
+                .iter()
+                .map(|message| Ok(message.ingress.inspect()?.0))
+                .collect::<AnyhowResult<Vec<_>>>()?;
+            let (first, last) = match (expiries.iter().min(), expiries.iter().max()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => return Ok(bundle),
+            };
+            if first > now && last <= latest {
+                return Ok(bundle);
+            }
+            if first > now {
+                let valid_from = last - Duration::minutes(MAX_INGRESS_EXPIRY_MINUTES);
+                next_valid = Some(next_valid.map_or(valid_from, |time| valid_from.min(time)));
+            }
+        }
+        Err(QuillError::Validation(match next_valid {
+            Some(time) => format!("No copy of the messages can be sent before {}", time),
+            None => "All copies of the messages have expired".to_string(),
+        })
+        .into())
+    }
+}
+
+fn checksum(messages: &[BundledMessage]) -> AnyhowResult<String> {
+    Ok(hex::encode(sha256(to_canonical_json(messages)?.as_bytes())))
+}
+
+// The checksum of bundles signed by earlier versions, which hashed the JSON in
+// the order of the fields of the structs.
+fn legacy_checksum(messages: &[BundledMessage]) -> AnyhowResult<String> {
+    Ok(hex::encode(sha256(
+        serde_json::to_string(messages)?.as_bytes(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(index: usize, depends_on: Vec<usize>) -> BundledMessage {
+        BundledMessage {
+            index,
+            canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai".to_string(),
+            method_name: "send_dfx".to_string(),
+            depends_on,
+            ingress: Ingress {
+                call_type: "update".to_string(),
+                request_id: Some("ab".repeat(32)),
+                content: "d9d9f7a0".to_string(),
+            },
+            request_status: Some(RequestStatus {
+                canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai".to_string(),
+                request_id: "ab".repeat(32),
+                content: "d9d9f7a1".to_string(),
+            }),
+        }
+    }
+
+    fn bundle() -> Bundle {
+        let messages = vec![
+            message(0, Vec::new()),
+            BundledMessage {
+                request_status: None,
+                ..message(1, vec![0])
+            },
+        ];
+        Bundle {
+            version: BUNDLE_VERSION,
+            checksum: checksum(&messages).unwrap(),
+            messages,
+        }
+    }
+
+    #[test]
+    fn bundle_roundtrip() {
+        let bundle = bundle();
+        let json = to_canonical_json(&bundle).unwrap();
+        assert_eq!(serde_json::from_str::<Bundle>(&json).unwrap(), bundle);
+        assert_eq!(Bundle::from_json(&json).unwrap(), bundle);
+        for message in &bundle.messages {
+            let json = to_canonical_json(message).unwrap();
+            assert_eq!(
+                &serde_json::from_str::<BundledMessage>(&json).unwrap(),
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn copies_roundtrip() {
+        let copies = Copies::new(vec![bundle(), bundle()]);
+        let json = to_canonical_json(&copies).unwrap();
+        assert_eq!(serde_json::from_str::<Copies>(&json).unwrap(), copies);
+    }
+
+    #[test]
+    fn checksum_of_earlier_versions() {
+        let mut bundle = bundle();
+        bundle.checksum = legacy_checksum(&bundle.messages).unwrap();
+        assert!(bundle.validate().is_ok());
+        bundle.checksum = "00".repeat(32);
+        assert!(bundle.validate().is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Checks of the certificates in the responses to `read_state` requests. The
+//! agent verifies the signatures of a certificate and of its delegation with
+//! the root key; these checks make sure that a correctly signed certificate
+//! is also recent and issued by the subnet of the canister.
+
+use crate::AnyhowResult;
+use anyhow::anyhow;
+use ic_types::Principal;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// The maximum difference between the time of a certificate and the local time.
+const MAX_CERTIFICATE_TIME_OFFSET: Duration = Duration::from_secs(5 * 60);
+
+/// Checks the time of the certificate in a `read_state` response and, if it
+/// is delegated to a subnet, that the subnet's canister ranges contain the
+/// canister.
+pub fn check(response: &[u8], canister_id: Principal) -> AnyhowResult {
+    let response: Value = serde_cbor::from_slice(response)
+        .map_err(|_| anyhow!("Invalid cbor data in the read_state response"))?;
+    let certificate = match field(&response, "certificate") {
+        Some(Value::Bytes(certificate)) => parse(certificate)?,
+        _ => return Err(anyhow!("The read_state response has no certificate")),
+    };
+    let tree = field(&certificate, "tree").ok_or_else(|| anyhow!("The certificate has no tree"))?;
+    check_time(tree)?;
+
+    if let Some(delegation) = field(&certificate, "delegation") {
+        let (subnet_id, delegation_certificate) = match (
+            field(delegation, "subnet_id"),
+            field(delegation, "certificate"),
+        ) {
+            (Some(Value::Bytes(subnet_id)), Some(Value::Bytes(certificate))) => {
+                (subnet_id, parse(certificate)?)
+            }
+            _ => return Err(anyhow!("Invalid delegation in the certificate")),
+        };
+        if field(&delegation_certificate, "delegation").is_some() {
+            return Err(anyhow!(
+                "The delegation of the certificate is delegated again"
+            ));
+        }
+        let tree = field(&delegation_certificate, "tree")
+            .ok_or_else(|| anyhow!("The delegation certificate has no tree"))?;
+        let ranges = lookup(
+            tree,
+            &[
+                &b"subnet"[..],
+                subnet_id.as_slice(),
+                &b"canister_ranges"[..],
+            ],
+        )
+        .ok_or_else(|| anyhow!("The delegation certificate has no canister ranges"))?;
+        let ranges = match serde_cbor::from_slice(ranges) {
+            Ok(Value::Array(ranges)) => ranges,
+            _ => {
+                return Err(anyhow!(
+                    "Invalid canister ranges in the delegation certificate"
+                ))
+            }
+        };
+        let canister = canister_id.as_slice();
+        let contains = |range: &Value| match range {
+            Value::Array(bounds) => match bounds.as_slice() {
+                [Value::Bytes(low), Value::Bytes(high)] => {
+                    low.as_slice() <= canister && canister <= high.as_slice()
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if !ranges.iter().any(contains) {
+            return Err(anyhow!(
+                "The certificate was issued by the subnet {}, which doesn't host the canister {}",
+                Principal::try_from(subnet_id.as_slice())
+                    .map_or_else(|_| hex::encode(subnet_id), |subnet_id| subnet_id.to_text()),
+                canister_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the certificate of a `read_state` response, which the IC signed.
+pub fn certificate(response: &[u8]) -> Option<Vec<u8>> {
+    match field(&serde_cbor::from_slice(response).ok()?, "certificate") {
+        Some(Value::Bytes(certificate)) => Some(certificate.clone()),
+        _ => None,
+    }
+}
+
+fn parse(certificate: &[u8]) -> AnyhowResult<Value> {
+    serde_cbor::from_slice(certificate).map_err(|_| anyhow!("Invalid cbor data in the certificate"))
+}
+
+fn check_time(tree: &Value) -> AnyhowResult {
+    let time = lookup(tree, &[&b"time"[..]])
+        .and_then(leb128)
+        .ok_or_else(|| anyhow!("The certificate has no valid time"))?;
+    let time = UNIX_EPOCH + Duration::from_nanos(time);
+    let now = SystemTime::now();
+    let offset = now
+        .duration_since(time)
+        .or_else(|_| time.duration_since(now))
+        .unwrap_or_default();
+    if offset > MAX_CERTIFICATE_TIME_OFFSET {
+        return Err(anyhow!(
+            "The time of the certificate differs from the local time by {} seconds",
+            offset.as_secs()
+        ));
+    }
+    Ok(())
+}
+
+// Returns the value of a field of a CBOR map.
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    match value {
+        Value::Map(m) => m.get(&Value::Text(name.to_string())),
+        _ => None,
+    }
+}
+
+// Returns the leaf at the path of a hash tree, which is encoded as arrays
+// tagged with 0 (empty), 1 (fork), 2 (labeled), 3 (leaf) or 4 (pruned).
+fn lookup<'a>(tree: &'a Value, path: &[&[u8]]) -> Option<&'a [u8]> {
+    let items = match tree {
+        Value::Array(items) => items,
+        _ => return None,
+    };
+    match (items.first(), path.split_first()) {
+        (Some(Value::Integer(1)), _) => {
+            lookup(items.get(1)?, path).or_else(|| lookup(items.get(2)?, path))
+        }
+        (Some(Value::Integer(2)), Some((label, rest))) => match items.get(1)? {
+            Value::Bytes(l) if l.as_slice() == *label => lookup(items.get(2)?, rest),
+            _ => None,
+        },
+        (Some(Value::Integer(3)), None) => match items.get(1)? {
+            Value::Bytes(leaf) => Some(leaf),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Decodes an unsigned LEB128 number, e.g. the time of a certificate.
+pub fn leb128(bytes: &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
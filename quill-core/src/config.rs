@@ -0,0 +1,213 @@
+//! The quill configuration, stored as `config.toml` in the config directory.
+
+use crate::{error::QuillError, get_config_dir, AnyhowResult};
+use anyhow::{anyhow, Context};
+use ic_types::Principal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct Config {
+    /// The principal controlling the neurons spawned from maturity.
+    pub maturity_destination: Option<String>,
+    /// The defaults for the options of all commands.
+    #[serde(default, skip_serializing_if = "Defaults::is_empty")]
+    pub defaults: Defaults,
+    /// Named sets of defaults, selected with `--profile`, which replace the
+    /// corresponding general defaults.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Defaults>,
+    /// Named networks, selected with `--network`, besides the built-in
+    /// `mainnet` and `local`, which they can replace.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub networks: BTreeMap<String, Network>,
+}
+
+/// The endpoints of an IC network and how their root key is trusted.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Network {
+    /// The URL of the network, or a comma-separated list of endpoints.
+    pub url: String,
+    /// Trusts the root key reported by the network, as for a local replica.
+    /// Refused for the endpoints of the mainnet.
+    #[serde(default)]
+    pub fetch_root_key: bool,
+}
+
+// The networks which can be selected without configuring them.
+fn builtin_network(name: &str) -> Option<Network> {
+    let (url, fetch_root_key) = match name {
+        "mainnet" => (crate::IC_URL, false),
+        "local" => ("http://127.0.0.1:4943", true),
+        _ => return None,
+    };
+    Some(Network {
+        url: url.to_string(),
+        fetch_root_key,
+    })
+}
+
+/// Defaults for options which would otherwise have to be passed every time.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Defaults {
+    pub pem_file: Option<String>,
+    /// The URL of the IC replica messages are sent to.
+    pub url: Option<String>,
+    /// The network messages are sent to, instead of the URL.
+    pub network: Option<String>,
+    /// The fee of ICP transfers, e.g. "0.0001".
+    pub fee: Option<String>,
+    /// The number of seconds after which signed messages expire, at most 300.
+    pub expiry: Option<u64>,
+    /// How signed messages are carried to the online machine, e.g.
+    /// "QR + manual retyping".
+    pub handoff: Option<String>,
+    /// The estimated number of seconds the handoff takes; signing warns when
+    /// the messages would expire sooner.
+    pub handoff_secs: Option<u64>,
+    pub json: Option<bool>,
+    pub compact: Option<bool>,
+}
+
+impl Defaults {
+    fn is_empty(&self) -> bool {
+        self.pem_file.is_none()
+            && self.url.is_none()
+            && self.network.is_none()
+            && self.fee.is_none()
+            && self.expiry.is_none()
+            && self.handoff.is_none()
+            && self.handoff_secs.is_none()
+            && self.json.is_none()
+            && self.compact.is_none()
+    }
+
+    /// Returns the defaults set by the `QUILL_*` environment variables, e.g.
+    /// `QUILL_PEM_FILE` or `QUILL_JSON`. `IC_URL` is accepted for the URL too.
+    pub fn from_env() -> AnyhowResult<Self> {
+        Ok(Self {
+            pem_file: env_var("QUILL_PEM_FILE"),
+            url: env_var("QUILL_URL").or_else(|| env_var("IC_URL")),
+            network: env_var("QUILL_NETWORK"),
+            fee: env_var("QUILL_FEE"),
+            expiry: env_var("QUILL_EXPIRY")
+                .map(|expiry| {
+                    expiry.parse().map_err(|_| {
+                        QuillError::Validation(format!("Invalid QUILL_EXPIRY: {}", expiry))
+                    })
+                })
+                .transpose()?,
+            handoff: None,
+            handoff_secs: None,
+            json: env_flag("QUILL_JSON")?,
+            compact: env_flag("QUILL_COMPACT")?,
+        })
+    }
+
+    /// Returns these defaults with the values set in `other` replaced. A URL
+    /// and a network replace each other.
+    pub fn merge(self, other: Defaults) -> Self {
+        let (url, network) = if other.url.is_some() || other.network.is_some() {
+            (other.url, other.network)
+        } else {
+            (self.url, self.network)
+        };
+        Self {
+            pem_file: other.pem_file.or(self.pem_file),
+            url,
+            network,
+            fee: other.fee.or(self.fee),
+            expiry: other.expiry.or(self.expiry),
+            handoff: other.handoff.or(self.handoff),
+            handoff_secs: other.handoff_secs.or(self.handoff_secs),
+            json: other.json.or(self.json),
+            compact: other.compact.or(self.compact),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration; a missing file yields the default configuration.
+    pub fn load() -> AnyhowResult<Self> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Couldn't parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> AnyhowResult {
+        let path = config_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)
+            .with_context(|| format!("Couldn't write {}", path.display()))
+    }
+
+    /// Returns the general defaults, replaced by those of the given profile.
+    pub fn defaults(&self, profile: Option<&str>) -> AnyhowResult<Defaults> {
+        let defaults = self.defaults.clone();
+        match profile {
+            None => Ok(defaults),
+            Some(name) => {
+                let profile = self.profiles.get(name).ok_or_else(|| {
+                    QuillError::Validation(format!("Unknown profile in the config: {}", name))
+                })?;
+                Ok(defaults.merge(profile.clone()))
+            }
+        }
+    }
+
+    /// Returns the network of the given name from the config or the built-in
+    /// networks.
+    pub fn network(&self, name: &str) -> AnyhowResult<Network> {
+        self.networks
+            .get(name)
+            .cloned()
+            .or_else(|| builtin_network(name))
+            .ok_or_else(|| {
+                QuillError::Validation(format!(
+                    "Unknown network {}, use mainnet, local or a network of the config",
+                    name
+                ))
+                .into()
+            })
+    }
+
+    pub fn maturity_destination(&self) -> AnyhowResult<Option<Principal>> {
+        self.maturity_destination
+            .as_ref()
+            .map(|p| {
+                Principal::from_text(p)
+                    .map_err(|err| anyhow!("Invalid maturity destination in the config: {}", err))
+            })
+            .transpose()
+    }
+}
+
+// Returns the value of an environment variable, treating an empty one as unset.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_flag(name: &str) -> AnyhowResult<Option<bool>> {
+    env_var(name)
+        .map(|value| match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" => Ok(true),
+            "0" | "false" | "no" => Ok(false),
+            _ => Err(QuillError::Validation(format!("Invalid {}: {}", name, value)).into()),
+        })
+        .transpose()
+}
+
+fn config_path() -> AnyhowResult<PathBuf> {
+    Ok(get_config_dir()?.join(CONFIG_FILE))
+}
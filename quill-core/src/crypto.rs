@@ -0,0 +1,254 @@
+//! The cryptography of messages: SHA-256, the verification of signatures and
+//! the keys of PEM files.
+//!
+//! Signatures are verified with OpenSSL by default, or in pure Rust with the
+//! `pure-rust` feature, which also signs without OpenSSL and takes precedence.
+//! Builds with neither, e.g. for a browser, can build and hash messages but
+//! not verify them. The `mnemonic` feature derives keys from seed phrases.
+
+#[cfg(feature = "mnemonic")]
+use crate::error::QuillError;
+use crate::AnyhowResult;
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+
+// The DER encoding of an Ed25519 public key, up to the 32 bytes of the key.
+#[cfg(feature = "pure-rust")]
+const ED25519_DER_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+// The DER encoding of a secp256k1 public key, up to the 65 bytes of the
+// uncompressed point.
+#[cfg(feature = "pure-rust")]
+const SECP256K1_DER_PREFIX: &[u8] = &[
+    0x30, 0x56, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b,
+    0x81, 0x04, 0x00, 0x0a, 0x03, 0x42, 0x00,
+];
+
+// The BIP32 derivation path of the key of a seed phrase, as in the wallets of
+// the IC (coin type 223).
+#[cfg(feature = "mnemonic")]
+const MNEMONIC_DERIVATION_PATH: &str = "m/44'/223'/0'/0/0";
+
+// The DER encoding of a secp256k1 private key in the SEC1 format, up to the 32
+// bytes of the key, and from them to the 65 bytes of the public key.
+#[cfg(feature = "mnemonic")]
+const SEC1_SECP256K1_PREFIX: &[u8] = &[0x30, 0x74, 0x02, 0x01, 0x01, 0x04, 0x20];
+#[cfg(feature = "mnemonic")]
+const SEC1_SECP256K1_INFIX: &[u8] = &[
+    0xa0, 0x07, 0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a, 0xa1, 0x44, 0x03, 0x42, 0x00,
+];
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Verifies an Ed25519 or a secp256k1 signature, the key types of PEM files.
+#[cfg(feature = "pure-rust")]
+pub fn verify_signature(public_key: &[u8], signature: &[u8], message: &[u8]) -> AnyhowResult<bool> {
+    use k256::ecdsa::signature::Verifier;
+    use std::convert::TryFrom;
+
+    if let Some(key) = public_key.strip_prefix(ED25519_DER_PREFIX) {
+        let key = ed25519_dalek::PublicKey::from_bytes(key)
+            .map_err(|_| anyhow!("Invalid Ed25519 public key"))?;
+        Ok(match ed25519_dalek::Signature::try_from(signature) {
+            Ok(signature) => key.verify(message, &signature).is_ok(),
+            Err(_) => false,
+        })
+    } else if let Some(key) = public_key.strip_prefix(SECP256K1_DER_PREFIX) {
+        let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+            .map_err(|_| anyhow!("Invalid secp256k1 public key"))?;
+        Ok(match k256::ecdsa::Signature::try_from(signature) {
+            // Signatures with a high s are valid too, as with OpenSSL.
+            Ok(signature) => key
+                .verify(message, &signature.normalize_s().unwrap_or(signature))
+                .is_ok(),
+            Err(_) => false,
+        })
+    } else {
+        Err(anyhow!("Unsupported type of public key"))
+    }
+}
+
+/// Verifies an Ed25519 or a secp256k1 signature, the key types of PEM files.
+#[cfg(all(feature = "openssl", not(feature = "pure-rust")))]
+pub fn verify_signature(public_key: &[u8], signature: &[u8], message: &[u8]) -> AnyhowResult<bool> {
+    use openssl::{
+        bn::BigNum,
+        ecdsa::EcdsaSig,
+        pkey::{Id, PKey},
+        sign::Verifier,
+    };
+
+    let key = PKey::public_key_from_der(public_key)?;
+    match key.id() {
+        Id::ED25519 => Ok(Verifier::new_without_digest(&key)?.verify_oneshot(signature, message)?),
+        Id::EC if signature.len() == 64 => {
+            let signature = EcdsaSig::from_private_components(
+                BigNum::from_slice(&signature[..32])?,
+                BigNum::from_slice(&signature[32..])?,
+            )?;
+            let key = key.ec_key()?;
+            Ok(signature.verify(&sha256(message), &key)?)
+        }
+        Id::EC => Ok(false),
+        _ => Err(anyhow!("Unsupported type of public key")),
+    }
+}
+
+/// Fails, since signatures can only be verified with the `openssl` or the
+/// `pure-rust` feature.
+#[cfg(not(any(feature = "openssl", feature = "pure-rust")))]
+pub fn verify_signature(_: &[u8], _: &[u8], _: &[u8]) -> AnyhowResult<bool> {
+    Err(anyhow!(
+        "Signatures can't be verified without the openssl or pure-rust feature of quill-core"
+    ))
+}
+
+/// Returns the 64 bytes of r and s of a DER-encoded ECDSA signature, as HSMs
+/// often return them, or `None` if it isn't one.
+pub fn ecdsa_signature_from_der(der: &[u8]) -> Option<Vec<u8>> {
+    // A sequence of the two integers, whose lengths always fit in one byte.
+    match der {
+        [0x30, len, rest @ ..] if *len as usize == rest.len() => {
+            let (r, rest) = der_integer(rest)?;
+            let (s, rest) = der_integer(rest)?;
+            match rest {
+                [] => Some([r, s].concat()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Returns a DER-encoded integer of at most 32 bytes, left-padded to 32 bytes,
+// and the bytes after it.
+fn der_integer(der: &[u8]) -> Option<([u8; 32], &[u8])> {
+    let (len, rest) = match der {
+        [0x02, len, rest @ ..] => (*len as usize, rest),
+        _ => return None,
+    };
+    let value = rest.get(..len)?;
+    // A leading zero only keeps the integer positive.
+    let value = value.strip_prefix(&[0]).unwrap_or(value);
+    if value.len() > 32 {
+        return None;
+    }
+    let mut padded = [0; 32];
+    padded[32 - value.len()..].copy_from_slice(value);
+    Some((padded, &rest[len..]))
+}
+
+/// The key of a PEM file, which signs without OpenSSL.
+#[cfg(feature = "pure-rust")]
+pub enum SigningKey {
+    /// A secp256k1 key, signing with deterministic nonces (RFC 6979).
+    Secp256k1(k256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::Keypair),
+}
+
+#[cfg(feature = "pure-rust")]
+impl SigningKey {
+    /// Loads a secp256k1 key in the SEC1 format of `openssl ecparam`, or an
+    /// Ed25519 key in the PKCS#8 format of dfx.
+    pub fn from_pem(pem: &str) -> Option<Self> {
+        for block in pem::parse_many(pem).ok()? {
+            match block.tag.as_str() {
+                "EC PRIVATE KEY" => {
+                    let key = k256::SecretKey::from_sec1_der(&block.contents).ok()?;
+                    return Some(SigningKey::Secp256k1(key.into()));
+                }
+                "PRIVATE KEY" => return ed25519_keypair(&block.contents).map(SigningKey::Ed25519),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Returns the DER-encoded public key, from which the principal is derived.
+    pub fn public_key(&self) -> Vec<u8> {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        match self {
+            SigningKey::Secp256k1(key) => [
+                SECP256K1_DER_PREFIX,
+                key.verifying_key().to_encoded_point(false).as_bytes(),
+            ]
+            .concat(),
+            SigningKey::Ed25519(keypair) => {
+                [ED25519_DER_PREFIX, keypair.public.as_bytes()].concat()
+            }
+        }
+    }
+
+    /// Signs the blob, returning r and s for secp256k1 as the IC expects them.
+    pub fn sign(&self, blob: &[u8]) -> Vec<u8> {
+        use k256::ecdsa::signature::Signer;
+
+        match self {
+            SigningKey::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature = key.sign(blob);
+                signature.as_ref().to_vec()
+            }
+            SigningKey::Ed25519(keypair) => keypair.sign(blob).to_bytes().to_vec(),
+        }
+    }
+}
+
+// Returns the Ed25519 key pair of a PKCS#8 document, whose private key is an
+// octet string of the 32-byte seed nested in another one.
+#[cfg(feature = "pure-rust")]
+fn ed25519_keypair(der: &[u8]) -> Option<ed25519_dalek::Keypair> {
+    const ED25519_OID: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+    const SEED_PREFIX: &[u8] = &[0x04, 0x22, 0x04, 0x20];
+    let oid = der
+        .windows(ED25519_OID.len())
+        .position(|w| w == ED25519_OID)?;
+    let seed = der[oid..]
+        .windows(SEED_PREFIX.len())
+        .position(|w| w == SEED_PREFIX)
+        .and_then(|start| der.get(oid + start + SEED_PREFIX.len()..)?.get(..32))?;
+    let secret = ed25519_dalek::SecretKey::from_bytes(seed).ok()?;
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    Some(ed25519_dalek::Keypair { secret, public })
+}
+
+/// Returns the PEM file of the secp256k1 key which a BIP39 seed phrase derives
+/// at m/44'/223'/0'/0/0, so that it can be used like any other PEM file.
+#[cfg(feature = "mnemonic")]
+pub fn mnemonic_to_pem(phrase: &str) -> AnyhowResult<String> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = bip39::Mnemonic::from_phrase(&phrase, bip39::Language::English)
+        .map_err(|err| QuillError::Identity(format!("Invalid seed phrase: {}", err)))?;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    let path = MNEMONIC_DERIVATION_PATH
+        .parse()
+        .map_err(|err| anyhow!("Invalid derivation path: {}", err))?;
+    let seed = bip39::Seed::new(&mnemonic, "");
+    let key = bip32::XPrv::derive_from_path(seed.as_bytes(), &path)
+        .map_err(|err| anyhow!("Couldn't derive the key of the seed phrase: {}", err))?;
+    let secret = k256::SecretKey::from(key.private_key());
+    let public = secret.public_key().to_encoded_point(false);
+    // The curve is included, which OpenSSL needs to load the key.
+    let der = [
+        SEC1_SECP256K1_PREFIX,
+        &secret.to_be_bytes(),
+        SEC1_SECP256K1_INFIX,
+        public.as_bytes(),
+    ]
+    .concat();
+    let pem = pem::Pem {
+        tag: "EC PRIVATE KEY".to_string(),
+        contents: der,
+    };
+    Ok(pem::encode_config(
+        &pem,
+        pem::EncodeConfig {
+            line_ending: pem::LineEnding::LF,
+        },
+    ))
+}
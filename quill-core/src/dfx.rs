@@ -0,0 +1,103 @@
+//! The message files of `dfx canister sign` and `dfx canister send`, which
+//! hold one signed message each, so that messages can be exchanged with dfx.
+
+use crate::{
+    envelope,
+    error::QuillError,
+    sign::signed_message::{Ingress, RequestStatus},
+    AnyhowResult,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// The only version of the format of dfx.
+pub const DFX_MESSAGE_VERSION: usize = 1;
+
+/// A message file of dfx. Unlike quill's messages, it repeats the fields of
+/// the envelope in plain text and holds the arguments as raw bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DfxMessage {
+    pub version: usize,
+    pub creation: String,
+    pub expiration: String,
+    pub network: String,
+    pub call_type: String,
+    pub sender: String,
+    pub canister_id: String,
+    pub method_name: String,
+    pub arg: Vec<u8>,
+    pub request_id: Option<String>,
+    pub content: String,
+    pub signed_request_status: Option<String>,
+}
+
+impl DfxMessage {
+    /// Converts a message of quill, the plain text fields are taken from its
+    /// envelope. dfx sends it to `network`; the time of the conversion is
+    /// recorded as its creation.
+    pub fn from_quill(
+        ingress: &Ingress,
+        request_status: Option<&RequestStatus>,
+        network: &str,
+    ) -> AnyhowResult<Self> {
+        let (expiration, sender, canister_id, method_name, _) = ingress.inspect()?;
+        let arg = envelope::arg(&envelope::content(&hex::decode(&ingress.content)?)?)
+            .ok_or_else(|| QuillError::Validation("The message has no arguments".to_string()))?;
+        Ok(Self {
+            version: DFX_MESSAGE_VERSION,
+            creation: Utc::now().to_rfc3339(),
+            expiration: expiration.to_rfc3339(),
+            network: network.to_string(),
+            call_type: ingress.call_type.clone(),
+            sender: sender.to_text(),
+            canister_id: canister_id.to_text(),
+            method_name,
+            arg,
+            request_id: ingress.request_id.clone(),
+            content: ingress.content.clone(),
+            signed_request_status: request_status.map(|status| status.content.clone()),
+        })
+    }
+
+    /// Converts the message to quill's, after checking that the plain text
+    /// fields match the envelope which is actually sent.
+    pub fn to_quill(&self) -> AnyhowResult<(Ingress, Option<RequestStatus>)> {
+        if self.version != DFX_MESSAGE_VERSION {
+            return Err(QuillError::Validation(format!(
+                "Unsupported version {} of the dfx message",
+                self.version
+            ))
+            .into());
+        }
+        let ingress = Ingress {
+            call_type: self.call_type.clone(),
+            request_id: self.request_id.clone(),
+            content: self.content.clone(),
+        };
+        let (_, sender, canister_id, method_name, _) = ingress.inspect()?;
+        if sender.to_text() != self.sender
+            || canister_id.to_text() != self.canister_id
+            || method_name != self.method_name
+        {
+            return Err(QuillError::Validation(
+                "The fields of the dfx message don't match its envelope".to_string(),
+            )
+            .into());
+        }
+        let request_status = match (&self.signed_request_status, &self.request_id) {
+            (Some(content), Some(request_id)) => Some(RequestStatus {
+                canister_id: self.canister_id.clone(),
+                request_id: request_id.clone(),
+                content: content.clone(),
+            }),
+            (Some(_), None) => {
+                return Err(QuillError::Validation(
+                    "The dfx message has a request status without a request id".to_string(),
+                )
+                .into())
+            }
+            (None, _) => None,
+        };
+        Ok((ingress, request_status))
+    }
+}
@@ -0,0 +1,301 @@
+//! Raw CBOR envelopes, the exact bytes submitted to the replica, and the
+//! request ids of their content.
+
+use crate::{
+    crypto::{sha256, verify_signature},
+    error::QuillError,
+    request_id::RequestId,
+    sign::signed_message::Ingress,
+    AnyhowResult, Identity,
+};
+use anyhow::anyhow;
+use chrono::{DateTime, TimeZone, Utc};
+use ic_types::Principal;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+// The self-describing CBOR tag which the agent puts in front of every envelope.
+const SELF_DESCRIBE_TAG: &[u8] = &[0xd9, 0xd9, 0xf7];
+
+// The domain separator prepended to the request id before signing.
+const IC_REQUEST_DOMAIN_SEPARATOR: &[u8] = b"\x0Aic-request";
+
+/// The result of checking an envelope offline.
+pub struct Verification {
+    pub request_id: RequestId,
+    pub sender: Principal,
+    pub ingress_expiry: DateTime<Utc>,
+    /// Whether the signature was made for the content by the key of the
+    /// sender, or `None` if the envelope isn't signed.
+    pub signature_valid: Option<bool>,
+}
+
+/// Returns whether the data is a CBOR envelope rather than a JSON message.
+pub fn is_cbor(data: &[u8]) -> bool {
+    data.starts_with(SELF_DESCRIBE_TAG)
+}
+
+/// Returns the content map of an envelope.
+pub fn content(envelope: &[u8]) -> AnyhowResult<Value> {
+    let cbor: Value = serde_cbor::from_slice(envelope)
+        .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+    match cbor {
+        Value::Map(mut m) => m
+            .remove(&Value::Text("content".to_string()))
+            .ok_or_else(|| anyhow!("Invalid cbor content")),
+        _ => Err(anyhow!("Invalid cbor content")),
+    }
+}
+
+/// Computes the request id of the content of an envelope, which is the
+/// representation-independent hash of the content map.
+pub fn request_id(content: &Value) -> AnyhowResult<RequestId> {
+    Ok(RequestId::new(hash(content)?))
+}
+
+/// Returns the bytes which the sender signs for a request: the domain separator
+/// followed by the request id.
+pub fn message_to_sign(request_id: &RequestId) -> AnyhowResult<Vec<u8>> {
+    let mut message = IC_REQUEST_DOMAIN_SEPARATOR.to_vec();
+    message.extend_from_slice(request_id.as_slice());
+    Ok(message)
+}
+
+/// Builds an envelope from its content, the DER-encoded public key of the
+/// sender and its signature of the request id.
+pub fn signed_envelope(
+    content: &Value,
+    public_key: &[u8],
+    signature: &[u8],
+) -> AnyhowResult<Vec<u8>> {
+    build_envelope(content, Some(public_key), Some(signature))
+}
+
+/// Signs the content with the identity, like the agent does, and returns the
+/// envelope. Without an identity the envelope is anonymous; an
+/// [`Identity::External`] leaves it unsigned.
+pub fn sign_content(content: &Value, identity: Option<&Identity>) -> AnyhowResult<Vec<u8>> {
+    let identity = match identity {
+        Some(identity) => identity,
+        None => return build_envelope(content, None, None),
+    };
+    let message = message_to_sign(&request_id(content)?)?;
+    let (public_key, signature) = identity.sign_blob(&message)?;
+    build_envelope(content, Some(&public_key), signature.as_deref())
+}
+
+fn build_envelope(
+    content: &Value,
+    public_key: Option<&[u8]>,
+    signature: Option<&[u8]>,
+) -> AnyhowResult<Vec<u8>> {
+    let mut envelope = std::collections::BTreeMap::new();
+    envelope.insert(Value::Text("content".to_string()), content.clone());
+    if let Some(public_key) = public_key {
+        envelope.insert(
+            Value::Text("sender_pubkey".to_string()),
+            Value::Bytes(public_key.to_vec()),
+        );
+    }
+    if let Some(signature) = signature {
+        envelope.insert(
+            Value::Text("sender_sig".to_string()),
+            Value::Bytes(signature.to_vec()),
+        );
+    }
+    let mut bytes = SELF_DESCRIBE_TAG.to_vec();
+    bytes.extend(serde_cbor::to_vec(&Value::Map(envelope))?);
+    Ok(bytes)
+}
+
+fn hash(value: &Value) -> AnyhowResult<[u8; 32]> {
+    Ok(match value {
+        Value::Text(text) => sha256(text.as_bytes()),
+        Value::Bytes(bytes) => sha256(bytes),
+        Value::Integer(n) if *n >= 0 => sha256(&leb128(*n as u128)),
+        Value::Array(values) => {
+            let mut data = Vec::new();
+            for value in values {
+                data.extend_from_slice(&hash(value)?);
+            }
+            sha256(&data)
+        }
+        Value::Map(map) => {
+            let mut fields = Vec::new();
+            for (key, value) in map {
+                let mut field = hash(key)?.to_vec();
+                field.extend_from_slice(&hash(value)?);
+                fields.push(field);
+            }
+            fields.sort();
+            sha256(&fields.concat())
+        }
+        Value::Tag(_, value) => hash(value)?,
+        _ => return Err(anyhow!("Unsupported value in the envelope content")),
+    })
+}
+
+fn leb128(mut n: u128) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Returns the argument of the call in the content of an envelope.
+pub fn arg(content: &Value) -> Option<Vec<u8>> {
+    match content {
+        Value::Map(m) => match m.get(&Value::Text("arg".to_string())) {
+            Some(Value::Bytes(arg)) => Some(arg.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the public key of the signer of an envelope, if it's signed.
+pub fn sender_pubkey(envelope: &[u8]) -> AnyhowResult<Option<Vec<u8>>> {
+    let cbor: Value = serde_cbor::from_slice(envelope)
+        .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+    Ok(match cbor {
+        Value::Map(m) => match m.get(&Value::Text("sender_pubkey".to_string())) {
+            Some(Value::Bytes(public_key)) => Some(public_key.clone()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Wraps an envelope into a message, taking the call type from its content.
+pub fn to_ingress(envelope: &[u8]) -> AnyhowResult<Ingress> {
+    let content = content(envelope)?;
+    let request_type = match &content {
+        Value::Map(m) => m.get(&Value::Text("request_type".to_string())),
+        _ => None,
+    };
+    let call_type = match request_type {
+        Some(Value::Text(t)) if t == "call" => "update",
+        Some(Value::Text(t)) if t == "query" => "query",
+        _ => return Err(anyhow!("Only call and query envelopes can be sent")),
+    };
+    let ingress = Ingress::default()
+        .with_call_type(call_type.to_string())
+        .with_content(hex::encode(envelope));
+    Ok(if call_type == "update" {
+        ingress.with_request_id(request_id(&content)?)
+    } else {
+        ingress
+    })
+}
+
+/// Recomputes the request id of an envelope and checks its signature against
+/// the public key it carries.
+pub fn verify(envelope: &[u8]) -> AnyhowResult<Verification> {
+    let cbor: Value = serde_cbor::from_slice(envelope)
+        .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+    let envelope = match cbor {
+        Value::Map(m) => m,
+        _ => return Err(anyhow!("Invalid cbor content")),
+    };
+    let field = |map: &std::collections::BTreeMap<Value, Value>, name: &str| {
+        map.get(&Value::Text(name.to_string())).cloned()
+    };
+    let content = field(&envelope, "content").ok_or_else(|| anyhow!("Invalid cbor content"))?;
+    let hash = hash(&content)?;
+    let (sender, ingress_expiry) = match &content {
+        Value::Map(m) => match (field(m, "sender"), field(m, "ingress_expiry")) {
+            (Some(Value::Bytes(sender)), Some(Value::Integer(expiry))) => (
+                Principal::try_from(sender)?,
+                Utc.timestamp((expiry / 1_000_000_000) as i64, 0),
+            ),
+            _ => return Err(anyhow!("Invalid cbor content")),
+        },
+        _ => return Err(anyhow!("Invalid cbor content")),
+    };
+    let signature_valid = match (
+        field(&envelope, "sender_pubkey"),
+        field(&envelope, "sender_sig"),
+    ) {
+        (Some(Value::Bytes(public_key)), Some(Value::Bytes(signature))) => {
+            let mut message = IC_REQUEST_DOMAIN_SEPARATOR.to_vec();
+            message.extend_from_slice(&hash);
+            Some(
+                Principal::self_authenticating(&public_key) == sender
+                    && verify_signature(&public_key, &signature, &message)?,
+            )
+        }
+        _ => None,
+    };
+    Ok(Verification {
+        request_id: RequestId::new(hash),
+        sender,
+        ingress_expiry,
+        signature_valid,
+    })
+}
+
+/// An unsigned call, as the NNS dapp passes it to a hardware wallet for signing.
+pub struct CallPayload {
+    /// `update` or `query`, like the call types of messages.
+    pub call_type: String,
+    pub sender: Principal,
+    pub canister_id: Principal,
+    pub method_name: String,
+    pub arg: Vec<u8>,
+}
+
+/// Parses the CBOR payload which the hardware wallet flow of the NNS dapp
+/// signs: the content of a call, wrapped in a map as the `content` of an
+/// envelope, or on its own.
+pub fn call_payload(data: &[u8]) -> AnyhowResult<CallPayload> {
+    let invalid = || {
+        QuillError::Validation("Invalid payload, expected the CBOR content of a call".to_string())
+    };
+    let cbor: Value = serde_cbor::from_slice(data).map_err(|_| invalid())?;
+    let map = match cbor {
+        Value::Map(mut m) => match m.remove(&Value::Text("content".to_string())) {
+            Some(Value::Map(content)) => content,
+            Some(_) => return Err(invalid().into()),
+            None => m,
+        },
+        _ => return Err(invalid().into()),
+    };
+    let text = |name: &str| match map.get(&Value::Text(name.to_string())) {
+        Some(Value::Text(text)) => Ok(text.clone()),
+        _ => Err(QuillError::Validation(format!(
+            "The payload has no {}",
+            name
+        ))),
+    };
+    let bytes = |name: &str| match map.get(&Value::Text(name.to_string())) {
+        Some(Value::Bytes(bytes)) => Ok(bytes.clone()),
+        _ => Err(QuillError::Validation(format!(
+            "The payload has no {}",
+            name
+        ))),
+    };
+    let call_type = match text("request_type")?.as_str() {
+        "call" => "update",
+        "query" => "query",
+        other => {
+            return Err(QuillError::Validation(format!(
+                "Only calls and queries can be signed, the payload is a {} request",
+                other
+            ))
+            .into())
+        }
+    };
+    Ok(CallPayload {
+        call_type: call_type.to_string(),
+        sender: Principal::try_from(bytes("sender")?)?,
+        canister_id: Principal::try_from(bytes("canister_id")?)?,
+        method_name: text("method_name")?,
+        arg: bytes("arg")?,
+    })
+}
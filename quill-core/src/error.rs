@@ -0,0 +1,66 @@
+//! Error categories, which determine the exit code of quill.
+
+use std::fmt;
+
+const VALIDATION_EXIT_CODE: i32 = 2;
+const NETWORK_EXIT_CODE: i32 = 3;
+const CANDID_EXIT_CODE: i32 = 4;
+pub const IDENTITY_EXIT_CODE: i32 = 5;
+
+/// An error with a category, carried inside an `anyhow::Error`.
+#[derive(Debug)]
+pub enum QuillError {
+    /// The arguments or the message file are invalid.
+    Validation(String),
+    /// The request couldn't be submitted or was rejected.
+    Network(String),
+    /// A candid value couldn't be encoded or decoded.
+    Candid(String),
+    /// The identity couldn't be loaded from the PEM file.
+    Identity(String),
+}
+
+impl QuillError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            QuillError::Validation(_) => VALIDATION_EXIT_CODE,
+            QuillError::Network(_) => NETWORK_EXIT_CODE,
+            QuillError::Candid(_) => CANDID_EXIT_CODE,
+            QuillError::Identity(_) => IDENTITY_EXIT_CODE,
+        }
+    }
+}
+
+impl fmt::Display for QuillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuillError::Validation(msg)
+            | QuillError::Network(msg)
+            | QuillError::Candid(msg)
+            | QuillError::Identity(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for QuillError {}
+
+/// Returns the exit code for an error, derived from the first cause with a
+/// known category; uncategorized errors exit with 1.
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<QuillError>() {
+            return err.exit_code();
+        }
+        #[cfg(feature = "transport")]
+        if cause.is::<ic_agent::AgentError>() {
+            return NETWORK_EXIT_CODE;
+        }
+        if cause.is::<candid::Error>() {
+            return CANDID_EXIT_CODE;
+        }
+        if cause.is::<serde_json::Error>() || cause.is::<hex::FromHexError>() {
+            return VALIDATION_EXIT_CODE;
+        }
+    }
+    1
+}
@@ -0,0 +1,59 @@
+//! Word fingerprints of account ids and principals, which can be compared
+//! verbally, e.g. over the phone, instead of reading out 64 hex characters.
+
+use crate::{crypto::sha256, icrc1::Account};
+
+// The number of words of a fingerprint, each encoding one byte of the hash.
+const FINGERPRINT_WORDS: usize = 5;
+
+const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "adult", "agent", "alarm", "album", "alert", "alley", "amber",
+    "angle", "ankle", "apple", "april", "apron", "arena", "armor", "arrow", "atlas", "attic",
+    "autumn", "award", "bacon", "badge", "baker", "bamboo", "banjo", "barrel", "basil", "basket",
+    "beach", "beaver", "bell", "berry", "bingo", "bishop", "blade", "blanket", "bonus", "border",
+    "bottle", "boxer", "branch", "bread", "brick", "bridge", "broom", "bucket", "butter", "cabin",
+    "cactus", "camel", "camera", "candle", "canoe", "canyon", "carbon", "carpet", "castle",
+    "cattle", "cedar", "cello", "chalk", "cherry", "chess", "circus", "citrus", "clock", "cloud",
+    "clover", "cobra", "coconut", "comet", "copper", "coral", "cotton", "cowboy", "crane",
+    "crayon", "cricket", "crystal", "curtain", "cushion", "daisy", "dancer", "denim", "desert",
+    "diamond", "dinner", "doctor", "dollar", "dolphin", "donkey", "dragon", "dream", "drum",
+    "eagle", "echo", "eclipse", "elbow", "ember", "engine", "falcon", "farmer", "feather",
+    "fiddle", "finger", "flag", "flute", "forest", "fossil", "fountain", "fox", "galaxy", "garden",
+    "garlic", "ginger", "giraffe", "glacier", "globe", "gold", "gorilla", "grape", "gravel",
+    "guitar", "hammer", "harbor", "harp", "hazel", "helmet", "hockey", "honey", "hornet", "horse",
+    "hotel", "husky", "igloo", "indigo", "island", "ivory", "jacket", "jaguar", "jelly", "jigsaw",
+    "jockey", "jungle", "kayak", "kettle", "kiwi", "koala", "ladder", "lagoon", "lantern", "laser",
+    "lemon", "leopard", "lily", "lion", "lizard", "lobster", "locket", "magnet", "mango", "maple",
+    "marble", "meadow", "melon", "meteor", "mirror", "monkey", "moose", "motor", "muffin",
+    "napkin", "nectar", "needle", "nickel", "noodle", "nugget", "oasis", "ocean", "olive", "onion",
+    "orange", "orbit", "orchid", "otter", "oyster", "paddle", "palace", "panda", "panther",
+    "parrot", "peanut", "pebble", "pencil", "pepper", "piano", "pickle", "pilot", "pirate",
+    "planet", "plum", "pocket", "poem", "potato", "prism", "pumpkin", "puppet", "puzzle", "quartz",
+    "rabbit", "radar", "radio", "raven", "rocket", "ruby", "saddle", "salmon", "sandal", "satin",
+    "scarf", "shadow", "shark", "shovel", "silver", "sketch", "sleigh", "spider", "sponge",
+    "squid", "statue", "stereo", "sugar", "summit", "sunset", "tango", "teapot", "tiger", "tomato",
+    "tractor", "trumpet", "tulip", "tunnel", "turtle", "valley", "velvet", "violin", "volcano",
+    "wagon", "walnut", "walrus", "wizard", "yogurt", "zebra", "zipper",
+];
+
+/// Returns the fingerprint of the bytes of an account id or a principal, e.g.
+/// `dinner-boxer-plum-harp-nickel`.
+pub fn fingerprint(bytes: &[u8]) -> String {
+    sha256(bytes)[..FINGERPRINT_WORDS]
+        .iter()
+        .map(|byte| WORDS[*byte as usize])
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Returns the fingerprint of an ICRC-1 account, which is the fingerprint of
+/// its owner if it has the default subaccount.
+pub fn icrc1_fingerprint(account: &Account) -> String {
+    let mut bytes = account.owner.as_slice().to_vec();
+    if let Some(subaccount) = &account.subaccount {
+        if subaccount.iter().any(|byte| *byte != 0) {
+            bytes.extend_from_slice(subaccount);
+        }
+    }
+    fingerprint(&bytes)
+}
@@ -0,0 +1,170 @@
+//! Splitting of signed messages into a sequence of frames, each small enough
+//! for one QR code, which can be shown as an animated QR code and reassembled
+//! in any order on the online machine.
+//!
+//! A frame has the format `UR:QUILL/<index>-<count>/<checksum>/<fragment>`,
+//! where the checksum is the CRC32 of the whole message and the fragment is a
+//! part of the message in uppercase hex, so that the frames only use
+//! characters of the alphanumeric QR mode.
+
+use crate::AnyhowResult;
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+const PREFIX: &str = "UR:QUILL/";
+
+/// The default number of message bytes per frame, which fits in a QR code of
+/// version 20 with medium error correction.
+pub const DEFAULT_FRAGMENT_BYTES: usize = 300;
+
+/// The most frames of a message, far more than any message of quill needs, so
+/// that a garbled or hostile frame can't make the decoder wait for billions.
+pub const MAX_FRAMES: usize = 4096;
+
+// The most missing frames listed by the error of an incomplete message.
+const MAX_LISTED_MISSING: usize = 10;
+
+/// Returns whether the text starts with a frame.
+pub fn is_frame(text: &str) -> bool {
+    text.trim_start().starts_with(PREFIX)
+}
+
+/// Splits the data into frames of at most `fragment_bytes` bytes each.
+pub fn encode(data: &[u8], fragment_bytes: usize) -> Vec<String> {
+    let checksum = checksum(data);
+    let chunks: Vec<_> = data.chunks(fragment_bytes.max(1)).collect();
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            format!(
+                "{}{}-{}/{}/{}",
+                PREFIX,
+                i + 1,
+                chunks.len(),
+                checksum,
+                hex::encode_upper(chunk)
+            )
+        })
+        .collect()
+}
+
+/// Collects frames in any order, ignoring repeated frames, until the message
+/// is complete.
+#[derive(Default)]
+pub struct Decoder {
+    count: usize,
+    checksum: String,
+    fragments: BTreeMap<usize, Vec<u8>>,
+}
+
+impl Decoder {
+    /// Adds a frame; frames of a different message are rejected.
+    pub fn receive(&mut self, frame: &str) -> AnyhowResult {
+        let invalid = || anyhow!("Invalid frame: {}", frame);
+        let mut parts = frame
+            .trim()
+            .strip_prefix(PREFIX)
+            .ok_or_else(invalid)?
+            .split('/');
+        let (index, count) = parts
+            .next()
+            .and_then(|part| part.split_once('-'))
+            .ok_or_else(invalid)?;
+        let index = index.parse::<usize>().map_err(|_| invalid())?;
+        let count = count.parse::<usize>().map_err(|_| invalid())?;
+        let checksum = parts.next().ok_or_else(invalid)?;
+        let fragment = hex::decode(parts.next().ok_or_else(invalid)?).map_err(|_| invalid())?;
+        if parts.next().is_some() || fragment.is_empty() || index == 0 || index > count {
+            return Err(invalid());
+        }
+        if count > MAX_FRAMES {
+            return Err(anyhow!(
+                "The frame belongs to a message of {} frames, more than the maximum of {}: {}",
+                count,
+                MAX_FRAMES,
+                frame
+            ));
+        }
+        if self.fragments.is_empty() {
+            self.count = count;
+            self.checksum = checksum.to_string();
+        } else if self.count != count || self.checksum != checksum {
+            return Err(anyhow!("The frame belongs to another message: {}", frame));
+        }
+        self.fragments.insert(index, fragment);
+        Ok(())
+    }
+
+    /// Returns the number of received and expected frames.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.fragments.len(), self.count)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.count > 0 && self.fragments.len() == self.count
+    }
+
+    /// Returns the reassembled message after verifying its checksum.
+    pub fn finish(self) -> AnyhowResult<Vec<u8>> {
+        if !self.is_complete() {
+            let missing: Vec<_> = (1..=self.count)
+                .filter(|i| !self.fragments.contains_key(i))
+                .collect();
+            let mut listed: Vec<_> = missing
+                .iter()
+                .take(MAX_LISTED_MISSING)
+                .map(|i| i.to_string())
+                .collect();
+            if missing.len() > MAX_LISTED_MISSING {
+                listed.push(format!("and {} more", missing.len() - MAX_LISTED_MISSING));
+            }
+            return Err(anyhow!("Missing frames: {}", listed.join(", ")));
+        }
+        let data: Vec<u8> = self.fragments.into_iter().flat_map(|(_, f)| f).collect();
+        if checksum(&data) != self.checksum {
+            return Err(anyhow!(
+                "The checksum of the reassembled message doesn't match"
+            ));
+        }
+        Ok(data)
+    }
+}
+
+/// Reassembles a message from frames, one per line.
+pub fn decode(text: &str) -> AnyhowResult<Vec<u8>> {
+    let mut decoder = Decoder::default();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        decoder.receive(line)?;
+    }
+    decoder.finish()
+}
+
+/// Reads frames from a scanner, one per line, until the message is complete.
+/// Lines without a frame are skipped and the `QR-Code:` prefix of `zbarcam`
+/// and `zbarimg` is removed.
+pub fn scan(input: impl BufRead) -> AnyhowResult<Vec<u8>> {
+    let mut decoder = Decoder::default();
+    for line in input.lines() {
+        let line = line?;
+        let frame = line.trim().trim_start_matches("QR-Code:");
+        if !is_frame(frame) {
+            continue;
+        }
+        if let Err(err) = decoder.receive(frame) {
+            eprintln!("{}", err);
+            continue;
+        }
+        let (received, count) = decoder.progress();
+        eprintln!("Received {} of {} frames", received, count);
+        if decoder.is_complete() {
+            break;
+        }
+    }
+    decoder.finish()
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("{:08X}", crc32fast::hash(data))
+}
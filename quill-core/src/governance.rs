@@ -0,0 +1,251 @@
+//! Human-readable decoding of the governance canister responses.
+
+use crate::{governance_enums, AnyhowResult};
+use anyhow::anyhow;
+use candid::{CandidType, Decode, Deserialize};
+use ic_types::Principal;
+use serde::Serialize;
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct NeuronId {
+    pub id: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct GovernanceError {
+    pub error_message: String,
+    pub error_type: i32,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Empty {}
+
+#[derive(CandidType, Deserialize)]
+pub struct SpawnResponse {
+    pub created_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ClaimOrRefreshResponse {
+    pub refreshed_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct MakeProposalResponse {
+    pub proposal_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct MergeMaturityResponse {
+    pub merged_maturity_e8s: u64,
+    pub new_stake_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseResponse {
+    pub transfer_block_height: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum CommandResponse {
+    Error(GovernanceError),
+    Spawn(SpawnResponse),
+    Split(SpawnResponse),
+    Follow(Empty),
+    ClaimOrRefresh(ClaimOrRefreshResponse),
+    Configure(Empty),
+    RegisterVote(Empty),
+    DisburseToNeuron(SpawnResponse),
+    MakeProposal(MakeProposalResponse),
+    MergeMaturity(MergeMaturityResponse),
+    Disburse(DisburseResponse),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ManageNeuronResponse {
+    pub command: Option<CommandResponse>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum ClaimOrRefreshResult {
+    Error(GovernanceError),
+    NeuronId(NeuronId),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ClaimOrRefreshNeuronFromAccountResponse {
+    pub result: Option<ClaimOrRefreshResult>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct BallotInfo {
+    pub vote: i32,
+    pub proposal_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub enum DissolveState {
+    DissolveDelaySeconds(u64),
+    WhenDissolvedTimestampSeconds(u64),
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct Followees {
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct NeuronStakeTransfer {
+    #[serde(serialize_with = "hex::serialize")]
+    pub to_subaccount: Vec<u8>,
+    pub neuron_stake_e8s: u64,
+    pub from: Option<Principal>,
+    pub memo: u64,
+    #[serde(serialize_with = "hex::serialize")]
+    pub from_subaccount: Vec<u8>,
+    pub transfer_timestamp: u64,
+    pub block_height: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct Neuron {
+    pub id: Option<NeuronId>,
+    pub controller: Option<Principal>,
+    pub recent_ballots: Vec<BallotInfo>,
+    pub kyc_verified: bool,
+    pub not_for_profit: bool,
+    pub maturity_e8s_equivalent: u64,
+    pub cached_neuron_stake_e8s: u64,
+    pub created_timestamp_seconds: u64,
+    pub aging_since_timestamp_seconds: u64,
+    pub hot_keys: Vec<Principal>,
+    #[serde(serialize_with = "hex::serialize")]
+    pub account: Vec<u8>,
+    pub dissolve_state: Option<DissolveState>,
+    pub followees: Vec<(i32, Followees)>,
+    pub neuron_fees_e8s: u64,
+    pub transfer: Option<NeuronStakeTransfer>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct NeuronInfo {
+    pub dissolve_delay_seconds: u64,
+    pub recent_ballots: Vec<BallotInfo>,
+    pub created_timestamp_seconds: u64,
+    pub state: i32,
+    pub retrieved_at_timestamp_seconds: u64,
+    pub voting_power: u64,
+    pub age_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct ListNeuronsResponse {
+    pub neuron_infos: Vec<(u64, NeuronInfo)>,
+    pub full_neurons: Vec<Neuron>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub enum FullNeuronResult {
+    Ok(Neuron),
+    Err(GovernanceError),
+}
+
+/// Returns the complete neuron records of a `list_neurons` or `get_full_neuron`
+/// response as pretty-printed JSON.
+pub fn export_json(blob: &[u8], method_name: &str) -> AnyhowResult<String> {
+    let json = match method_name {
+        "list_neurons" => serde_json::to_string_pretty(&Decode!(blob, ListNeuronsResponse)?)?,
+        "get_full_neuron" => serde_json::to_string_pretty(&Decode!(blob, FullNeuronResult)?)?,
+        _ => {
+            return Err(anyhow!(
+                "JSON export is only supported for list_neurons and get_full_neuron"
+            ))
+        }
+    };
+    Ok(json)
+}
+
+/// Returns a human-readable description of a governance response, if the
+/// method is supported and the response could be decoded.
+pub fn describe_response(blob: &[u8], method_name: &str) -> Option<String> {
+    match method_name {
+        "manage_neuron" => {
+            let response = Decode!(blob, ManageNeuronResponse).ok()?;
+            Some(match response.command? {
+                CommandResponse::Error(err) => describe_error(&err),
+                CommandResponse::Spawn(r) => {
+                    format!(
+                        "Spawned neuron {}.",
+                        describe_neuron_id(r.created_neuron_id)
+                    )
+                }
+                CommandResponse::Split(r) => {
+                    format!(
+                        "Split off neuron {}.",
+                        describe_neuron_id(r.created_neuron_id)
+                    )
+                }
+                CommandResponse::DisburseToNeuron(r) => format!(
+                    "Disbursed to neuron {}.",
+                    describe_neuron_id(r.created_neuron_id)
+                ),
+                CommandResponse::Follow(_) => "Followees updated.".to_string(),
+                CommandResponse::Configure(_) => "Neuron configured.".to_string(),
+                CommandResponse::RegisterVote(_) => "Vote registered.".to_string(),
+                CommandResponse::ClaimOrRefresh(r) => format!(
+                    "Refreshed neuron {}.",
+                    describe_neuron_id(r.refreshed_neuron_id)
+                ),
+                CommandResponse::MakeProposal(r) => {
+                    format!("Submitted proposal {}.", describe_neuron_id(r.proposal_id))
+                }
+                CommandResponse::MergeMaturity(r) => format!(
+                    "Merged {} e8s of maturity, the new stake is {} e8s.",
+                    r.merged_maturity_e8s, r.new_stake_e8s
+                ),
+                CommandResponse::Disburse(r) => {
+                    format!("Disbursed in ledger block {}.", r.transfer_block_height)
+                }
+            })
+        }
+        "claim_or_refresh_neuron_from_account" => {
+            let response = Decode!(blob, ClaimOrRefreshNeuronFromAccountResponse).ok()?;
+            Some(match response.result? {
+                ClaimOrRefreshResult::Error(err) => describe_error(&err),
+                ClaimOrRefreshResult::NeuronId(id) => {
+                    format!("Claimed or refreshed neuron {}.", id.id)
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+fn describe_neuron_id(id: Option<NeuronId>) -> String {
+    id.map_or_else(|| "(unknown id)".to_string(), |id| id.id.to_string())
+}
+
+/// Translates a governance error into a message with a hint on how to fix it.
+pub fn describe_error(err: &GovernanceError) -> String {
+    let hint = match err.error_type {
+        2 => "The governance canister is busy, try again later.",
+        3 => "The signing principal is neither the controller nor a hot key of the neuron.",
+        4 => "Check the neuron or proposal id.",
+        5 => "The command is malformed or not applicable.",
+        6 => "Stop dissolving the neuron first.",
+        7 => "Start dissolving the neuron first.",
+        8 => "The neuron must be fully dissolved, wait until its dissolve delay has passed.",
+        9 => "This operation can't be performed with a hot key.",
+        10 => "Too many requests, try again later.",
+        11 => "The neuron doesn't satisfy the requirements of this operation.",
+        12 => "A call to another canister failed, try again later.",
+        13 => "Another operation on this neuron is in progress, try again later.",
+        14 => "The neuron or account doesn't hold enough ICP to cover the amount and the fees.",
+        15 => "Check the principal id.",
+        16 => "The proposal is malformed.",
+        _ => "No further details are available.",
+    };
+    let name = governance_enums::name(governance_enums::ERROR_TYPES, err.error_type)
+        .unwrap_or("Unspecified");
+    format!("Error {}: {}\n{}", name, err.error_message, hint)
+}
@@ -0,0 +1,137 @@
+//! Names of the enums which the governance canister encodes as `int32`. Its
+//! candid interface is generated from protobuf definitions, so these names
+//! aren't part of `governance.did` and are kept here instead.
+
+use candid::{
+    idl_hash,
+    parser::value::{IDLField, IDLValue, VariantValue},
+    IDLArgs,
+};
+use std::convert::TryFrom;
+
+pub const TOPICS: &[&str] = &[
+    "Unspecified",
+    "NeuronManagement",
+    "ExchangeRate",
+    "NetworkEconomics",
+    "Governance",
+    "NodeAdmin",
+    "ParticipantManagement",
+    "SubnetManagement",
+    "NetworkCanisterManagement",
+    "Kyc",
+    "NodeProviderRewards",
+    "SnsDecentralizationSale",
+    "SubnetReplicaVersionManagement",
+    "ReplicaVersionManagement",
+    "SnsAndCommunityFund",
+];
+
+pub const PROPOSAL_STATUSES: &[&str] = &[
+    "Unspecified",
+    "Open",
+    "Rejected",
+    "Adopted",
+    "Executed",
+    "Failed",
+];
+
+pub const REWARD_STATUSES: &[&str] = &[
+    "Unspecified",
+    "AcceptVotes",
+    "ReadyToSettle",
+    "Settled",
+    "Ineligible",
+];
+
+pub const NEURON_STATES: &[&str] = &["Unspecified", "NotDissolving", "Dissolving", "Dissolved"];
+
+pub const VOTES: &[&str] = &["Unspecified", "Yes", "No"];
+
+pub const ERROR_TYPES: &[&str] = &[
+    "Unspecified",
+    "Ok",
+    "Unavailable",
+    "NotAuthorized",
+    "NotFound",
+    "InvalidCommand",
+    "RequiresNotDissolving",
+    "RequiresDissolving",
+    "RequiresDissolved",
+    "HotKey",
+    "ResourceExhausted",
+    "PreconditionFailed",
+    "External",
+    "LedgerUpdateOngoing",
+    "InsufficientFunds",
+    "InvalidPrincipal",
+    "InvalidProposal",
+];
+
+// The `int32` fields of the governance records and the enums of their values.
+const FIELDS: &[(&str, &[&str])] = &[
+    ("topic", TOPICS),
+    ("exclude_topic", TOPICS),
+    ("status", PROPOSAL_STATUSES),
+    ("include_status", PROPOSAL_STATUSES),
+    ("reward_status", REWARD_STATUSES),
+    ("include_reward_status", REWARD_STATUSES),
+    ("state", NEURON_STATES),
+    ("vote", VOTES),
+    ("error_type", ERROR_TYPES),
+];
+
+/// Returns the name of an enum value, if it is known.
+pub fn name(names: &[&'static str], value: i32) -> Option<&'static str> {
+    usize::try_from(value)
+        .ok()
+        .and_then(|index| names.get(index))
+        .copied()
+}
+
+/// Replaces the values of the enum fields in decoded governance arguments or
+/// responses with their names, e.g. `topic = "Governance (4)"`. Unknown
+/// values are kept as numbers.
+pub fn name_values(args: IDLArgs) -> IDLArgs {
+    IDLArgs {
+        args: args
+            .args
+            .into_iter()
+            .map(|value| rename(value, None))
+            .collect(),
+    }
+}
+
+fn rename(value: IDLValue, names: Option<&[&'static str]>) -> IDLValue {
+    match value {
+        IDLValue::Int32(n) => match names.and_then(|names| name(names, n)) {
+            Some(name) => IDLValue::Text(format!("{} ({})", name, n)),
+            None => IDLValue::Int32(n),
+        },
+        IDLValue::Opt(value) => IDLValue::Opt(Box::new(rename(*value, names))),
+        IDLValue::Vec(values) => IDLValue::Vec(
+            values
+                .into_iter()
+                .map(|value| rename(value, names))
+                .collect(),
+        ),
+        IDLValue::Record(fields) => {
+            IDLValue::Record(fields.into_iter().map(rename_field).collect())
+        }
+        IDLValue::Variant(VariantValue(field, index)) => {
+            IDLValue::Variant(VariantValue(Box::new(rename_field(*field)), index))
+        }
+        value => value,
+    }
+}
+
+fn rename_field(field: IDLField) -> IDLField {
+    let names = FIELDS
+        .iter()
+        .find(|(name, _)| idl_hash(name) == field.id.get_id())
+        .map(|(_, names)| *names);
+    IDLField {
+        id: field.id,
+        val: rename(field.val, names),
+    }
+}
@@ -0,0 +1,497 @@
+//! The HTTP transport of the requests to the IC. Unlike the transport of the
+//! agent, its TLS settings can be changed for private deployments, which use
+//! certificates of an internal authority, and it fails over between several
+//! endpoints.
+
+use crate::{certificate, error::QuillError, ic_urls, AnyhowResult};
+use anyhow::Context;
+use ic_agent::agent::ReplicaV2Transport;
+use ic_agent::agent_error::HttpErrorPayload;
+use ic_agent::{AgentError, RequestId};
+use ic_types::Principal;
+use reqwest::{Certificate, Client, Method, StatusCode, Url};
+use serde_cbor::Value;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+// The time after which a request to an endpoint is given up, so that the next
+// endpoint is tried.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// The index of the endpoint which last answered, which is tried first.
+static PREFERRED_ENDPOINT: AtomicUsize = AtomicUsize::new(0);
+
+// The longest wait asked for by the Retry-After header of a 429 response.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(5 * 60);
+
+// The time until which the endpoints asked not to send further requests, by
+// the Retry-After header of their last 429 response. Rate limits apply to the
+// client, so all requests wait, including those of concurrently sent messages.
+static RATE_LIMITED_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+// The responses to synchronous calls which contained the certified reply, by
+// request id. They answer the first poll of the status of the request instead
+// of a read_state request.
+static CERTIFIED_REPLIES: Mutex<Vec<(RequestId, Vec<u8>)>> = Mutex::new(Vec::new());
+
+/// Returns the certified reply of a synchronous call of the request, in the
+/// format of a `read_state` response, if its call API returned one.
+pub fn take_certified_reply(request_id: &RequestId) -> Option<Vec<u8>> {
+    let mut replies = CERTIFIED_REPLIES.lock().unwrap();
+    let i = replies.iter().position(|(id, _)| id == request_id)?;
+    Some(replies.remove(i).1)
+}
+
+/// Returns how much longer the endpoints asked to wait before the next request.
+pub fn rate_limit_delay() -> Option<Duration> {
+    RATE_LIMITED_UNTIL
+        .lock()
+        .unwrap()
+        .and_then(|until| until.checked_duration_since(Instant::now()))
+}
+
+/// The IP version of the connections to the IC.
+#[derive(Clone, Copy)]
+pub enum IpVersion {
+    V4,
+    V6,
+}
+
+impl IpVersion {
+    fn matches(self, address: IpAddr) -> bool {
+        match self {
+            IpVersion::V4 => address.is_ipv4(),
+            IpVersion::V6 => address.is_ipv6(),
+        }
+    }
+
+    fn unspecified(self) -> IpAddr {
+        match self {
+            IpVersion::V4 => Ipv4Addr::UNSPECIFIED.into(),
+            IpVersion::V6 => Ipv6Addr::UNSPECIFIED.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for IpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpVersion::V4 => write!(f, "IPv4"),
+            IpVersion::V6 => write!(f, "IPv6"),
+        }
+    }
+}
+
+// The settings of the HTTP client given by the global options.
+#[derive(Clone, Default)]
+struct ClientOptions {
+    ca_cert: Option<Certificate>,
+    ca_cert_file: Option<String>,
+    insecure_tls: bool,
+    ip_version: Option<IpVersion>,
+    /// The addresses of hosts which aren't looked up.
+    hosts: Vec<(String, IpAddr)>,
+}
+
+static CLIENT_OPTIONS: RwLock<Option<ClientOptions>> = RwLock::new(None);
+
+fn update_options(update: impl FnOnce(&mut ClientOptions)) {
+    update(
+        CLIENT_OPTIONS
+            .write()
+            .unwrap()
+            .get_or_insert_with(Default::default),
+    );
+}
+
+/// Trusts the certificate authority in the PEM file in addition to the usual
+/// ones, or accepts any certificate if `insecure`.
+pub fn set_tls_options(ca_cert_file: Option<&str>, insecure: bool) -> AnyhowResult {
+    let ca_cert = match ca_cert_file {
+        Some(path) => {
+            let pem = std::fs::read_to_string(path)
+                .with_context(|| format!("Couldn't read the CA certificate {}", path))?;
+            let certificate = Certificate::from_pem(pem.as_bytes())
+                .ok()
+                .filter(|_| pem.contains("-----BEGIN CERTIFICATE-----"))
+                .ok_or_else(|| {
+                    QuillError::Validation(format!("{} isn't a PEM-encoded certificate", path))
+                })?;
+            Some(certificate)
+        }
+        None => None,
+    };
+    if insecure {
+        eprintln!(
+            "WARNING: --insecure-tls disables the verification of TLS certificates. Anyone on \
+             the network path can read and change the requests and responses. Only use it for \
+             test deployments, never for the mainnet."
+        );
+    }
+    update_options(|options| {
+        options.ca_cert = ca_cert;
+        options.ca_cert_file = ca_cert_file.map(str::to_string);
+        options.insecure_tls = insecure;
+    });
+    Ok(())
+}
+
+/// Connects to the IC only over the given IP version, and to the given
+/// addresses of hosts, which are mapped as `<host>=<ip address>`, without
+/// looking them up.
+pub fn set_dns_options(ip_version: Option<IpVersion>, hosts: &[String]) -> AnyhowResult {
+    let hosts = hosts
+        .iter()
+        .map(|mapping| {
+            let (host, address) = mapping.split_once('=').ok_or_else(|| {
+                QuillError::Validation(format!(
+                    "Invalid host mapping {}, it must be <host>=<ip address>",
+                    mapping
+                ))
+            })?;
+            let address = address.parse().map_err(|_| {
+                QuillError::Validation(format!("Invalid IP address {} of {}", address, host))
+            })?;
+            Ok((host.to_string(), address))
+        })
+        .collect::<AnyhowResult<_>>()?;
+    update_options(|options| {
+        options.ip_version = ip_version;
+        options.hosts = hosts;
+    });
+    Ok(())
+}
+
+/// Returns the options of `curl` which make it connect to the IC with the
+/// settings of the global options.
+pub fn curl_options() -> Vec<String> {
+    let options = CLIENT_OPTIONS.read().unwrap().clone().unwrap_or_default();
+    let mut curl_options = Vec::new();
+    if let Some(path) = &options.ca_cert_file {
+        curl_options.push(format!("--cacert {}", shell_words::quote(path)));
+    }
+    if options.insecure_tls {
+        curl_options.push("--insecure".to_string());
+    }
+    match options.ip_version {
+        Some(IpVersion::V4) => curl_options.push("--ipv4".to_string()),
+        Some(IpVersion::V6) => curl_options.push("--ipv6".to_string()),
+        None => (),
+    }
+    for url in ic_urls() {
+        let url = match Url::parse(&url) {
+            Ok(url) => url,
+            Err(_) => continue,
+        };
+        let port = url.port_or_known_default().unwrap_or(443);
+        for (host, address) in &options.hosts {
+            if url.host_str() == Some(host.as_str()) {
+                let address = match address {
+                    IpAddr::V4(address) => address.to_string(),
+                    IpAddr::V6(address) => format!("[{}]", address),
+                };
+                curl_options.push(format!("--resolve {}:{}:{}", host, port, address));
+            }
+        }
+    }
+    curl_options
+}
+
+// Returns a client with the settings of the global options. With a forced IP
+// version, the hosts of the endpoints are looked up here and only their
+// addresses of that version are used.
+fn client() -> AnyhowResult<Client> {
+    let options = CLIENT_OPTIONS.read().unwrap().clone().unwrap_or_default();
+    let mut builder = Client::builder().danger_accept_invalid_certs(options.insecure_tls);
+    if let Some(ca_cert) = options.ca_cert {
+        builder = builder.add_root_certificate(ca_cert);
+    }
+    // The port is taken from the URL.
+    for (host, address) in &options.hosts {
+        builder = builder.resolve(host, SocketAddr::new(*address, 0));
+    }
+    if let Some(version) = options.ip_version {
+        for url in ic_urls() {
+            let url = Url::parse(&url)?;
+            let (host, port) = match (url.host_str(), url.port_or_known_default()) {
+                (Some(host), Some(port)) => (host, port),
+                _ => continue,
+            };
+            if host.parse::<IpAddr>().is_ok() || options.hosts.iter().any(|(h, _)| h == host) {
+                continue;
+            }
+            let address = (host, port)
+                .to_socket_addrs()
+                .map_err(|err| QuillError::Network(format!("Couldn't look up {}: {}", host, err)))?
+                .find(|address| version.matches(address.ip()))
+                .ok_or_else(|| {
+                    QuillError::Network(format!("{} has no {} address", host, version))
+                })?;
+            builder = builder.resolve(host, address);
+        }
+        builder = builder.local_address(version.unspecified());
+    }
+    builder.build().map_err(|err| {
+        QuillError::Validation(format!("Couldn't create the HTTP client: {}", err)).into()
+    })
+}
+
+/// Sends the requests of an agent to the endpoints of the IC. A request which
+/// fails with a connection error, a timeout, rate limiting or a server error is
+/// sent to the next endpoint; the IC ignores duplicates of a signed message.
+pub struct HttpTransport {
+    urls: Vec<String>,
+    client: Client,
+    // The endpoint which answered the last request.
+    endpoint: Mutex<Option<String>>,
+}
+
+impl HttpTransport {
+    pub fn create() -> AnyhowResult<Self> {
+        Ok(Self {
+            urls: ic_urls(),
+            client: client()?,
+            endpoint: Mutex::new(None),
+        })
+    }
+
+    /// Returns the URL of the endpoint which answered the last request.
+    pub fn endpoint(&self) -> Option<String> {
+        self.endpoint.lock().unwrap().clone()
+    }
+
+    /// Submits an update call with the synchronous call API, which waits a few
+    /// seconds for the call to be executed and then returns its certified
+    /// reply, so that its status needn't be polled. If the call takes longer,
+    /// it's only accepted; if the endpoint doesn't support the API, the call is
+    /// submitted with the asynchronous one.
+    pub async fn call_sync(
+        &self,
+        effective_canister_id: Principal,
+        envelope: Vec<u8>,
+        request_id: RequestId,
+    ) -> Result<(), AgentError> {
+        let endpoint = format!("v3/canister/{}/call", effective_canister_id.to_text());
+        let response = match self
+            .request(Method::POST, endpoint, Some(envelope.clone()))
+            .await
+        {
+            Err(AgentError::HttpError(payload))
+                if payload.status == 404 || payload.status == 405 =>
+            {
+                debug!("The synchronous call API isn't supported, using the asynchronous one");
+                return self.call(effective_canister_id, envelope, request_id).await;
+            }
+            response => response?,
+        };
+        let fields = match serde_cbor::from_slice(&response) {
+            Ok(Value::Map(fields)) => fields,
+            // The call was accepted, but not executed yet.
+            _ => return Ok(()),
+        };
+        let field = |name: &str| fields.get(&Value::Text(name.to_string()));
+        match (field("status"), field("certificate")) {
+            (Some(Value::Text(status)), Some(Value::Bytes(certificate))) if status == "replied" => {
+                let response = serde_cbor::to_vec(&Value::Map(
+                    vec![(
+                        Value::Text("certificate".to_string()),
+                        Value::Bytes(certificate.clone()),
+                    )]
+                    .into_iter()
+                    .collect(),
+                ))
+                .map_err(|err| AgentError::MessageError(err.to_string()))?;
+                certificate::check(&response, effective_canister_id).map_err(|err| {
+                    AgentError::MessageError(format!(
+                        "The certificate of the response is invalid: {}",
+                        err
+                    ))
+                })?;
+                debug!("The synchronous call returned the certified reply");
+                CERTIFIED_REPLIES
+                    .lock()
+                    .unwrap()
+                    .push((request_id, response));
+            }
+            (Some(Value::Text(status)), _) if status == "non_replicated_rejection" => {
+                let reject_code = match field("reject_code") {
+                    Some(Value::Integer(code)) => *code as u64,
+                    _ => 0,
+                };
+                let reject_message = match field("reject_message") {
+                    Some(Value::Text(message)) => message.clone(),
+                    _ => String::new(),
+                };
+                return Err(AgentError::ReplicaError {
+                    reject_code,
+                    reject_message,
+                });
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        endpoint: String,
+        body: Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, AgentError> {
+        if let Some(delay) = rate_limit_delay() {
+            eprintln!(
+                "Rate limited by the IC, waiting {}s before the next request",
+                delay.as_secs() + 1
+            );
+            tokio::time::sleep(delay).await;
+        }
+        let first = PREFERRED_ENDPOINT.load(Ordering::Relaxed) % self.urls.len();
+        let mut attempts = (0..self.urls.len())
+            .map(|i| (first + i) % self.urls.len())
+            .peekable();
+        loop {
+            let i = attempts.next().unwrap();
+            let url = &self.urls[i];
+            let result = self
+                .request_endpoint(url, method.clone(), &endpoint, body.clone())
+                .await;
+            match (result, attempts.peek()) {
+                (Err(err), Some(next)) if fails_over(&err) => {
+                    warn!(%url, error = %err, "Failing over to the next endpoint");
+                    eprintln!(
+                        "The request to {} failed ({}), trying {}",
+                        url, err, self.urls[*next]
+                    );
+                }
+                (result, _) => {
+                    if result.is_ok() {
+                        PREFERRED_ENDPOINT.store(i, Ordering::Relaxed);
+                        *self.endpoint.lock().unwrap() = Some(url.clone());
+                    }
+                    return result;
+                }
+            }
+        }
+    }
+
+    async fn request_endpoint(
+        &self,
+        url: &str,
+        method: Method,
+        endpoint: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, AgentError> {
+        let mut request = self
+            .client
+            .request(method, format!("{}/api/{}", url, endpoint))
+            .timeout(REQUEST_TIMEOUT);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/cbor")
+                .body(body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|err| AgentError::TransportError(Box::new(err)))?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            // Only the delay in seconds is supported, not an HTTP date.
+            let retry_after = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_AFTER));
+            if let Some(retry_after) = retry_after {
+                warn!(%url, retry_after_secs = retry_after.as_secs(), "Rate limited");
+                *RATE_LIMITED_UNTIL.lock().unwrap() = Some(Instant::now() + retry_after);
+            }
+        }
+        let content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let content = response
+            .bytes()
+            .await
+            .map_err(|err| AgentError::TransportError(Box::new(err)))?
+            .to_vec();
+        if status.is_client_error() || status.is_server_error() {
+            return Err(AgentError::HttpError(HttpErrorPayload {
+                status: status.as_u16(),
+                content_type,
+                content,
+            }));
+        }
+        Ok(content)
+    }
+}
+
+fn fails_over(err: &AgentError) -> bool {
+    match err {
+        AgentError::HttpError(payload) => payload.status == 429 || payload.status >= 500,
+        AgentError::TransportError(_) => true,
+        _ => false,
+    }
+}
+
+impl ReplicaV2Transport for HttpTransport {
+    fn call<'a>(
+        &'a self,
+        effective_canister_id: Principal,
+        envelope: Vec<u8>,
+        _request_id: RequestId,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AgentError>> + Send + 'a>> {
+        Box::pin(async move {
+            let endpoint = format!("v2/canister/{}/call", effective_canister_id.to_text());
+            self.request(Method::POST, endpoint, Some(envelope))
+                .await
+                .map(|_| ())
+        })
+    }
+
+    fn read_state<'a>(
+        &'a self,
+        effective_canister_id: Principal,
+        envelope: Vec<u8>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, AgentError>> + Send + 'a>> {
+        Box::pin(async move {
+            let endpoint = format!("v2/canister/{}/read_state", effective_canister_id.to_text());
+            let response = self.request(Method::POST, endpoint, Some(envelope)).await?;
+            certificate::check(&response, effective_canister_id).map_err(|err| {
+                AgentError::MessageError(format!(
+                    "The certificate of the response is invalid: {}",
+                    err
+                ))
+            })?;
+            Ok(response)
+        })
+    }
+
+    fn query<'a>(
+        &'a self,
+        effective_canister_id: Principal,
+        envelope: Vec<u8>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, AgentError>> + Send + 'a>> {
+        Box::pin(async move {
+            let endpoint = format!("v2/canister/{}/query", effective_canister_id.to_text());
+            self.request(Method::POST, endpoint, Some(envelope)).await
+        })
+    }
+
+    fn status<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, AgentError>> + Send + 'a>> {
+        Box::pin(async move {
+            self.request(Method::GET, "v2/status".to_string(), None)
+                .await
+        })
+    }
+}
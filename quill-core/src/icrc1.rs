@@ -0,0 +1,170 @@
+//! Types of ICRC-1 ledgers and the textual encoding of ICRC-1 accounts.
+
+use anyhow::anyhow;
+use candid::{CandidType, Deserialize, Nat};
+use ic_types::Principal;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(CandidType, Deserialize, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub owner: Principal,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TransferArg {
+    pub from_subaccount: Option<Vec<u8>>,
+    pub to: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+}
+
+impl Account {
+    // The subaccount, or `None` if it's the default (all zeros) subaccount.
+    fn effective_subaccount(&self) -> Option<&[u8]> {
+        self.subaccount
+            .as_deref()
+            .filter(|subaccount| subaccount.iter().any(|b| *b != 0))
+    }
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.effective_subaccount() {
+            None => write!(f, "{}", self.owner),
+            Some(subaccount) => {
+                let hex = hex::encode(subaccount);
+                write!(
+                    f,
+                    "{}-{}.{}",
+                    self.owner,
+                    checksum(&self.owner, subaccount),
+                    hex.trim_start_matches('0')
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for Account {
+    type Err = anyhow::Error;
+
+    /// Parses the textual encoding `<principal>[-<checksum>.<subaccount>]`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (owner_and_checksum, subaccount) = match text.split_once('.') {
+            None => {
+                let owner = Principal::from_text(text).map_err(|err| anyhow!(err))?;
+                return Ok(Self {
+                    owner,
+                    subaccount: None,
+                });
+            }
+            Some(parts) => parts,
+        };
+        let (owner, expected_checksum) = owner_and_checksum
+            .rsplit_once('-')
+            .ok_or_else(|| anyhow!("Missing checksum in the account {}", text))?;
+        let owner = Principal::from_text(owner).map_err(|err| anyhow!(err))?;
+        if subaccount.is_empty() || subaccount.starts_with('0') || subaccount.len() > 64 {
+            return Err(anyhow!(
+                "The subaccount must be non-empty hex without leading zeros: {}",
+                text
+            ));
+        }
+        let subaccount = hex::decode(format!("{:0>64}", subaccount))?;
+        if checksum(&owner, &subaccount) != expected_checksum {
+            return Err(anyhow!("Invalid checksum in the account {}", text));
+        }
+        Ok(Self {
+            owner,
+            subaccount: Some(subaccount),
+        })
+    }
+}
+
+// The CRC32 checksum of the owner and the subaccount, as lowercase base32 without padding.
+fn checksum(owner: &Principal, subaccount: &[u8]) -> String {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(owner.as_slice());
+    hasher.update(subaccount);
+    base32(&hasher.finalize().to_be_bytes())
+}
+
+fn base32(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut result = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    result
+}
+
+/// Parses an amount of tokens with up to `decimals` digits after the point
+/// into the smallest token units.
+pub fn parse_tokens(amount: &str, decimals: u32) -> Result<u64, String> {
+    let mut it = amount.split('.');
+    let tokens = it
+        .next()
+        .unwrap_or("0")
+        .parse::<u64>()
+        .map_err(|err| format!("Couldn't parse tokens: {:?}", err))?;
+    let fraction = it.next().unwrap_or("");
+    if it.next().is_some() || fraction.len() > decimals as usize {
+        return Err(format!(
+            "The amount must have at most {} decimal digits",
+            decimals
+        ));
+    }
+    let units = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<width$}", fraction, width = decimals as usize)
+            .parse::<u64>()
+            .map_err(|err| format!("Couldn't parse the decimal digits: {:?}", err))?
+    };
+    10u64
+        .checked_pow(decimals)
+        .and_then(|scale| tokens.checked_mul(scale))
+        .and_then(|units_from_tokens| units_from_tokens.checked_add(units))
+        .ok_or_else(|| "The amount is too large".to_string())
+}
+
+/// Formats an amount in the smallest token units as tokens with `decimals`
+/// digits after the point.
+pub fn format_tokens(units: u64, decimals: u32) -> String {
+    match 10u64.checked_pow(decimals) {
+        Some(scale) if decimals > 0 => format!(
+            "{}.{:0width$}",
+            units / scale,
+            units % scale,
+            width = decimals as usize
+        ),
+        _ => units.to_string(),
+    }
+}
+
+/// Separates the digits of the integer part of an amount in groups of three,
+/// e.g. "1,250.00000000".
+pub fn group_digits(amount: &str) -> String {
+    let (integer, fraction) = amount.split_at(amount.find('.').unwrap_or(amount.len()));
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped + fraction
+}
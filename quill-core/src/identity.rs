@@ -0,0 +1,157 @@
+//! The identity quill signs with, which other tools built on ic-agent can use
+//! as theirs to load keys the same way as quill.
+
+#[cfg(feature = "mnemonic")]
+use crate::crypto::mnemonic_to_pem;
+#[cfg(feature = "pure-rust")]
+use crate::crypto::SigningKey;
+use crate::{error::QuillError, AnyhowResult};
+#[cfg(feature = "transport")]
+use anyhow::anyhow;
+#[cfg(feature = "transport")]
+use ic_agent::{
+    identity::{BasicIdentity, Secp256k1Identity, Signature},
+    Identity as AgentIdentity,
+};
+#[cfg(feature = "hsm")]
+use ic_identity_hsm::HardwareIdentity;
+use ic_types::Principal;
+
+/// A signing identity loaded by quill. With the `transport` feature it
+/// implements [`ic_agent::Identity`], so that it can be given to an agent with
+/// `AgentBuilder::with_identity`.
+///
+/// The variants are the key backends quill supports: PEM files, seed phrases
+/// (the `mnemonic` feature), HSMs (the `hsm` feature) and external signers.
+/// Further backends are added as variants, so matching on them should have a
+/// wildcard arm. With the `pure-rust` feature, PEM files are loaded as
+/// [`Identity::PureRust`] instead, which signs without OpenSSL; without it and
+/// without `transport`, PEM files can't be loaded.
+#[non_exhaustive]
+pub enum Identity {
+    /// A secp256k1 key, as generated by `openssl ecparam -name secp256k1`.
+    #[cfg(feature = "transport")]
+    Secp256k1(Secp256k1Identity),
+    /// An Ed25519 key, as generated by dfx.
+    #[cfg(feature = "transport")]
+    Ed25519(BasicIdentity),
+    /// A key held by an external signer, of which only the DER-encoded public
+    /// key is known. Envelopes are left unsigned, to be signed by the signer.
+    External(Vec<u8>),
+    /// A secp256k1 or Ed25519 key of a PEM file, signing in pure Rust.
+    #[cfg(feature = "pure-rust")]
+    PureRust(SigningKey),
+    /// The secp256k1 key of a BIP39 seed phrase, derived at m/44'/223'/0'/0/0,
+    /// loaded like the PEM file of the key.
+    #[cfg(feature = "mnemonic")]
+    Mnemonic(Box<Identity>),
+    /// A key on an HSM, e.g. a YubiHSM or a Nitrokey, used through its PKCS#11
+    /// library.
+    #[cfg(feature = "hsm")]
+    Hsm(HardwareIdentity),
+}
+
+impl Identity {
+    /// Loads the key of a PEM file, trying secp256k1 first and Ed25519 next.
+    pub fn from_pem(pem: &str) -> AnyhowResult<Self> {
+        #[cfg(feature = "pure-rust")]
+        let identity = SigningKey::from_pem(pem).map(Identity::PureRust);
+        #[cfg(all(feature = "transport", not(feature = "pure-rust")))]
+        let identity = Secp256k1Identity::from_pem(pem.as_bytes())
+            .map(Identity::Secp256k1)
+            .or_else(|_| BasicIdentity::from_pem(pem.as_bytes()).map(Identity::Ed25519))
+            .ok();
+        #[cfg(not(any(feature = "transport", feature = "pure-rust")))]
+        let identity = {
+            let _ = pem;
+            None::<Identity>
+        };
+        identity.ok_or_else(|| {
+            QuillError::Identity("Couldn't load identity from PEM file".to_string()).into()
+        })
+    }
+
+    /// Loads the key of a BIP39 seed phrase in English.
+    #[cfg(feature = "mnemonic")]
+    pub fn from_mnemonic(phrase: &str) -> AnyhowResult<Self> {
+        let identity = Identity::from_pem(&mnemonic_to_pem(phrase)?)?;
+        Ok(Identity::Mnemonic(Box::new(identity)))
+    }
+
+    /// Opens the key with the given id in the slot of an HSM, through the
+    /// PKCS#11 library of the HSM, e.g. `/usr/local/lib/opensc-pkcs11.so`.
+    #[cfg(feature = "hsm")]
+    pub fn from_hsm(
+        pkcs11_lib_path: &str,
+        slot_index: usize,
+        key_id: &str,
+        pin: &str,
+    ) -> AnyhowResult<Self> {
+        let pin = pin.to_string();
+        HardwareIdentity::new(pkcs11_lib_path, slot_index, key_id, || Ok(pin))
+            .map(Identity::Hsm)
+            .map_err(|err| {
+                QuillError::Identity(format!("Couldn't open the key of the HSM: {}", err)).into()
+            })
+    }
+}
+
+impl Identity {
+    /// Returns the principal of the key, the sender of the messages it signs.
+    pub fn principal(&self) -> AnyhowResult<Principal> {
+        match self {
+            #[cfg(feature = "transport")]
+            Identity::Secp256k1(identity) => identity.sender().map_err(|err| anyhow!(err)),
+            #[cfg(feature = "transport")]
+            Identity::Ed25519(identity) => identity.sender().map_err(|err| anyhow!(err)),
+            Identity::External(public_key) => Ok(Principal::self_authenticating(public_key)),
+            #[cfg(feature = "pure-rust")]
+            Identity::PureRust(key) => Ok(Principal::self_authenticating(key.public_key())),
+            #[cfg(feature = "mnemonic")]
+            Identity::Mnemonic(identity) => identity.principal(),
+            #[cfg(feature = "hsm")]
+            Identity::Hsm(identity) => identity.sender().map_err(|err| anyhow!(err)),
+        }
+    }
+
+    /// Signs the blob and returns the DER-encoded public key of the key with
+    /// the signature, which is `None` for an external signer.
+    pub fn sign_blob(&self, blob: &[u8]) -> AnyhowResult<(Vec<u8>, Option<Vec<u8>>)> {
+        #[cfg(feature = "transport")]
+        let agent_signature = |signature: Result<Signature, String>| -> AnyhowResult<_> {
+            let signature = signature.map_err(|err| anyhow!(err))?;
+            let public_key = signature
+                .public_key
+                .ok_or_else(|| anyhow!("The identity has no public key"))?;
+            Ok((public_key, signature.signature))
+        };
+        match self {
+            #[cfg(feature = "transport")]
+            Identity::Secp256k1(identity) => agent_signature(identity.sign(blob)),
+            #[cfg(feature = "transport")]
+            Identity::Ed25519(identity) => agent_signature(identity.sign(blob)),
+            Identity::External(public_key) => Ok((public_key.clone(), None)),
+            #[cfg(feature = "pure-rust")]
+            Identity::PureRust(key) => Ok((key.public_key(), Some(key.sign(blob)))),
+            #[cfg(feature = "mnemonic")]
+            Identity::Mnemonic(identity) => identity.sign_blob(blob),
+            #[cfg(feature = "hsm")]
+            Identity::Hsm(identity) => agent_signature(identity.sign(blob)),
+        }
+    }
+}
+
+#[cfg(feature = "transport")]
+impl AgentIdentity for Identity {
+    fn sender(&self) -> Result<Principal, String> {
+        self.principal().map_err(|err| err.to_string())
+    }
+
+    fn sign(&self, blob: &[u8]) -> Result<Signature, String> {
+        let (public_key, signature) = self.sign_blob(blob).map_err(|err| err.to_string())?;
+        Ok(Signature {
+            public_key: Some(public_key),
+            signature,
+        })
+    }
+}
@@ -0,0 +1,109 @@
+//! Conversion of JSON values into candid values of a known type.
+
+use crate::AnyhowResult;
+use anyhow::anyhow;
+use candid::{
+    parser::typing::TypeEnv,
+    types::{Label, Type},
+};
+use serde_json::Value;
+
+/// Converts a JSON value into the candid textual representation of a value of
+/// the given type. Variants are JSON objects with a single key, optional values
+/// are `null` or the value itself, and byte vectors can be hex strings.
+pub fn json_to_candid(env: &TypeEnv, value: &Value, ty: &Type) -> AnyhowResult<String> {
+    let mismatch = || anyhow!("Expected a value of type {}, found {}", ty, value);
+    Ok(match ty {
+        Type::Var(name) => {
+            let ty = env.rec_find_type(name).map_err(|err| anyhow!("{}", err))?;
+            return json_to_candid(env, value, ty);
+        }
+        Type::Knot(_) | Type::Unknown => return Err(anyhow!("Unsupported type {}", ty)),
+        Type::Null | Type::Reserved => match value {
+            Value::Null => "null".to_string(),
+            _ => return Err(mismatch()),
+        },
+        Type::Bool => value.as_bool().ok_or_else(mismatch)?.to_string(),
+        Type::Text => format!("{:?}", value.as_str().ok_or_else(mismatch)?),
+        Type::Principal => format!("principal {:?}", value.as_str().ok_or_else(mismatch)?),
+        Type::Nat
+        | Type::Nat8
+        | Type::Nat16
+        | Type::Nat32
+        | Type::Nat64
+        | Type::Int
+        | Type::Int8
+        | Type::Int16
+        | Type::Int32
+        | Type::Int64
+        | Type::Float32
+        | Type::Float64 => match value {
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.clone(),
+            _ => return Err(mismatch()),
+        },
+        Type::Opt(inner) => match value {
+            Value::Null => "null".to_string(),
+            _ => format!("opt {}", json_to_candid(env, value, inner)?),
+        },
+        Type::Vec(inner) => match (value, inner.as_ref()) {
+            (Value::String(s), Type::Nat8) => {
+                let bytes = hex::decode(s).map_err(|err| anyhow!("Invalid hex {}: {}", s, err))?;
+                let bytes: Vec<_> = bytes.iter().map(|b| b.to_string()).collect();
+                format!("vec {{ {} }}", bytes.join("; "))
+            }
+            (Value::Array(items), _) => {
+                let items = items
+                    .iter()
+                    .map(|item| json_to_candid(env, item, inner))
+                    .collect::<AnyhowResult<Vec<_>>>()?;
+                format!("vec {{ {} }}", items.join("; "))
+            }
+            _ => return Err(mismatch()),
+        },
+        Type::Record(fields) => {
+            let object = value.as_object().ok_or_else(mismatch)?;
+            if let Some(key) = object
+                .keys()
+                .find(|key| !fields.iter().any(|field| label_name(&field.id) == **key))
+            {
+                return Err(anyhow!("Unknown field {} in {}", key, value));
+            }
+            let fields = fields
+                .iter()
+                .filter_map(|field| {
+                    let name = label_name(&field.id);
+                    object.get(&name).map(|value| {
+                        json_to_candid(env, value, &field.ty)
+                            .map(|value| format!("{:?} = {}", name, value))
+                    })
+                })
+                .collect::<AnyhowResult<Vec<_>>>()?;
+            format!("record {{ {} }}", fields.join("; "))
+        }
+        Type::Variant(fields) => {
+            let object = value.as_object().ok_or_else(mismatch)?;
+            let (name, value) = match object.iter().next() {
+                Some(entry) if object.len() == 1 => entry,
+                _ => return Err(mismatch()),
+            };
+            let field = fields
+                .iter()
+                .find(|field| label_name(&field.id) == *name)
+                .ok_or_else(|| anyhow!("Unknown variant {} of type {}", name, ty))?;
+            format!(
+                "variant {{ {:?} = {} }}",
+                name,
+                json_to_candid(env, value, &field.ty)?
+            )
+        }
+        _ => return Err(anyhow!("Unsupported type {}", ty)),
+    })
+}
+
+fn label_name(label: &Label) -> String {
+    match label {
+        Label::Named(name) => name.clone(),
+        label => label.to_string(),
+    }
+}
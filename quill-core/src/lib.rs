@@ -0,0 +1,413 @@
+//! The core of quill: building, signing and verifying messages to the NNS and
+//! SNS canisters, the bundle formats of signed messages, the NNS types, and
+//! the transport of the messages to the IC.
+//!
+//! Exchanges and wallet backends can embed it to sign messages without
+//! running the quill binary: an agent built with [`get_agent_with_transport`]
+//! and a [`sign::sign_transport::SignReplicaV2Transport`] signs the calls made
+//! with it instead of sending them, the signed messages are bundled with
+//! [`bundle::Bundle`], and checked with [`envelope::verify`] before they are
+//! sent. The types of the NNS canisters are in [`governance`] and [`icrc1`].
+//! [`Identity`] loads keys like quill and can sign for any ic-agent based tool.
+//!
+//! The formats of signed messages, [`SignedMessageV1`], [`SignedMessageV2`],
+//! [`Bundle`] and [`Copies`], are re-exported here and follow semantic
+//! versioning: files written by earlier versions stay readable with
+//! [`Bundle::from_json`]. [`SignedMessageV1`] and its parts never change;
+//! within a major version of quill-core, the other formats only gain optional
+//! fields, so their structs are `#[non_exhaustive]` and built with their
+//! constructors. Other changes come with a new format version.
+//!
+//! Sending requests to the IC, and building calls with an agent, needs the
+//! default `transport` feature, which brings in ic-agent, tokio and reqwest;
+//! signers without network access can leave it out. Request ids, envelopes
+//! and their signatures are computed by quill-core itself, see [`envelope`]. Signatures are verified with OpenSSL, the default `openssl` feature,
+//! or in pure Rust with the `pure-rust` feature, which also signs; see
+//! [`crypto`]. Without both, e.g. for `wasm32-unknown-unknown`, messages are
+//! built and hashed but not verified.
+
+use anyhow::anyhow;
+use candid::{
+    parser::typing::{check_prog, TypeEnv},
+    types::Function,
+    IDLProg,
+};
+use error::QuillError;
+#[cfg(feature = "transport")]
+use ic_agent::{agent::ReplicaV2Transport, Agent, NonceFactory};
+use ic_types::Principal;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+pub const IC_URL: &str = "https://ic0.app";
+
+// The URL used instead of `IC_URL`, e.g. set by the configuration.
+static IC_URL_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn set_ic_url(url: String) {
+    *IC_URL_OVERRIDE.write().unwrap() = Some(url);
+}
+
+/// Returns the URL of the IC replica which messages are sent to, the first
+/// one if a list of endpoints was given.
+pub fn ic_url() -> String {
+    ic_urls().remove(0)
+}
+
+/// Returns the URLs of the endpoints of the IC, which were given as a
+/// comma-separated list and are failed over in order.
+pub fn ic_urls() -> Vec<String> {
+    IC_URL_OVERRIDE
+        .read()
+        .unwrap()
+        .as_deref()
+        .unwrap_or(IC_URL)
+        .split(',')
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .collect()
+}
+
+// Set by the global `--offline` flag, which forbids all network access.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Fails if network access was forbidden with `--offline`.
+pub fn require_network() -> AnyhowResult {
+    if OFFLINE.load(Ordering::Relaxed) {
+        return Err(QuillError::Network(
+            "This command needs network access, which --offline forbids".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub mod aliases;
+pub mod bundle;
+pub mod certificate;
+pub mod config;
+pub mod crypto;
+pub mod dfx;
+pub mod envelope;
+pub mod error;
+pub mod fingerprint;
+#[cfg(feature = "frames")]
+pub mod frames;
+pub mod governance;
+pub mod governance_enums;
+#[cfg(feature = "transport")]
+pub mod http_transport;
+pub mod icrc1;
+pub mod identity;
+pub mod json_candid;
+#[cfg(feature = "transport")]
+pub mod logging;
+#[cfg(feature = "plugin")]
+pub mod plugin;
+pub mod proxy;
+pub mod rates;
+pub mod request_id;
+#[cfg(feature = "transport")]
+pub mod retry;
+#[cfg(feature = "transport")]
+pub mod root_key;
+pub mod sign;
+#[cfg(feature = "sns")]
+pub mod sns_governance;
+pub mod unsigned;
+
+pub use bundle::{Bundle, BundledMessage, Copies, BUNDLE_VERSION};
+pub use identity::Identity;
+pub use sign::signed_message::{
+    BundleLink, Ingress, IngressWithRequestId, MessageSummary, RequestStatus, SignedMessageV1,
+    SignedMessageV2, SIGNED_MESSAGE_V2,
+};
+
+pub type AnyhowResult<T = ()> = anyhow::Result<T>;
+
+/// Serializes a value as canonical JSON: the keys of all objects are sorted
+/// and there is no whitespace, so equal values always print the same bytes.
+pub fn to_canonical_json<T: ?Sized + serde::Serialize>(value: &T) -> AnyhowResult<String> {
+    Ok(serde_json::to_string(&sort_keys(serde_json::to_value(
+        value,
+    )?))?)
+}
+
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(sort_keys).collect())
+        }
+        value => value,
+    }
+}
+
+// The ids of the NNS canisters, ryjl3-tyaaa-aaaaa-aaaba-cai,
+// rrkah-fqaaa-aaaaa-aaaaq-cai and rkp4c-7iaaa-aaaaa-aaaca-cai.
+const LEDGER_CANISTER_ID: &[u8] = &[0, 0, 0, 0, 0, 0, 0, 2, 1, 1];
+const GOVERNANCE_CANISTER_ID: &[u8] = &[0, 0, 0, 0, 0, 0, 0, 1, 1, 1];
+const CYCLES_MINTING_CANISTER_ID: &[u8] = &[0, 0, 0, 0, 0, 0, 0, 4, 1, 1];
+
+pub fn ledger_canister_id() -> Principal {
+    Principal::from_slice(LEDGER_CANISTER_ID)
+}
+
+pub fn governance_canister_id() -> Principal {
+    Principal::from_slice(GOVERNANCE_CANISTER_ID)
+}
+
+pub fn cycles_minting_canister_id() -> Principal {
+    Principal::from_slice(CYCLES_MINTING_CANISTER_ID)
+}
+
+// The interfaces of the SNS canisters and other ICRC-1 ledgers, which don't
+// have fixed canister ids.
+const SNS_CANDIDS: &[&str] = &[
+    include_str!("../candid/icrc1.did"),
+    #[cfg(feature = "sns")]
+    sns_governance::CANDID,
+    #[cfg(feature = "sns")]
+    include_str!("../candid/sns_swap.did"),
+];
+
+// Returns the candid for the specified canister id, if there is one. All
+// interfaces are embedded or cached by `fetch-did`, so messages and responses
+// are decoded offline. The interfaces of SNS canisters are looked up by the
+// method name instead.
+pub fn get_local_candid(canister_id: Principal, method_name: &str) -> AnyhowResult<String> {
+    let cached = cached_candid_path(canister_id)
+        .ok()
+        .filter(|path| path.exists());
+    if let Some(cached) = cached {
+        std::fs::read_to_string(&cached)
+            .map_err(|err| anyhow!("Couldn't read {}: {}", cached.display(), err))
+    } else if canister_id == governance_canister_id() {
+        String::from_utf8(include_bytes!("../candid/governance.did").to_vec())
+            .map_err(|e| anyhow!(e))
+    } else if canister_id == ledger_canister_id() {
+        String::from_utf8(include_bytes!("../candid/ledger.did").to_vec()).map_err(|e| anyhow!(e))
+    } else if canister_id == cycles_minting_canister_id() {
+        Ok(include_str!("../candid/cmc.did").to_string())
+    } else {
+        SNS_CANDIDS
+            .iter()
+            .find(|spec| get_candid_type(spec.to_string(), method_name).is_some())
+            .map(|spec| spec.to_string())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No candid interface found for method {} of canister {}",
+                    method_name,
+                    canister_id
+                )
+            })
+    }
+}
+
+/// Returns the path of the candid interface of a canister cached by `fetch-did`.
+pub fn cached_candid_path(canister_id: Principal) -> AnyhowResult<PathBuf> {
+    Ok(get_config_dir()?
+        .join("candid")
+        .join(format!("{}.did", canister_id)))
+}
+
+/// Returns pretty-printed encoding of a candid value. Values which can't be
+/// decoded with the known interface are printed without type information.
+pub fn get_idl_string(
+    blob: &[u8],
+    canister_id: Principal,
+    method_name: &str,
+    part: &str,
+) -> AnyhowResult<String> {
+    let method_type = get_local_candid(canister_id, method_name)
+        .ok()
+        .and_then(|spec| get_candid_type(spec, method_name));
+    let result = match method_type {
+        None => candid::IDLArgs::from_bytes(blob),
+        Some((env, func)) => candid::IDLArgs::from_bytes_with_types(
+            blob,
+            &env,
+            if part == "args" {
+                &func.args
+            } else {
+                &func.rets
+            },
+        )
+        .or_else(|_| candid::IDLArgs::from_bytes(blob)),
+    };
+    let mut args = result?;
+    if canister_id == governance_canister_id() {
+        args = governance_enums::name_values(args);
+    }
+    Ok(format!("{}", args))
+}
+
+/// Returns a human-readable decoding of a response, falling back to the
+/// pretty-printed candid value.
+pub fn get_response_string(
+    blob: &[u8],
+    canister_id: Principal,
+    method_name: &str,
+) -> AnyhowResult<String> {
+    if canister_id == governance_canister_id() {
+        if let Some(description) = governance::describe_response(blob, method_name) {
+            return Ok(description);
+        }
+    }
+    #[cfg(feature = "sns")]
+    if canister_id != governance_canister_id() && canister_id != ledger_canister_id() {
+        if let Some(description) = sns_governance::describe_response(blob, method_name) {
+            return Ok(description);
+        }
+    }
+    get_idl_string(blob, canister_id, method_name, "rets")
+}
+
+/// Returns the candid type of a specifed method and correspondig idl description.
+pub fn get_candid_type(idl: String, method_name: &str) -> Option<(TypeEnv, Function)> {
+    let ast = candid::pretty_parse::<IDLProg>("/dev/null", &idl).ok()?;
+    let mut env = TypeEnv::new();
+    let actor = check_prog(&mut env, &ast).ok()?;
+    let method = env.get_method(&actor?, method_name).ok()?.clone();
+    Some((env, method))
+}
+
+/// Reads from the file path or STDIN and returns the content.
+pub fn read_from_file(path: &str) -> AnyhowResult<String> {
+    use std::io::Read;
+    let mut content = String::new();
+    if path == "-" {
+        std::io::stdin().read_to_string(&mut content)?;
+    } else {
+        let path = std::path::Path::new(&path);
+        let mut file = std::fs::File::open(&path)
+            .map_err(|_| QuillError::Validation("Message file doesn't exist".to_string()))?;
+        file.read_to_string(&mut content)
+            .map_err(|_| QuillError::Validation("Cannot read the message file.".to_string()))?;
+    }
+    Ok(content)
+}
+
+/// Reads raw bytes from the file path or STDIN.
+pub fn read_bytes_from_file(path: &str) -> AnyhowResult<Vec<u8>> {
+    use std::io::Read;
+    let mut content = Vec::new();
+    if path == "-" {
+        std::io::stdin().read_to_end(&mut content)?;
+    } else {
+        content = std::fs::read(path)
+            .map_err(|_| QuillError::Validation("Message file doesn't exist".to_string()))?;
+    }
+    Ok(content)
+}
+
+/// Returns the quill configuration directory, `$QUILL_CONFIG_DIR` or `~/.config/quill`.
+pub fn get_config_dir() -> AnyhowResult<PathBuf> {
+    if let Ok(dir) = std::env::var("QUILL_CONFIG_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map_err(|_| anyhow!("Couldn't determine the home directory"))?;
+    Ok(PathBuf::from(home).join(".config").join("quill"))
+}
+
+/// Returns an agent with an identity derived from a private key if it was provided.
+#[cfg(feature = "transport")]
+pub fn get_agent(pem: &Option<String>) -> AnyhowResult<Agent> {
+    require_network()?;
+    get_agent_with_transport(
+        pem,
+        std::time::Duration::from_secs(60 * 5),
+        http_transport::HttpTransport::create()?,
+    )
+}
+
+/// Returns an agent which sends its requests to the given transport, e.g. one
+/// which only records the signed messages without any network access.
+#[cfg(feature = "transport")]
+pub fn get_agent_with_transport<T>(
+    pem: &Option<String>,
+    timeout: std::time::Duration,
+    transport: T,
+) -> AnyhowResult<Agent>
+where
+    T: 'static + ReplicaV2Transport + Send + Sync,
+{
+    let identity = pem.as_deref().map(get_identity).transpose()?;
+    get_agent_with_identity(identity, timeout, transport)
+}
+
+/// Like [`get_agent_with_transport`], but with the given identity, e.g. an
+/// [`Identity::External`] which leaves the messages unsigned. Without one, the
+/// agent is anonymous.
+#[cfg(feature = "transport")]
+pub fn get_agent_with_identity<T>(
+    identity: Option<Identity>,
+    timeout: std::time::Duration,
+    transport: T,
+) -> AnyhowResult<Agent>
+where
+    T: 'static + ReplicaV2Transport + Send + Sync,
+{
+    build_agent(identity, NonceFactory::random(), timeout, transport)
+}
+
+/// Like [`get_agent_with_identity`], but every call gets the given nonce
+/// instead of a random one, so that signing the same call at the same time
+/// gives the same message, e.g. for test vectors.
+#[cfg(feature = "transport")]
+pub fn get_agent_with_nonce<T>(
+    identity: Option<Identity>,
+    nonce: Vec<u8>,
+    timeout: std::time::Duration,
+    transport: T,
+) -> AnyhowResult<Agent>
+where
+    T: 'static + ReplicaV2Transport + Send + Sync,
+{
+    let nonces = NonceFactory::from_iterator(Box::new(std::iter::repeat(nonce)));
+    build_agent(identity, nonces, timeout, transport)
+}
+
+#[cfg(feature = "transport")]
+fn build_agent<T>(
+    identity: Option<Identity>,
+    nonces: NonceFactory,
+    timeout: std::time::Duration,
+    transport: T,
+) -> AnyhowResult<Agent>
+where
+    T: 'static + ReplicaV2Transport + Send + Sync,
+{
+    let builder = Agent::builder()
+        .with_transport(transport)
+        .with_nonce_factory(nonces)
+        .with_ingress_expiry(Some(timeout));
+
+    match identity {
+        Some(identity) => builder.with_identity(identity),
+        None => builder,
+    }
+    .build()
+    .map_err(|err| anyhow!(err))
+}
+
+/// Returns an identity derived from the private key, or an identity error if
+/// it can't be loaded.
+pub fn get_identity(pem: &str) -> AnyhowResult<Identity> {
+    Identity::from_pem(pem)
+}
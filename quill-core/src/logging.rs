@@ -0,0 +1,35 @@
+//! Diagnostic logs of the requests sent to the IC, enabled with `-v`.
+
+use crate::AnyhowResult;
+use anyhow::Context;
+use std::fs::OpenOptions;
+use std::sync::Mutex;
+use tracing::Level;
+
+/// Writes the logs to STDERR, or appends them to the given file. One `-v` logs
+/// the submitted messages and their outcome, two also log every HTTP request.
+pub fn init(verbosity: u64, file: Option<&str>) -> AnyhowResult {
+    let level = match verbosity {
+        0 => return Ok(()),
+        1 => Level::INFO,
+        _ => Level::DEBUG,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false);
+    match file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Couldn't open the log file {}", path))?;
+            builder
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .init()
+        }
+        None => builder.with_writer(std::io::stderr).init(),
+    }
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Plugins, which add subcommands for other canisters. A plugin is a TOML
+//! manifest `<name>.toml` in the `plugins` directory of the config directory,
+//! which names the canister, its candid interface and the operations that
+//! become the subcommands of `quill <name>`:
+//!
+//! ```toml
+//! canister_id = "qoctq-giaaa-aaaaa-aaaea-cai"
+//! candid = "vault.did"
+//!
+//! [operations.deposit]
+//! method = "deposit"
+//! args = '(record { amount = ${amount} : nat64; memo = "${memo}" })'
+//! description = "Deposits into the vault"
+//! ```
+//!
+//! Each `${name}` of the arguments becomes the option `--name` of the operation.
+//! Within a text literal, like `"${memo}"`, the value is text; anywhere else it
+//! has to be a single candid value, like `5` or `principal "aaaaa-aa"`.
+
+use crate::{error::QuillError, get_config_dir, get_local_candid, AnyhowResult};
+use anyhow::Context;
+use candid::IDLArgs;
+use ic_types::Principal;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const PLUGINS_DIR: &str = "plugins";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub canister_id: Principal,
+    /// The path of the candid interface, relative to the manifest. By default
+    /// the interface cached with `fetch-did` is used.
+    pub candid: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub operations: BTreeMap<String, Operation>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Operation {
+    pub method: String,
+    /// The candid arguments, with `${name}` for the values of the options.
+    #[serde(default = "no_args")]
+    pub args: String,
+    pub description: Option<String>,
+    /// Signs a query instead of an update call.
+    #[serde(default)]
+    pub query: bool,
+}
+
+fn no_args() -> String {
+    "()".to_string()
+}
+
+/// Returns the names of the installed plugins.
+pub fn list() -> AnyhowResult<Vec<String>> {
+    let dir = get_config_dir()?.join(PLUGINS_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "toml")
+        {
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+impl Manifest {
+    /// Loads the manifest of the plugin with the given name.
+    pub fn load(name: &str) -> AnyhowResult<Self> {
+        let path = get_config_dir()?
+            .join(PLUGINS_DIR)
+            .join(format!("{}.toml", name));
+        if name.contains(|c: char| c == '/' || c == '\\' || c == '.') || !path.exists() {
+            let plugins = list()?;
+            return Err(QuillError::Validation(format!(
+                "Unknown command or plugin {}, the plugins are: {}",
+                name,
+                if plugins.is_empty() {
+                    "none".to_string()
+                } else {
+                    plugins.join(", ")
+                }
+            ))
+            .into());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        let mut manifest: Manifest = toml::from_str(&content).map_err(|err| {
+            QuillError::Validation(format!("Couldn't parse {}: {}", path.display(), err))
+        })?;
+        manifest.path = path;
+        Ok(manifest)
+    }
+
+    /// Returns the candid interface of the canister.
+    pub fn candid(&self, method_name: &str) -> AnyhowResult<String> {
+        match &self.candid {
+            Some(candid) => {
+                let path = self
+                    .path
+                    .parent()
+                    .map_or_else(|| PathBuf::from(candid), |dir| dir.join(candid));
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Couldn't read {}", path.display()))
+            }
+            None => get_local_candid(self.canister_id, method_name),
+        }
+    }
+}
+
+impl Operation {
+    /// Returns the names of the values in the arguments, in their order.
+    pub fn params(&self) -> Vec<String> {
+        let mut params = Vec::new();
+        let mut rest = self.args.as_str();
+        while let Some(start) = rest.find("${") {
+            rest = &rest[start + 2..];
+            if let Some(end) = rest.find('}') {
+                let name = rest[..end].to_string();
+                if !params.contains(&name) {
+                    params.push(name);
+                }
+                rest = &rest[end + 1..];
+            }
+        }
+        params
+    }
+
+    /// Returns the arguments with the values filled in, in a single pass so
+    /// that values are never expanded again. Values in text literals are
+    /// escaped, and the others are checked to be single candid values, so
+    /// that no value can change the rest of the arguments.
+    pub fn fill(&self, values: &BTreeMap<String, String>) -> AnyhowResult<String> {
+        let mut args = String::new();
+        let mut in_text = false;
+        let mut chars = self.args.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' if in_text => {
+                    args.push(c);
+                    if let Some((_, escaped)) = chars.next() {
+                        args.push(escaped);
+                    }
+                }
+                '"' => {
+                    in_text = !in_text;
+                    args.push(c);
+                }
+                '$' if self.args[i..].starts_with("${") => {
+                    let end = match self.args[i..].find('}') {
+                        Some(end) => i + end,
+                        None => {
+                            args.push_str(&self.args[i..]);
+                            break;
+                        }
+                    };
+                    let name = &self.args[i + 2..end];
+                    let value = values.get(name).map(String::as_str).unwrap_or_default();
+                    if in_text {
+                        args.push_str(&escape_text(value));
+                    } else {
+                        args.push_str(&candid_value(name, value)?);
+                    }
+                    while chars.peek().map_or(false, |(j, _)| *j <= end) {
+                        chars.next();
+                    }
+                }
+                _ => args.push(c),
+            }
+        }
+        Ok(args)
+    }
+}
+
+// Escapes text for a candid text literal.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Returns the value as candid text if it is a single candid value.
+fn candid_value(name: &str, value: &str) -> AnyhowResult<String> {
+    match format!("({})", value).parse::<IDLArgs>() {
+        Ok(IDLArgs { args }) if args.len() == 1 => Ok(args[0].to_string()),
+        _ => Err(QuillError::Validation(format!(
+            "The value of --{} isn't a single candid value: {}",
+            name, value
+        ))
+        .into()),
+    }
+}
@@ -0,0 +1,41 @@
+//! The proxy of all requests to the IC. The HTTP client of the agent reads it
+//! from the standard environment variables, so an explicit proxy is set there.
+
+use crate::{error::QuillError, AnyhowResult};
+
+const SCHEMES: &[&str] = &["http://", "https://", "socks5://", "socks5h://"];
+
+/// Sets the proxy given with `--proxy`, or else the one of `ALL_PROXY` if
+/// neither `HTTPS_PROXY` nor `HTTP_PROXY` is set, for all requests.
+pub fn configure(proxy: Option<&str>) -> AnyhowResult {
+    let proxy = match proxy {
+        Some(proxy) => proxy.to_string(),
+        None if env_var("HTTPS_PROXY").is_some() || env_var("HTTP_PROXY").is_some() => {
+            return Ok(())
+        }
+        None => match env_var("ALL_PROXY") {
+            Some(proxy) => proxy,
+            None => return Ok(()),
+        },
+    };
+    if !SCHEMES.iter().any(|scheme| proxy.starts_with(scheme)) {
+        return Err(QuillError::Validation(format!(
+            "Invalid proxy {}, it must start with one of {}",
+            proxy,
+            SCHEMES.join(", ")
+        ))
+        .into());
+    }
+    for name in &["HTTPS_PROXY", "HTTP_PROXY"] {
+        std::env::set_var(name, &proxy);
+    }
+    Ok(())
+}
+
+// Returns the value of a variable, in upper or lower case, if it's set.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .or_else(|_| std::env::var(name.to_lowercase()))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
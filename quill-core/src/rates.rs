@@ -0,0 +1,42 @@
+//! Exchange rates from a local file, used to estimate the fiat value of
+//! amounts without network access.
+
+use crate::{error::QuillError, AnyhowResult};
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The rates file, e.g. `{"symbol": "$", "rates": {"ICP": 10.5}}`.
+#[derive(Deserialize)]
+pub struct Rates {
+    /// The symbol of the fiat currency.
+    pub symbol: String,
+    /// The price of one token in the fiat currency, by token symbol.
+    pub rates: BTreeMap<String, f64>,
+}
+
+impl Rates {
+    pub fn load(path: &str) -> AnyhowResult<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read the rates file {}", path))?;
+        serde_json::from_str(&content).map_err(|err| {
+            QuillError::Validation(format!("Invalid rates file {}: {}", path, err)).into()
+        })
+    }
+
+    /// Returns the estimated value of the amount, e.g. "~$1.3M", or `None` if
+    /// there's no rate for the token.
+    pub fn estimate(&self, token: &str, amount: f64) -> Option<String> {
+        let value = amount * self.rates.get(token)?;
+        let (value, suffix) = if value >= 1e9 {
+            (value / 1e9, "B")
+        } else if value >= 1e6 {
+            (value / 1e6, "M")
+        } else if value >= 1e3 {
+            (value / 1e3, "K")
+        } else {
+            return Some(format!("~{}{:.2}", self.symbol, value));
+        };
+        Some(format!("~{}{:.1}{}", self.symbol, value, suffix))
+    }
+}
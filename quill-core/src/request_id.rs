@@ -0,0 +1,61 @@
+//! Request ids, the representation-independent hashes of the content of
+//! requests. They are computed by quill-core itself, so that messages are
+//! hashed and checked without the agent of the `transport` feature.
+
+use crate::error::QuillError;
+use std::fmt;
+use std::str::FromStr;
+
+/// The id of a request, the SHA-256 hash of its content map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId([u8; 32]);
+
+impl RequestId {
+    pub fn new(hash: [u8; 32]) -> Self {
+        RequestId(hash)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for RequestId {
+    type Err = QuillError;
+
+    /// Reads the hex of a request id, with or without `0x`.
+    fn from_str(text: &str) -> Result<Self, QuillError> {
+        let mut hash = [0; 32];
+        hex::decode_to_slice(text.trim_start_matches("0x"), &mut hash)
+            .map_err(|_| QuillError::Validation(format!("Invalid request id: {}", text)))?;
+        Ok(RequestId(hash))
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl From<RequestId> for String {
+    fn from(request_id: RequestId) -> Self {
+        request_id.to_string()
+    }
+}
+
+#[cfg(feature = "transport")]
+impl From<ic_agent::RequestId> for RequestId {
+    fn from(request_id: ic_agent::RequestId) -> Self {
+        let mut hash = [0; 32];
+        hash.copy_from_slice(request_id.as_slice());
+        RequestId(hash)
+    }
+}
+
+#[cfg(feature = "transport")]
+impl From<RequestId> for ic_agent::RequestId {
+    fn from(request_id: RequestId) -> Self {
+        ic_agent::RequestId::new(&request_id.0)
+    }
+}
@@ -0,0 +1,74 @@
+//! Retries of requests to the IC which failed with transient errors.
+
+use crate::http_transport::rate_limit_delay;
+use ic_agent::AgentError;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+// The delay before the first retry, which doubles with every further retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// The kind of a request, which determines the errors it's retried after.
+#[derive(Clone, Copy, PartialEq)]
+pub enum RequestKind {
+    /// Queries and `read_state` requests, which change nothing and are retried
+    /// after rate limiting, server and connection errors.
+    Read,
+    /// The submission of a signed message, which is only retried when the
+    /// replica didn't take it (rate limiting, unavailability) or the
+    /// connection failed. The IC ignores duplicates of a signed message.
+    Submission,
+}
+
+fn is_transient(err: &AgentError, kind: RequestKind) -> bool {
+    match err {
+        AgentError::HttpError(payload) => match payload.status {
+            429 | 503 => true,
+            500..=599 => kind == RequestKind::Read,
+            _ => false,
+        },
+        AgentError::TransportError(_) => true,
+        _ => false,
+    }
+}
+
+/// Sends the request, and sends it again up to `retries` times with
+/// exponential backoff as long as it fails with a transient error. After
+/// rate limiting, it waits at least as long as the Retry-After header asked.
+pub async fn retry<T, F, Fut>(
+    kind: RequestKind,
+    retries: u32,
+    mut request: F,
+) -> Result<T, AgentError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AgentError>>,
+{
+    let mut backoff = INITIAL_DELAY;
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(err) if attempt < retries && is_transient(&err, kind) => {
+                attempt += 1;
+                let delay = backoff.max(rate_limit_delay().unwrap_or_default());
+                warn!(attempt, error = %err, "Retrying the request");
+                let what = match kind {
+                    RequestKind::Read => "The request",
+                    RequestKind::Submission => "The submission of the message",
+                };
+                eprintln!(
+                    "{} failed ({}), retrying in {:.1}s ({} of {})",
+                    what,
+                    err,
+                    delay.as_secs_f64(),
+                    attempt,
+                    retries
+                );
+                tokio::time::sleep(delay).await;
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! The root key of the IC, which certifies the responses to update calls.
+
+use crate::{error::QuillError, ic_url, ic_urls, AnyhowResult};
+use ic_agent::Agent;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The DER-encoded root public key of the IC mainnet.
+const IC_ROOT_KEY: &str = "308182301d060d2b0601040182dc7c0503010201060c2b0601040182dc7c05030201036100814c0e6ec71fab583b08bd81373c255c3c371b2e84863c98a4f1e08b74235d14fb5d9c0cd546d9685f913a0c0b2cc5341583bf4b4392e467db96d65b9bb4cb717112f8472e0d5a4d14505ffd7484b01291091c5f87b98883463f98091a0baaae";
+
+// Set by `--fetch-root-key` (or its old name `--insecure-local-dev-env`) or a
+// network whose root key is fetched, like `--network local`; it applies to all
+// commands.
+static FETCH_ROOT_KEY: AtomicBool = AtomicBool::new(false);
+
+// Set once the key reported by the replica was checked.
+static VERIFIED: AtomicBool = AtomicBool::new(false);
+
+// The domains of the boundary nodes of the IC mainnet.
+const MAINNET_DOMAINS: [&str; 3] = ["ic0.app", "icp0.io", "icp-api.io"];
+
+/// Makes quill fetch and trust the root key of the replica instead of only the
+/// one of the mainnet, for local replicas and dfx networks. It is refused for
+/// the endpoints of the mainnet, where a spoofed endpoint could forge any
+/// response with its own key.
+pub fn set_fetch_root_key() -> AnyhowResult {
+    for url in ic_urls() {
+        let host = reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        if MAINNET_DOMAINS
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+        {
+            return Err(QuillError::Validation(format!(
+                "--fetch-root-key is unsafe for the IC mainnet and refused for {}",
+                url
+            ))
+            .into());
+        }
+    }
+    FETCH_ROOT_KEY.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Returns true if the root key reported by the replica is trusted even if it
+/// isn't the one of the mainnet.
+pub fn trusts_reported_key() -> bool {
+    FETCH_ROOT_KEY.load(Ordering::Relaxed)
+}
+
+/// Returns true if the DER-encoded key is the root key of the IC mainnet.
+pub fn is_mainnet_key(key: &[u8]) -> bool {
+    hex::decode(IC_ROOT_KEY).map_or(false, |mainnet_key| key == mainnet_key.as_slice())
+}
+
+/// Checks that the replica reports the root key of the mainnet, so that a
+/// spoofed endpoint is noticed before anything is sent to it. Any other key is
+/// only accepted with `--fetch-root-key`.
+pub async fn verify(agent: &Agent) -> AnyhowResult {
+    if VERIFIED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let status = agent.status().await.map_err(|err| {
+        QuillError::Network(format!("Couldn't get the status of {}: {}", ic_url(), err))
+    })?;
+    // Replicas which don't report their key are checked by the certificates.
+    if let Some(key) = status.root_key {
+        if !is_mainnet_key(&key) {
+            if !trusts_reported_key() {
+                return Err(QuillError::Network(format!(
+                    "The replica at {} reports a root key which isn't the one of the IC mainnet; \
+                     use --fetch-root-key only if it is a local or test replica you trust",
+                    ic_url()
+                ))
+                .into());
+            }
+            eprintln!(
+                "Warning: trusting the root key reported by {}, which isn't the one of the IC mainnet.",
+                ic_url()
+            );
+        }
+    }
+    VERIFIED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Makes the agent verify certificates with the key reported by the replica,
+/// if that was allowed with `--fetch-root-key`. Otherwise the agent keeps the
+/// key of the mainnet it is built with.
+pub async fn trust(agent: &Agent) -> AnyhowResult {
+    if trusts_reported_key() {
+        agent.fetch_root_key().await.map_err(|err| {
+            QuillError::Network(format!(
+                "Couldn't fetch the root key of {}: {}",
+                ic_url(),
+                err
+            ))
+        })?;
+    }
+    Ok(())
+}
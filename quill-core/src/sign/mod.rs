@@ -1,4 +1,5 @@
 //! This module provides all the infrastructure for the messaing signing.
 
+#[cfg(feature = "transport")]
 pub mod sign_transport;
 pub mod signed_message;
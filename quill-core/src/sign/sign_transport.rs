@@ -1,4 +1,4 @@
-use crate::lib::sign::signed_message::{Ingress, RequestStatus};
+use crate::sign::signed_message::{Ingress, RequestStatus};
 use ic_agent::agent::ReplicaV2Transport;
 use ic_agent::{AgentError, RequestId};
 use ic_types::Principal;
@@ -101,7 +101,7 @@ fn run(s: &SignReplicaV2Transport, envelope: Vec<u8>, request_id: Option<Request
     let message = match request_id {
         Some(request_id) => message
             .with_call_type("update".to_string())
-            .with_request_id(request_id),
+            .with_request_id(request_id.into()),
         None => message.with_call_type("query".to_string()),
     };
     let mut data = s.data.write().unwrap();
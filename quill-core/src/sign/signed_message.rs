@@ -0,0 +1,333 @@
+use crate::{crypto::sha256, error::QuillError, request_id::RequestId, AnyhowResult};
+use crate::{get_idl_string, get_response_string};
+use anyhow::anyhow;
+use chrono::{DateTime, TimeZone, Utc};
+use ic_types::principal::Principal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// The signed `read_state` request for the status of a call.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct RequestStatus {
+    pub canister_id: String,
+    pub request_id: String,
+    pub content: String,
+}
+
+/// A signed call or query, with its hex-encoded CBOR envelope in `content`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct Ingress {
+    pub call_type: String,
+    pub request_id: Option<String>,
+    pub content: String,
+}
+
+/// A signed call with the request for its status, as printed by default.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct IngressWithRequestId {
+    pub ingress: Ingress,
+    pub request_status: RequestStatus,
+}
+
+/// The first format of signed messages, which `--message-version 1` prints.
+pub type SignedMessageV1 = IngressWithRequestId;
+
+pub const SIGNED_MESSAGE_V2: u32 = 2;
+
+/// A signed message with the metadata needed to audit it without decoding
+/// the envelope: a hash of its content, the quill version which signed it, a
+/// readable summary and its position in the bundle it was signed with.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct SignedMessageV2 {
+    pub version: u32,
+    pub quill_version: String,
+    /// The hex-encoded SHA-256 hash of the CBOR envelope of the ingress message.
+    pub content_sha256: String,
+    pub summary: MessageSummary,
+    pub bundle: BundleLink,
+    pub ingress: Ingress,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_status: Option<RequestStatus>,
+}
+
+/// The decoded fields of a message, for humans reading the file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct MessageSummary {
+    pub sender: String,
+    pub canister_id: String,
+    pub method_name: String,
+    pub arguments: String,
+    pub ingress_expiry: String,
+}
+
+/// The position of a message in the bundle it was signed with.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub struct BundleLink {
+    /// The checksum of the bundle, which is the same for all its messages.
+    pub checksum: String,
+    pub index: usize,
+    pub depends_on: Vec<usize>,
+}
+
+impl BundleLink {
+    pub fn new(checksum: String, index: usize, depends_on: Vec<usize>) -> Self {
+        Self {
+            checksum,
+            index,
+            depends_on,
+        }
+    }
+}
+
+impl SignedMessageV2 {
+    pub fn new(
+        ingress: Ingress,
+        request_status: Option<RequestStatus>,
+        bundle: BundleLink,
+    ) -> AnyhowResult<Self> {
+        Ok(Self {
+            version: SIGNED_MESSAGE_V2,
+            quill_version: env!("CARGO_PKG_VERSION").to_string(),
+            content_sha256: hex::encode(sha256(&hex::decode(&ingress.content)?)),
+            summary: MessageSummary::new(&ingress)?,
+            bundle,
+            ingress,
+            request_status,
+        })
+    }
+
+    /// Checks that the hash and the summary match the content of the message.
+    pub fn validate(&self) -> AnyhowResult {
+        if self.version != SIGNED_MESSAGE_V2 {
+            return Err(QuillError::Validation(format!(
+                "Unsupported message version {}",
+                self.version
+            ))
+            .into());
+        }
+        if hex::encode(sha256(&hex::decode(&self.ingress.content)?)) != self.content_sha256 {
+            return Err(QuillError::Validation(format!(
+                "The content hash of message {} doesn't match its content",
+                self.bundle.index
+            ))
+            .into());
+        }
+        let mut summary = MessageSummary::new(&self.ingress)?;
+        // Other versions may render the arguments differently.
+        if self.quill_version != env!("CARGO_PKG_VERSION") {
+            summary.arguments = self.summary.arguments.clone();
+        }
+        if summary != self.summary {
+            return Err(QuillError::Validation(format!(
+                "The summary of message {} doesn't match its content",
+                self.bundle.index
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl MessageSummary {
+    fn new(ingress: &Ingress) -> AnyhowResult<Self> {
+        let (expiration, sender, canister_id, method_name, arguments) = ingress.inspect()?;
+        Ok(Self {
+            sender: sender.to_text(),
+            canister_id: canister_id.to_text(),
+            method_name,
+            arguments,
+            ingress_expiry: expiration.to_rfc3339(),
+        })
+    }
+}
+
+impl Ingress {
+    pub fn with_call_type(mut self, request_type: String) -> Self {
+        self.call_type = request_type;
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: RequestId) -> Self {
+        self.request_id = Some(String::from(request_id));
+        self
+    }
+
+    pub fn with_content(mut self, content: String) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn parse(&self) -> AnyhowResult<(Principal, Principal, String, String)> {
+        let (expiration, sender, canister_id, method_name, args) = self.inspect()?;
+        if Utc::now() > expiration {
+            return Err(QuillError::Validation(format!(
+                "The message has been expired at: {}",
+                expiration
+            ))
+            .into());
+        }
+        Ok((sender, canister_id, method_name, args))
+    }
+
+    /// Like `parse`, but also returns the ingress expiry and accepts expired messages.
+    pub fn inspect(&self) -> AnyhowResult<(DateTime<Utc>, Principal, Principal, String, String)> {
+        let cbor: Value = serde_cbor::from_slice(&hex::decode(&self.content)?)
+            .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+        if let Value::Map(m) = cbor {
+            let cbor_content = m
+                .get(&Value::Text("content".to_string()))
+                .ok_or_else(|| anyhow!("Invalid cbor content"))?;
+            if let Value::Map(m) = cbor_content {
+                if let (
+                    Some(Value::Integer(ingress_expiry)),
+                    Some(Value::Bytes(sender)),
+                    Some(Value::Bytes(canister_id)),
+                    Some(Value::Text(method_name)),
+                    Some(Value::Bytes(arg)),
+                ) = (
+                    m.get(&Value::Text("ingress_expiry".to_string())),
+                    m.get(&Value::Text("sender".to_string())),
+                    m.get(&Value::Text("canister_id".to_string())),
+                    m.get(&Value::Text("method_name".to_string())),
+                    m.get(&Value::Text("arg".to_string())),
+                ) {
+                    let seconds_since_epoch_cbor =
+                        Duration::from_nanos(*ingress_expiry as u64).as_secs();
+                    let expiration_from_cbor = Utc.timestamp(seconds_since_epoch_cbor as i64, 0);
+                    let sender = Principal::try_from(sender)?;
+                    let canister_id = Principal::try_from(canister_id)?;
+                    return Ok((
+                        expiration_from_cbor,
+                        sender,
+                        canister_id,
+                        method_name.to_string(),
+                        get_idl_string(arg, canister_id, method_name, "args")?,
+                    ));
+                }
+            }
+        }
+        Err(anyhow!("Invalid cbor content"))
+    }
+}
+
+pub fn parse_query_response(
+    response: Vec<u8>,
+    canister_id: Principal,
+    method_name: &str,
+) -> AnyhowResult<String> {
+    match get_query_reply(response)? {
+        Ok(reply) => get_response_string(&reply, canister_id, method_name),
+        Err(rejection) => Ok(rejection),
+    }
+}
+
+/// Returns the reply blob of a query response, or the rejection message if the
+/// query was rejected.
+pub fn get_query_reply(response: Vec<u8>) -> AnyhowResult<Result<Vec<u8>, String>> {
+    let cbor: Value = serde_cbor::from_slice(&response)
+        .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))?;
+    if let Value::Map(m) = cbor {
+        // Try to decode a rejected response.
+        if let (_, Some(Value::Integer(reject_code)), Some(Value::Text(reject_message))) = (
+            m.get(&Value::Text("status".to_string())),
+            m.get(&Value::Text("reject_code".to_string())),
+            m.get(&Value::Text("reject_message".to_string())),
+        ) {
+            return Ok(Err(format!(
+                "Rejected (code {}): {}",
+                reject_code, reject_message
+            )));
+        }
+
+        // Try to decode a successful response.
+        if let (_, Some(Value::Map(m))) = (
+            m.get(&Value::Text("status".to_string())),
+            m.get(&Value::Text("reply".to_string())),
+        ) {
+            if let Some(Value::Bytes(reply)) = m.get(&Value::Text("arg".to_string())) {
+                return Ok(Ok(reply.clone()));
+            }
+        }
+    }
+    Err(anyhow!("Invalid cbor content"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::to_canonical_json;
+    use serde::de::DeserializeOwned;
+
+    // Checks that the value reads back equal from the canonical JSON quill
+    // prints it as.
+    fn assert_roundtrip<T>(value: &T)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let json = to_canonical_json(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    fn ingress() -> Ingress {
+        Ingress {
+            call_type: "update".to_string(),
+            request_id: Some("ab".repeat(32)),
+            content: "d9d9f7a0".to_string(),
+        }
+    }
+
+    fn request_status() -> RequestStatus {
+        RequestStatus {
+            canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai".to_string(),
+            request_id: "ab".repeat(32),
+            content: "d9d9f7a1".to_string(),
+        }
+    }
+
+    fn signed_message_v2(request_status: Option<RequestStatus>) -> SignedMessageV2 {
+        SignedMessageV2 {
+            version: SIGNED_MESSAGE_V2,
+            quill_version: env!("CARGO_PKG_VERSION").to_string(),
+            content_sha256: "cd".repeat(32),
+            summary: MessageSummary {
+                sender: "2vxsx-fae".to_string(),
+                canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai".to_string(),
+                method_name: "send_dfx".to_string(),
+                arguments: "(record { memo = 0 })".to_string(),
+                ingress_expiry: "2021-05-10T00:05:00+00:00".to_string(),
+            },
+            bundle: BundleLink::new("ef".repeat(32), 1, vec![0]),
+            ingress: ingress(),
+            request_status,
+        }
+    }
+
+    #[test]
+    fn v1_roundtrip() {
+        assert_roundtrip(&ingress());
+        assert_roundtrip(&request_status());
+        assert_roundtrip(&IngressWithRequestId {
+            ingress: ingress(),
+            request_status: request_status(),
+        });
+        assert_roundtrip(&Ingress {
+            request_id: None,
+            ..ingress()
+        });
+    }
+
+    #[test]
+    fn v2_roundtrip() {
+        let message = signed_message_v2(Some(request_status()));
+        assert_roundtrip(&message.summary);
+        assert_roundtrip(&message.bundle);
+        assert_roundtrip(&message);
+        assert_roundtrip(&signed_message_v2(None));
+    }
+}
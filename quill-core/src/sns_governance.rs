@@ -0,0 +1,256 @@
+//! Human-readable decoding of the SNS governance canister responses.
+
+use crate::governance::{describe_error, GovernanceError};
+use candid::{types::reserved::Reserved, CandidType, Decode, Deserialize};
+use ic_types::Principal;
+use serde::Serialize;
+
+/// The candid interface of the SNS governance canister.
+pub const CANDID: &str = include_str!("../candid/sns_governance.did");
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct NeuronId {
+    #[serde(serialize_with = "hex::serialize")]
+    pub id: Vec<u8>,
+}
+
+/// Names of the neuron permissions in the order of their numeric values.
+pub const PERMISSIONS: &[&str] = &[
+    "unspecified",
+    "configure-dissolve-state",
+    "manage-principals",
+    "submit-proposal",
+    "vote",
+    "disburse",
+    "split",
+    "merge-maturity",
+    "disburse-maturity",
+    "stake-maturity",
+    "manage-voting-permission",
+];
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct NeuronPermission {
+    pub principal: Option<Principal>,
+    pub permission_type: Vec<i32>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub enum DissolveState {
+    DissolveDelaySeconds(u64),
+    WhenDissolvedTimestampSeconds(u64),
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct Neuron {
+    pub id: Option<NeuronId>,
+    pub permissions: Vec<NeuronPermission>,
+    pub maturity_e8s_equivalent: u64,
+    pub staked_maturity_e8s_equivalent: Option<u64>,
+    pub cached_neuron_stake_e8s: u64,
+    pub neuron_fees_e8s: u64,
+    pub created_timestamp_seconds: u64,
+    pub aging_since_timestamp_seconds: u64,
+    pub dissolve_state: Option<DissolveState>,
+    pub auto_stake_maturity: Option<bool>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListNeuronsResponse {
+    pub neurons: Vec<Neuron>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetMetadataResponse {
+    pub url: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ExecuteGenericNervousSystemFunction {
+    pub function_id: u64,
+    pub payload: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Motion {
+    pub motion_text: String,
+}
+
+/// The action of a proposal; only the actions with a dedicated rendering are decoded.
+#[derive(CandidType, Deserialize)]
+pub enum Action {
+    ManageNervousSystemParameters(Reserved),
+    AddGenericNervousSystemFunction(Reserved),
+    RemoveGenericNervousSystemFunction(Reserved),
+    UpgradeSnsToNextVersion(Reserved),
+    RegisterDappCanisters(Reserved),
+    TransferSnsTreasuryFunds(Reserved),
+    UpgradeSnsControlledCanister(Reserved),
+    DeregisterDappCanisters(Reserved),
+    Unspecified(Reserved),
+    ManageSnsMetadata(Reserved),
+    ExecuteGenericNervousSystemFunction(ExecuteGenericNervousSystemFunction),
+    Motion(Motion),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Proposal {
+    pub url: String,
+    pub title: String,
+    pub action: Option<Action>,
+    pub summary: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Tally {
+    pub no: u64,
+    pub yes: u64,
+    pub total: u64,
+    pub timestamp_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ProposalData {
+    pub id: Option<ProposalId>,
+    pub payload_text_rendering: Option<String>,
+    pub action: u64,
+    pub failure_reason: Option<GovernanceError>,
+    pub proposal_creation_timestamp_seconds: u64,
+    pub decided_timestamp_seconds: u64,
+    pub executed_timestamp_seconds: u64,
+    pub failed_timestamp_seconds: u64,
+    pub latest_tally: Option<Tally>,
+    pub proposal: Option<Proposal>,
+    pub proposer: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum GetProposalResult {
+    Error(GovernanceError),
+    Proposal(ProposalData),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetProposalResponse {
+    pub result: Option<GetProposalResult>,
+}
+
+/// Returns the name of a neuron permission.
+pub fn permission_name(permission: i32) -> String {
+    PERMISSIONS
+        .get(permission as usize)
+        .map_or_else(|| permission.to_string(), |name| name.to_string())
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct Empty {}
+
+#[derive(CandidType, Deserialize)]
+pub struct NervousSystemFunction {
+    pub id: u64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ListNervousSystemFunctionsResponse {
+    pub reserved_ids: Vec<u64>,
+    pub functions: Vec<NervousSystemFunction>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ProposalId {
+    pub id: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetProposal {
+    pub proposal_id: Option<ProposalId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ClaimOrRefreshResponse {
+    pub refreshed_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseMaturityResponse {
+    pub amount_disbursed_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct SplitResponse {
+    pub created_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct StakeMaturityResponse {
+    pub maturity_e8s: u64,
+    pub staked_maturity_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DisburseResponse {
+    pub transfer_block_height: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum CommandResponse {
+    Error(GovernanceError),
+    Follow(Empty),
+    Configure(Empty),
+    MakeProposal(GetProposal),
+    StakeMaturity(StakeMaturityResponse),
+    Split(SplitResponse),
+    DisburseMaturity(DisburseMaturityResponse),
+    ClaimOrRefresh(ClaimOrRefreshResponse),
+    Disburse(DisburseResponse),
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct ManageNeuronResponse {
+    pub command: Option<CommandResponse>,
+}
+
+/// Returns a human-readable description of an SNS governance response, if the
+/// method is supported and the response could be decoded.
+pub fn describe_response(blob: &[u8], method_name: &str) -> Option<String> {
+    match method_name {
+        "manage_neuron" => {
+            let response = Decode!(blob, ManageNeuronResponse).ok()?;
+            Some(match response.command? {
+                CommandResponse::Error(err) => describe_error(&err),
+                CommandResponse::Follow(_) => "Followees updated.".to_string(),
+                CommandResponse::Configure(_) => "Neuron configured.".to_string(),
+                CommandResponse::MakeProposal(r) => format!(
+                    "Submitted proposal {}.",
+                    r.proposal_id
+                        .map_or_else(|| "(unknown id)".to_string(), |id| id.id.to_string())
+                ),
+                CommandResponse::StakeMaturity(r) => format!(
+                    "Staked maturity, the remaining maturity is {} e8s and the staked maturity is {} e8s.",
+                    r.maturity_e8s, r.staked_maturity_e8s
+                ),
+                CommandResponse::Split(r) => format!(
+                    "Split off neuron {}.",
+                    r.created_neuron_id
+                        .map_or_else(|| "(unknown id)".to_string(), |id| hex::encode(id.id))
+                ),
+                CommandResponse::DisburseMaturity(r) => {
+                    format!("Disbursed {} e8s of maturity.", r.amount_disbursed_e8s)
+                }
+                CommandResponse::ClaimOrRefresh(r) => format!(
+                    "Claimed or refreshed neuron {}.",
+                    r.refreshed_neuron_id
+                        .map_or_else(|| "(unknown id)".to_string(), |id| hex::encode(id.id))
+                ),
+                CommandResponse::Disburse(r) => {
+                    format!("Disbursed in ledger block {}.", r.transfer_block_height)
+                }
+            })
+        }
+        _ => None,
+    }
+}
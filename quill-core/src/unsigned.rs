@@ -0,0 +1,144 @@
+//! Unsigned messages, which quill builds for signing services holding the key,
+//! e.g. HSMs of custodians. Each request carries its content as canonical
+//! CBOR and the bytes to sign; the signatures are attached with
+//! [`UnsignedMessage::attach`] to get messages which can be sent.
+
+use crate::{
+    crypto::{ecdsa_signature_from_der, sha256},
+    envelope,
+    error::QuillError,
+    sign::signed_message::{Ingress, RequestStatus},
+    AnyhowResult,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A request whose envelope isn't signed yet.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct UnsignedRequest {
+    pub request_id: String,
+    /// The hex-encoded content of the request as canonical CBOR.
+    pub content: String,
+    /// The hex-encoded bytes to sign, the domain separator `\x0Aic-request`
+    /// followed by the request id. Ed25519 keys sign them as they are.
+    pub message_to_sign: String,
+    /// The SHA-256 hash of `message_to_sign`, which ECDSA keys sign.
+    pub sha256_to_sign: String,
+}
+
+/// A call or query built for an external signer, with the request for its
+/// status if it is a call.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct UnsignedMessage {
+    pub call_type: String,
+    /// The hex-encoded DER public key of the signer, the sender of the requests.
+    pub sender_pubkey: String,
+    pub canister_id: String,
+    pub method_name: String,
+    pub request: UnsignedRequest,
+    pub request_status: Option<UnsignedRequest>,
+}
+
+impl UnsignedRequest {
+    // Takes the content of an unsigned envelope.
+    fn from_envelope(content: &str) -> AnyhowResult<Self> {
+        let content = envelope::content(&hex::decode(content)?)?;
+        let request_id = envelope::request_id(&content)?;
+        let message = envelope::message_to_sign(&request_id)?;
+        Ok(Self {
+            request_id: String::from(request_id),
+            content: hex::encode(serde_cbor::to_vec(&content)?),
+            message_to_sign: hex::encode(&message),
+            sha256_to_sign: hex::encode(sha256(&message)),
+        })
+    }
+
+    // Returns the envelope with the signature, checking that it is valid.
+    fn sign(
+        &self,
+        public_key: &[u8],
+        signatures: &BTreeMap<String, String>,
+    ) -> AnyhowResult<String> {
+        let signature = signatures
+            .get(&self.request_id)
+            .or_else(|| signatures.get(&format!("0x{}", self.request_id)))
+            .ok_or_else(|| {
+                QuillError::Validation(format!(
+                    "There is no signature for the request 0x{}",
+                    self.request_id
+                ))
+            })?;
+        let mut signature = hex::decode(signature.trim_start_matches("0x")).map_err(|_| {
+            QuillError::Validation(format!(
+                "The signature for the request 0x{} isn't hex",
+                self.request_id
+            ))
+        })?;
+        // HSMs often return ECDSA signatures DER-encoded, the IC expects r and s.
+        if let Some(raw) = ecdsa_signature_from_der(&signature) {
+            signature = raw;
+        }
+        let content = serde_cbor::from_slice(&hex::decode(&self.content)?)?;
+        let envelope = envelope::signed_envelope(&content, public_key, &signature)?;
+        let verification = envelope::verify(&envelope)?;
+        if String::from(verification.request_id) != self.request_id
+            || verification.signature_valid != Some(true)
+        {
+            return Err(QuillError::Validation(format!(
+                "The signature for the request 0x{} is invalid",
+                self.request_id
+            ))
+            .into());
+        }
+        Ok(hex::encode(envelope))
+    }
+}
+
+impl UnsignedMessage {
+    /// Takes a message built with an [`crate::Identity::External`], whose
+    /// envelopes aren't signed.
+    pub fn new(
+        ingress: &Ingress,
+        request_status: Option<&RequestStatus>,
+        public_key: &[u8],
+    ) -> AnyhowResult<Self> {
+        let (_, _, canister_id, method_name, _) = ingress.inspect()?;
+        Ok(Self {
+            call_type: ingress.call_type.clone(),
+            sender_pubkey: hex::encode(public_key),
+            canister_id: canister_id.to_text(),
+            method_name,
+            request: UnsignedRequest::from_envelope(&ingress.content)?,
+            request_status: request_status
+                .map(|status| UnsignedRequest::from_envelope(&status.content))
+                .transpose()?,
+        })
+    }
+
+    /// Attaches the signatures, given as hex by the request ids they sign, and
+    /// returns the signed message.
+    pub fn attach(
+        &self,
+        signatures: &BTreeMap<String, String>,
+    ) -> AnyhowResult<(Ingress, Option<RequestStatus>)> {
+        let public_key = hex::decode(&self.sender_pubkey)?;
+        let ingress = Ingress {
+            call_type: self.call_type.clone(),
+            request_id: Some(self.request.request_id.clone()).filter(|_| self.call_type != "query"),
+            content: self.request.sign(&public_key, signatures)?,
+        };
+        let request_status = self
+            .request_status
+            .as_ref()
+            .map(|status| -> AnyhowResult<_> {
+                Ok(RequestStatus {
+                    canister_id: self.canister_id.clone(),
+                    request_id: self.request.request_id.clone(),
+                    content: status.sign(&public_key, signatures)?,
+                })
+            })
+            .transpose()?;
+        Ok((ingress, request_status))
+    }
+}
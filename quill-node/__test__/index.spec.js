@@ -0,0 +1,20 @@
+const assert = require('assert')
+const fs = require('fs')
+const path = require('path')
+const quill = require('..')
+
+const pem = fs.readFileSync(path.join(__dirname, '../../tests/identity.pem'), 'utf8')
+const account = '345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752'
+
+const transfer = quill.signTransfer(pem, account, '0.000123')
+assert.strictEqual(transfer.length, 1)
+assert.strictEqual(transfer[0].ingress.callType, 'update')
+assert.strictEqual(quill.verifyMessage(quill.messagesToJson(transfer)).length, 2)
+
+const command = quill.signNeuronCommand(pem, JSON.stringify({ neuron_id: '2313380519530470538', start_dissolving: true }))
+assert.strictEqual(command.length, 1)
+
+assert.throws(() => quill.signNeuronCommand(pem, JSON.stringify({ neuron_id: '2313380519530470538', no_such_option: true })), /exit code 2/)
+assert.throws(() => quill.verifyMessage('not a message'), /Invalid JSON content \(exit code 2\)/)
+
+console.log('All tests passed.')
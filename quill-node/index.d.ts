@@ -0,0 +1,40 @@
+/* tslint:disable */
+/* eslint-disable */
+
+/* auto-generated by NAPI-RS */
+
+/** A signed call or query, with its hex-encoded CBOR envelope in `content`. */
+export interface Ingress {
+  callType: string
+  requestId?: string | undefined | null
+  content: string
+}
+/** The signed `read_state` request for the status of a call. */
+export interface RequestStatus {
+  canisterId: string
+  requestId: string
+  content: string
+}
+/** A signed call with the request for its status, as `quill` prints it. */
+export interface SignedMessage {
+  ingress: Ingress
+  requestStatus: RequestStatus
+}
+/** Signs an ICP transfer like `quill transfer`. */
+export function signTransfer(pem: string, to: string, amount: string, fee?: string | undefined | null, memo?: string | undefined | null): Array<SignedMessage>
+/**
+ * Signs a neuron command like `quill neuron-manage`, given as the JSON of an
+ * object like `{"neuron_id": "<neuron-id>", "start_dissolving": true}`.
+ */
+export function signNeuronCommand(pem: string, command: string): Array<SignedMessage>
+/**
+ * Returns the JSON of signed messages as `quill` prints it, e.g. to be sent
+ * with `quill send`.
+ */
+export function messagesToJson(messages: Array<SignedMessage>): string
+/**
+ * Verifies signed messages like `quill verify`, given as the JSON printed by
+ * quill, and returns one line per checked envelope; the problems found are
+ * thrown.
+ */
+export function verifyMessage(message: string): Array<string>
@@ -0,0 +1,127 @@
+//! The Node.js package `quill`, which signs and verifies messages with the
+//! same code as the command. Its TypeScript definitions in `index.d.ts` are
+//! generated from the types below by `npm run build`.
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use quill_cli::api;
+use quill_core::{error::exit_code, to_canonical_json, AnyhowResult, IngressWithRequestId};
+
+/// A signed call or query, with its hex-encoded CBOR envelope in `content`.
+#[napi(object)]
+pub struct Ingress {
+    pub call_type: String,
+    pub request_id: Option<String>,
+    pub content: String,
+}
+
+/// The signed `read_state` request for the status of a call.
+#[napi(object)]
+pub struct RequestStatus {
+    pub canister_id: String,
+    pub request_id: String,
+    pub content: String,
+}
+
+/// A signed call with the request for its status, as `quill` prints it.
+#[napi(object)]
+pub struct SignedMessage {
+    pub ingress: Ingress,
+    pub request_status: RequestStatus,
+}
+
+impl From<IngressWithRequestId> for SignedMessage {
+    fn from(message: IngressWithRequestId) -> Self {
+        Self {
+            ingress: Ingress {
+                call_type: message.ingress.call_type,
+                request_id: message.ingress.request_id,
+                content: message.ingress.content,
+            },
+            request_status: RequestStatus {
+                canister_id: message.request_status.canister_id,
+                request_id: message.request_status.request_id,
+                content: message.request_status.content,
+            },
+        }
+    }
+}
+
+impl From<SignedMessage> for IngressWithRequestId {
+    fn from(message: SignedMessage) -> Self {
+        Self {
+            ingress: quill_core::Ingress {
+                call_type: message.ingress.call_type,
+                request_id: message.ingress.request_id,
+                content: message.ingress.content,
+            },
+            request_status: quill_core::RequestStatus {
+                canister_id: message.request_status.canister_id,
+                request_id: message.request_status.request_id,
+                content: message.request_status.content,
+            },
+        }
+    }
+}
+
+/// Signs an ICP transfer like `quill transfer`.
+#[napi]
+pub fn sign_transfer(
+    pem: String,
+    to: String,
+    amount: String,
+    fee: Option<String>,
+    memo: Option<String>,
+) -> Result<Vec<SignedMessage>> {
+    messages(api::sign_transfer(
+        &pem,
+        &to,
+        &amount,
+        fee.as_deref(),
+        memo.as_deref(),
+    ))
+}
+
+/// Signs a neuron command like `quill neuron-manage`, given as the JSON of an
+/// object like `{"neuron_id": "<neuron-id>", "start_dissolving": true}`.
+#[napi]
+pub fn sign_neuron_command(pem: String, command: String) -> Result<Vec<SignedMessage>> {
+    messages(
+        api::NeuronCommand::from_json(&command)
+            .and_then(|command| api::sign_neuron_command(&pem, &command)),
+    )
+}
+
+/// Verifies signed messages like `quill verify`, given as the JSON printed by
+/// quill, and returns one line per checked envelope; the problems found are
+/// thrown.
+#[napi]
+pub fn verify_message(message: String) -> Result<Vec<String>> {
+    api::verify_message(&message).map_err(error)
+}
+
+/// Returns the JSON of signed messages as `quill` prints it, e.g. to be sent
+/// with `quill send`.
+#[napi]
+pub fn messages_to_json(messages: Vec<SignedMessage>) -> Result<String> {
+    let messages = messages
+        .into_iter()
+        .map(IngressWithRequestId::from)
+        .collect::<Vec<_>>();
+    to_canonical_json(&messages).map_err(error)
+}
+
+// Parses the JSON of signed messages.
+fn messages(json: AnyhowResult<String>) -> Result<Vec<SignedMessage>> {
+    let messages: Vec<IngressWithRequestId> = serde_json::from_str(&json.map_err(error)?)
+        .map_err(|err| Error::new(Status::GenericFailure, err.to_string()))?;
+    Ok(messages.into_iter().map(SignedMessage::from).collect())
+}
+
+// Throws an error with the message and the exit code of the command.
+fn error(err: anyhow::Error) -> Error {
+    Error::new(
+        Status::GenericFailure,
+        format!("{} (exit code {})", err, exit_code(&err)),
+    )
+}
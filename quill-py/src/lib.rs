@@ -0,0 +1,81 @@
+//! The Python package `quill`, which signs and verifies messages with the
+//! same code as the command. Errors raise `quill.QuillError` with the message
+//! and the exit code the command would fail with.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use quill_cli::api;
+use quill_core::{error::exit_code, AnyhowResult};
+
+create_exception!(quill, QuillError, PyException);
+
+/// Signs an ICP transfer like `quill transfer` and returns the JSON of the
+/// signed messages.
+#[pyfunction]
+fn sign_transfer(
+    pem: &str,
+    to: &str,
+    amount: &str,
+    fee: Option<&str>,
+    memo: Option<&str>,
+) -> PyResult<String> {
+    result(api::sign_transfer(pem, to, amount, fee, memo))
+}
+
+/// Signs a neuron command like `quill neuron-manage`, given as a JSON object
+/// like `{"neuron_id": "<neuron-id>", "start_dissolving": true}`, and returns
+/// the JSON of the signed messages.
+#[pyfunction]
+fn sign_neuron_command(pem: &str, command: &str) -> PyResult<String> {
+    result(
+        api::NeuronCommand::from_json(command)
+            .and_then(|command| api::sign_neuron_command(pem, &command)),
+    )
+}
+
+/// Signs an ICP transfer like `sign_transfer` with the key of a BIP39 seed
+/// phrase, derived at m/44'/223'/0'/0/0 as by the wallets of the IC.
+#[pyfunction]
+fn sign_transfer_mnemonic(
+    phrase: &str,
+    to: &str,
+    amount: &str,
+    fee: Option<&str>,
+    memo: Option<&str>,
+) -> PyResult<String> {
+    result(api::sign_transfer_mnemonic(phrase, to, amount, fee, memo))
+}
+
+/// Signs a neuron command like `sign_neuron_command` with the key of a BIP39
+/// seed phrase.
+#[pyfunction]
+fn sign_neuron_command_mnemonic(phrase: &str, command: &str) -> PyResult<String> {
+    result(
+        api::NeuronCommand::from_json(command)
+            .and_then(|command| api::sign_neuron_command_mnemonic(phrase, &command)),
+    )
+}
+
+/// Verifies signed messages like `quill verify` and returns one line per
+/// checked envelope; the problems found are raised.
+#[pyfunction]
+fn verify_message(message: &str) -> PyResult<Vec<String>> {
+    result(api::verify_message(message))
+}
+
+#[pymodule]
+fn quill(py: Python, module: &PyModule) -> PyResult<()> {
+    module.add("QuillError", py.get_type::<QuillError>())?;
+    module.add_function(wrap_pyfunction!(sign_transfer, module)?)?;
+    module.add_function(wrap_pyfunction!(sign_neuron_command, module)?)?;
+    module.add_function(wrap_pyfunction!(sign_transfer_mnemonic, module)?)?;
+    module.add_function(wrap_pyfunction!(sign_neuron_command_mnemonic, module)?)?;
+    module.add_function(wrap_pyfunction!(verify_message, module)?)?;
+    Ok(())
+}
+
+// Raises the error of a function as a `QuillError`.
+fn result<T>(result: AnyhowResult<T>) -> PyResult<T> {
+    result.map_err(|err| QuillError::new_err((err.to_string(), exit_code(&err))))
+}
@@ -0,0 +1,40 @@
+import json
+import os
+
+import pytest
+import quill
+
+PEM = open(os.path.join(os.path.dirname(__file__), "../../tests/identity.pem")).read()
+ACCOUNT = "345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752"
+PHRASE = " ".join(["abandon"] * 11 + ["about"])
+START_DISSOLVING = json.dumps({"neuron_id": "2313380519530470538", "start_dissolving": True})
+
+
+def test_sign_and_verify_transfer():
+    messages = quill.sign_transfer(PEM, ACCOUNT, "0.000123")
+    assert len(json.loads(messages)) == 1
+    assert len(quill.verify_message(messages)) == 2
+
+
+def test_sign_and_verify_neuron_command():
+    messages = quill.sign_neuron_command(PEM, START_DISSOLVING)
+    assert len(quill.verify_message(messages)) == 2
+
+
+def test_sign_with_mnemonic():
+    messages = quill.sign_transfer_mnemonic(PHRASE, ACCOUNT, "0.000123")
+    assert len(quill.verify_message(messages)) == 2
+    messages = quill.sign_neuron_command_mnemonic(PHRASE, START_DISSOLVING)
+    assert len(quill.verify_message(messages)) == 2
+    with pytest.raises(quill.QuillError) as error:
+        quill.sign_transfer_mnemonic(" ".join(["abandon"] * 12), ACCOUNT, "0.000123")
+    assert error.value.args[1] == 5
+
+
+def test_invalid_arguments_raise_with_exit_code():
+    with pytest.raises(quill.QuillError) as error:
+        quill.sign_neuron_command(PEM, json.dumps({"neuron_id": "2313380519530470538", "no_such_option": True}))
+    assert error.value.args[1] == 2
+    with pytest.raises(quill.QuillError) as error:
+        quill.verify_message("not a message")
+    assert error.value.args == ("Invalid JSON content", 2)
@@ -0,0 +1,162 @@
+//! The signing functions of quill for other languages, which run the same code
+//! as the commands. The C interface in [`crate::ffi`] and the Python package
+//! in `quill-py` wrap them.
+
+use crate::commands::{neuron_manage, send, transfer, verify};
+use ic_types::Principal;
+#[cfg(feature = "mnemonic")]
+use quill_core::crypto::mnemonic_to_pem;
+use quill_core::{bundle::Bundle, error::QuillError, to_canonical_json, AnyhowResult, Identity};
+use serde::Deserialize;
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+
+/// A neuron command for [`sign_neuron_command`], with the options of `quill
+/// neuron-manage`. The other languages give it as a JSON object, e.g.
+/// `{"neuron_id": "2313380519530470538", "start_dissolving": true}`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NeuronCommand {
+    /// The id or the alias of the neuron to manage.
+    pub neuron_id: String,
+    /// Principal to be used as a hot key.
+    #[serde(default)]
+    pub add_hot_key: Option<String>,
+    /// Principal hot key to be removed.
+    #[serde(default)]
+    pub remove_hot_key: Option<String>,
+    /// Number of dissolve seconds to add.
+    #[serde(default)]
+    pub additional_dissolve_delay_seconds: Option<u32>,
+    #[serde(default)]
+    pub start_dissolving: bool,
+    #[serde(default)]
+    pub stop_dissolving: bool,
+    #[serde(default)]
+    pub disburse: bool,
+    #[serde(default)]
+    pub spawn: bool,
+    /// The number of ICP to split off, which requires `stake`.
+    #[serde(default)]
+    pub split: Option<u64>,
+    /// The current stake of the neuron (in ICP), for `split`.
+    #[serde(default)]
+    pub stake: Option<String>,
+}
+
+impl NeuronCommand {
+    /// Reads a neuron command from its JSON object.
+    pub fn from_json(json: &str) -> AnyhowResult<Self> {
+        serde_json::from_str(json).map_err(|err| {
+            QuillError::Validation(format!("Invalid neuron command: {}", err)).into()
+        })
+    }
+
+    fn to_opts(&self) -> AnyhowResult<neuron_manage::ManageOpts> {
+        if self.split.is_some() != self.stake.is_some() {
+            return Err(QuillError::Validation(
+                "A split requires the current stake of the neuron, and the reverse".to_string(),
+            )
+            .into());
+        }
+        Ok(neuron_manage::ManageOpts {
+            neuron_id: self.neuron_id.clone(),
+            add_hot_key: principal(self.add_hot_key.as_deref())?,
+            remove_hot_key: principal(self.remove_hot_key.as_deref())?,
+            additional_dissolve_delay_seconds: self.additional_dissolve_delay_seconds,
+            start_dissolving: self.start_dissolving,
+            stop_dissolving: self.stop_dissolving,
+            disburse: self.disburse,
+            spawn: self.spawn,
+            split: self.split,
+            stake: self.stake.clone(),
+        })
+    }
+}
+
+/// Signs an ICP transfer like `quill transfer` and returns the JSON of the
+/// signed messages.
+pub fn sign_transfer(
+    pem: &str,
+    to: &str,
+    amount: &str,
+    fee: Option<&str>,
+    memo: Option<&str>,
+) -> AnyhowResult<String> {
+    Identity::from_pem(pem)?;
+    let valid = |name: &str, value: Option<&str>, validator: fn(&str) -> Result<(), String>| {
+        value
+            .map_or(Ok(()), validator)
+            .map_err(|err| QuillError::Validation(format!("Invalid {}: {}", name, err)))
+    };
+    valid("amount", Some(amount), transfer::icpts_amount_validator)?;
+    valid("fee", fee, transfer::icpts_amount_validator)?;
+    valid("memo", memo, transfer::memo_validator)?;
+    let opts = transfer::TransferOpts {
+        to: to.to_string(),
+        amount: amount.to_string(),
+        memo: memo.map(str::to_string),
+        fee: fee.map(str::to_string),
+    };
+    let messages = runtime().block_on(transfer::exec(&Some(pem.to_string()), opts))?;
+    to_canonical_json(&messages)
+}
+
+/// Signs a neuron command like `quill neuron-manage` and returns the JSON of
+/// the signed messages.
+pub fn sign_neuron_command(pem: &str, command: &NeuronCommand) -> AnyhowResult<String> {
+    Identity::from_pem(pem)?;
+    let opts = command.to_opts()?;
+    let messages = runtime().block_on(neuron_manage::exec(&Some(pem.to_string()), opts))?;
+    to_canonical_json(&messages)
+}
+
+/// Signs an ICP transfer like [`sign_transfer`] with the key of a BIP39 seed
+/// phrase instead of a PEM file.
+#[cfg(feature = "mnemonic")]
+pub fn sign_transfer_mnemonic(
+    phrase: &str,
+    to: &str,
+    amount: &str,
+    fee: Option<&str>,
+    memo: Option<&str>,
+) -> AnyhowResult<String> {
+    sign_transfer(&mnemonic_to_pem(phrase)?, to, amount, fee, memo)
+}
+
+/// Signs a neuron command like [`sign_neuron_command`] with the key of a BIP39
+/// seed phrase instead of a PEM file.
+#[cfg(feature = "mnemonic")]
+pub fn sign_neuron_command_mnemonic(phrase: &str, command: &NeuronCommand) -> AnyhowResult<String> {
+    sign_neuron_command(&mnemonic_to_pem(phrase)?, command)
+}
+
+/// Verifies signed messages like `quill verify` and returns one line per
+/// checked envelope, or the problems found as the error.
+pub fn verify_message(message: &str) -> AnyhowResult<Vec<String>> {
+    let bundle = Bundle::from_json(&send::message_json(message.to_string())?)?;
+    let (lines, problems) = verify::check(&bundle, None, None)?;
+    if !problems.is_empty() {
+        return Err(QuillError::Validation(format!(
+            "Verification failed:\n  {}",
+            problems.join("\n  ")
+        ))
+        .into());
+    }
+    Ok(lines)
+}
+
+// The runtime of all calls, created once rather than for every message.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("Unable to create a runtime"))
+}
+
+fn principal(text: Option<&str>) -> AnyhowResult<Option<Principal>> {
+    text.map(|text| {
+        Principal::from_text(text).map_err(|err| {
+            QuillError::Validation(format!("Invalid principal {}: {}", text, err)).into()
+        })
+    })
+    .transpose()
+}
@@ -0,0 +1,113 @@
+use crate::commands::{public::get_ids, OutputOpts};
+use anyhow::anyhow;
+use clap::Clap;
+use ic_base_types::PrincipalId;
+use ic_types::Principal;
+use ledger_canister::{AccountIdentifier, Subaccount};
+use quill_core::{
+    error::QuillError, fingerprint::fingerprint, icrc1::Account, to_canonical_json, AnyhowResult,
+};
+use std::convert::TryFrom;
+
+/// Prints all representations of the account of a principal: the account ids
+/// of the default and a custom subaccount, the ICRC-1 textual encoding and the
+/// checksum of the account id, along with the word fingerprints of the
+/// principal and the account ids.
+#[derive(Clap)]
+pub struct AccountIdOpts {
+    /// The principal of the account, default is the signing principal.
+    #[clap(long)]
+    principal: Option<Principal>,
+
+    /// The subaccount, as an index (e.g. 1) or up to 32 bytes of hex.
+    #[clap(long)]
+    subaccount: Option<String>,
+}
+
+/// The representations printed with `--json`.
+#[derive(Serialize, JsonSchema)]
+pub struct AccountIds {
+    pub principal_id: String,
+    pub principal_id_words: String,
+    pub default_account_id: String,
+    pub default_account_id_words: String,
+    /// The hex-encoded subaccount, null for the default one.
+    pub subaccount: Option<String>,
+    pub account_id: String,
+    pub account_id_words: String,
+    /// The first 8 hex digits of the account id, its CRC32.
+    pub account_id_checksum: String,
+    pub icrc1_account: String,
+}
+
+pub fn exec(pem: &Option<String>, opts: AccountIdOpts, output: &OutputOpts) -> AnyhowResult {
+    let principal = match opts.principal {
+        Some(principal) => principal,
+        None => get_ids(pem)?.0,
+    };
+    let principal_id = PrincipalId::try_from(principal.as_slice()).map_err(|err| anyhow!(err))?;
+    let subaccount = opts
+        .subaccount
+        .as_deref()
+        .map(parse_subaccount)
+        .transpose()?;
+    let default_account_id = AccountIdentifier::new(principal_id, None);
+    let account_id = AccountIdentifier::new(principal_id, subaccount);
+    let account = Account {
+        owner: principal,
+        subaccount: subaccount.map(|subaccount| subaccount.0.to_vec()),
+    };
+    // The first 4 bytes of an account id are the CRC32 of the remaining 28.
+    let checksum = account_id.to_hex()[..8].to_string();
+    let principal_words = fingerprint(principal.as_slice());
+    let default_account_words = fingerprint(&hex::decode(default_account_id.to_hex())?);
+    let account_words = fingerprint(&hex::decode(account_id.to_hex())?);
+
+    if output.json {
+        let ids = AccountIds {
+            principal_id: principal.to_text(),
+            principal_id_words: principal_words,
+            default_account_id: default_account_id.to_hex(),
+            default_account_id_words: default_account_words,
+            subaccount: subaccount.map(|subaccount| hex::encode(subaccount.0)),
+            account_id: account_id.to_hex(),
+            account_id_words: account_words,
+            account_id_checksum: checksum,
+            icrc1_account: account.to_string(),
+        };
+        println!("{}", to_canonical_json(&ids)?);
+        return Ok(());
+    }
+    println!("Principal id:        {} ({})", principal, principal_words);
+    println!(
+        "Default account id:  {} ({})",
+        default_account_id, default_account_words
+    );
+    if let Some(subaccount) = subaccount {
+        println!("Subaccount:          {}", hex::encode(subaccount.0));
+        println!("Account id:          {} ({})", account_id, account_words);
+    }
+    println!("Account id checksum: {} (CRC32)", checksum);
+    println!("ICRC-1 account:      {}", account);
+    Ok(())
+}
+
+fn parse_subaccount(subaccount: &str) -> AnyhowResult<Subaccount> {
+    let mut bytes = [0u8; 32];
+    if let Ok(index) = subaccount.parse::<u64>() {
+        bytes[24..].copy_from_slice(&index.to_be_bytes());
+        return Ok(Subaccount(bytes));
+    }
+    let hex = subaccount.trim_start_matches("0x");
+    if hex.len() > 64 {
+        return Err(QuillError::Validation(format!(
+            "A subaccount has at most 32 bytes: {}",
+            subaccount
+        ))
+        .into());
+    }
+    let decoded = hex::decode(format!("{:0>64}", hex))
+        .map_err(|_| QuillError::Validation(format!("Invalid subaccount: {}", subaccount)))?;
+    bytes.copy_from_slice(&decoded);
+    Ok(Subaccount(bytes))
+}
@@ -0,0 +1,84 @@
+use crate::commands::OutputOpts;
+use anyhow::anyhow;
+use clap::Clap;
+use ledger_canister::AccountIdentifier;
+use quill_core::{aliases::AliasBook, fingerprint::fingerprint, to_canonical_json, AnyhowResult};
+use std::str::FromStr;
+
+const ADDRESSES_FILE: &str = "addresses.json";
+
+/// Manages the local book of destination accounts.
+#[derive(Clap)]
+pub struct AddressOpts {
+    #[clap(subcommand)]
+    command: AddressCommand,
+}
+
+#[derive(Clap)]
+enum AddressCommand {
+    /// Adds (or replaces) a name for an account id, whose checksum is verified.
+    Add { name: String, account_id: String },
+    /// Removes a name.
+    Remove { name: String },
+    /// Lists all names, with the word fingerprints of their account ids.
+    List,
+}
+
+pub fn exec(opts: AddressOpts, output: &OutputOpts) -> AnyhowResult {
+    let mut book = AliasBook::load(ADDRESSES_FILE)?;
+    match opts.command {
+        AddressCommand::Add { name, account_id } => {
+            if parse_account_id(&name).is_ok() {
+                return Err(anyhow!("A name can't be an account id"));
+            }
+            let account = parse_account_id(&account_id)?;
+            book.aliases.insert(name, account.to_hex());
+            book.save()
+        }
+        AddressCommand::Remove { name } => {
+            book.aliases
+                .remove(&name)
+                .ok_or_else(|| anyhow!("Unknown address: {}", name))?;
+            book.save()
+        }
+        AddressCommand::List if output.json => {
+            println!("{}", to_canonical_json(&book.aliases)?);
+            Ok(())
+        }
+        AddressCommand::List => {
+            for (name, account_id) in &book.aliases {
+                let words = fingerprint(&hex::decode(account_id)?);
+                println!("{}: {} ({})", name, account_id, words);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Returns the account id, resolving it from the address book if it isn't an
+/// account id. The stored account id is verified again, so that an edited
+/// book can't redirect a transfer to a mistyped account.
+pub fn resolve_account_id(to: &str) -> AnyhowResult<AccountIdentifier> {
+    match parse_account_id(to) {
+        Ok(account) => Ok(account),
+        Err(_) => {
+            let account = parse_account_id(AliasBook::load(ADDRESSES_FILE)?.get(to)?)?;
+            eprintln!("Resolved {} to the account {}", to, account);
+            Ok(account)
+        }
+    }
+}
+
+/// Returns the name of the account id in the address book, if there is one.
+pub fn address_name(account_id: &str) -> AnyhowResult<Option<String>> {
+    Ok(AliasBook::load(ADDRESSES_FILE)?
+        .aliases
+        .into_iter()
+        .find(|(_, id)| id == account_id)
+        .map(|(name, _)| name))
+}
+
+fn parse_account_id(account_id: &str) -> AnyhowResult<AccountIdentifier> {
+    AccountIdentifier::from_str(account_id)
+        .map_err(|err| anyhow!("Invalid account id {}: {}", account_id, err))
+}
@@ -0,0 +1,37 @@
+use crate::commands::{convert_message::print_quill, OutputOpts};
+use clap::Clap;
+use quill_core::{
+    bundle::Bundle, error::QuillError, read_from_file, unsigned::UnsignedMessage, AnyhowResult,
+};
+use std::collections::BTreeMap;
+
+/// Attaches the signatures of an external signer to the messages printed with
+/// `--unsigned-envelope`, and prints the signed messages, which can be sent.
+/// Each signature is checked against the public key of the signer.
+#[derive(Clap)]
+pub struct AttachSignaturesOpts {
+    /// Path to the unsigned messages (use "-" for STDIN).
+    file_name: String,
+
+    /// A JSON file mapping the request ids to their hex-encoded signatures,
+    /// raw or DER-encoded for ECDSA keys.
+    #[clap(long)]
+    signatures: String,
+}
+
+pub async fn exec(opts: AttachSignaturesOpts, output: &OutputOpts) -> AnyhowResult {
+    let messages: Vec<UnsignedMessage> = serde_json::from_str(&read_from_file(&opts.file_name)?)
+        .map_err(|err| QuillError::Validation(format!("Invalid unsigned messages: {}", err)))?;
+    let signatures: BTreeMap<String, String> =
+        serde_json::from_str(&read_from_file(&opts.signatures)?).map_err(|err| {
+            QuillError::Validation(format!(
+                "Invalid signatures, expected an object of request ids and signatures: {}",
+                err
+            ))
+        })?;
+    let signed = messages
+        .iter()
+        .map(|message| message.attach(&signatures))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    print_quill(Bundle::new(signed)?, output).await
+}
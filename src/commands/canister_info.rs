@@ -0,0 +1,89 @@
+use crate::commands::{read_state, OutputOpts};
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{error::QuillError, to_canonical_json, AnyhowResult};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Prints the certified module hash and controllers of a canister, e.g. to
+/// check which code it runs before approving calls to it.
+#[derive(Clap)]
+pub struct CanisterInfoOpts {
+    canister_id: Principal,
+
+    /// Fails unless the canister runs the module with this hex-encoded SHA-256 hash.
+    #[clap(long)]
+    expected_module_hash: Option<String>,
+}
+
+/// The information printed with `--json`.
+#[derive(Serialize, JsonSchema)]
+pub struct CanisterInfo {
+    pub canister_id: String,
+    /// The hex-encoded SHA-256 hash of the module, null if no code is installed.
+    pub module_hash: Option<String>,
+    pub controllers: Vec<String>,
+}
+
+pub async fn exec(opts: CanisterInfoOpts, output: &OutputOpts) -> AnyhowResult {
+    let canister_id = opts.canister_id;
+    let path = |name: &str| {
+        vec![
+            b"canister".to_vec(),
+            canister_id.as_slice().to_vec(),
+            name.as_bytes().to_vec(),
+        ]
+    };
+    let mut values = read_state::read(&[path("module_hash"), path("controllers")], canister_id)
+        .await?
+        .into_iter();
+    let (module_hash, controllers) = (values.next().flatten(), values.next().flatten());
+    let controllers = controllers
+        .map(|controllers| {
+            controllers
+                .split(", ")
+                .filter(|controller| !controller.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    if output.json {
+        println!(
+            "{}",
+            to_canonical_json(&CanisterInfo {
+                canister_id: canister_id.to_text(),
+                module_hash: module_hash.clone(),
+                controllers: controllers.clone(),
+            })?
+        );
+    } else {
+        println!("Canister:    {}", canister_id);
+        match &module_hash {
+            Some(hash) => println!("Module hash: 0x{}", hash),
+            None => println!("Module hash: none, no code is installed"),
+        }
+        if controllers.is_empty() {
+            println!("Controllers: none");
+        }
+        for (i, controller) in controllers.iter().enumerate() {
+            let label = if i == 0 { "Controllers:" } else { "" };
+            println!("{:12} {}", label, controller);
+        }
+    }
+
+    if let Some(expected) = opts.expected_module_hash {
+        let expected = expected.trim().trim_start_matches("0x").to_lowercase();
+        if module_hash.as_deref() != Some(expected.as_str()) {
+            return Err(QuillError::Validation(format!(
+                "The canister {} doesn't run the module with the hash 0x{}",
+                canister_id, expected
+            ))
+            .into());
+        }
+        if !output.json {
+            println!("The module hash matches the expected hash.");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use clap::Clap;
+use openssl::sha::sha256;
+use quill_core::{error::QuillError, read_bytes_from_file, AnyhowResult};
+
+/// Prints the SHA-256 hash and the size of a file, e.g. to compare a message
+/// file on both sides of an air gap.
+#[derive(Clap)]
+pub struct ChecksumOpts {
+    /// Path to the file (use "-" for STDIN).
+    file_name: String,
+
+    /// The hex-encoded SHA-256 hash the file is expected to have.
+    #[clap(long)]
+    expected: Option<String>,
+}
+
+pub fn exec(opts: ChecksumOpts) -> AnyhowResult {
+    let content = read_bytes_from_file(&opts.file_name)?;
+    let hash = hex::encode(sha256(&content));
+    println!("SHA-256: {}", hash);
+    println!("Size:    {} bytes", content.len());
+    match opts.expected {
+        Some(expected) if !expected.trim().eq_ignore_ascii_case(&hash) => {
+            Err(QuillError::Validation(format!(
+                "The hash of {} doesn't match the expected hash {}",
+                opts.file_name, expected
+            ))
+            .into())
+        }
+        Some(_) => {
+            println!("The hash matches the expected hash.");
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Prints the hash and the size of a written file to STDERR, so that they
+/// can be compared with the output of `checksum` on the other side.
+pub fn print_checksum(path: &str, content: &[u8]) {
+    eprintln!(
+        "Wrote {} ({} bytes, SHA-256 {})",
+        path,
+        content.len(),
+        hex::encode(sha256(content))
+    );
+}
@@ -0,0 +1,36 @@
+use crate::commands::{send::read_bundle, write_output, OutputOpts};
+use clap::Clap;
+use quill_core::{bundle::Bundle, to_canonical_json, AnyhowResult};
+
+/// Combines the signed messages of several files into one bundle, e.g. payouts
+/// signed one by one, which `send` sends as one batch.
+#[derive(Clap)]
+pub struct CombineOpts {
+    /// Paths to the signed messages, in the order they are bundled.
+    #[clap(required = true)]
+    file_names: Vec<String>,
+
+    /// Makes the messages of different files independent of each other, so
+    /// that `send --parallel` sends them at the same time; the messages of one
+    /// file keep their dependencies. By default every message depends on the
+    /// one before it.
+    #[clap(long)]
+    independent: bool,
+}
+
+pub fn exec(opts: CombineOpts, output: &OutputOpts) -> AnyhowResult {
+    let mut messages = Vec::new();
+    for file_name in &opts.file_names {
+        let offset = messages.len();
+        for message in read_bundle(file_name)?.messages {
+            let depends_on = if opts.independent {
+                message.depends_on.iter().map(|dep| dep + offset).collect()
+            } else {
+                messages.len().checked_sub(1).into_iter().collect()
+            };
+            messages.push((message.ingress, message.request_status, depends_on));
+        }
+    }
+    let bundle = Bundle::with_dependencies(messages)?;
+    write_output(to_canonical_json(&bundle)?, output)
+}
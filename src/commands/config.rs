@@ -0,0 +1,38 @@
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{config::Config, AnyhowResult};
+
+/// Manages the persistent quill configuration.
+#[derive(Clap)]
+pub struct ConfigOpts {
+    #[clap(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Clap)]
+enum ConfigCommand {
+    /// Prints the configuration.
+    Show,
+    /// Sets the principal controlling the neurons spawned from maturity.
+    SetMaturityDestination { principal: Principal },
+    /// Removes the maturity destination, so spawned neurons are controlled by the neuron's controller.
+    UnsetMaturityDestination,
+}
+
+pub fn exec(opts: ConfigOpts) -> AnyhowResult {
+    let mut config = Config::load()?;
+    match opts.command {
+        ConfigCommand::Show => {
+            print!("{}", toml::to_string(&config)?);
+            Ok(())
+        }
+        ConfigCommand::SetMaturityDestination { principal } => {
+            config.maturity_destination = Some(principal.to_text());
+            config.save()
+        }
+        ConfigCommand::UnsetMaturityDestination => {
+            config.maturity_destination = None;
+            config.save()
+        }
+    }
+}
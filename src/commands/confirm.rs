@@ -0,0 +1,228 @@
+//! A full-screen confirmation of transfers, which can only be passed by typing
+//! the amount, so that a swapped destination or amount can't slip through.
+
+use crate::commands::address::address_name;
+#[cfg(feature = "sns")]
+use crate::commands::sns::cached_ledger_ids;
+use candid::{CandidType, Decode, Deserialize};
+#[cfg(feature = "tui")]
+use crossterm::{
+    cursor::MoveTo,
+    execute,
+    terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ic_types::Principal;
+use openssl::sha::sha256;
+use quill_core::{
+    envelope,
+    error::QuillError,
+    fingerprint::{fingerprint, icrc1_fingerprint},
+    icrc1::{format_tokens, group_digits, TransferArg},
+    ledger_canister_id,
+    rates::Rates,
+    sign::signed_message::Ingress,
+    AnyhowResult,
+};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+// The ledger of ckBTC, whose amounts have 8 decimals like those of ICP.
+#[cfg(feature = "ckbtc")]
+const CKBTC_LEDGER_CANISTER_ID: &str = "mxzaz-hqaaa-aaaar-qaada-cai";
+
+#[derive(CandidType, Deserialize)]
+pub struct Tokens {
+    pub e8s: u64,
+}
+
+// The fields of the ledger's `send_dfx` arguments which are shown.
+#[derive(CandidType, Deserialize)]
+pub struct SendArgs {
+    pub to: String,
+    pub amount: Tokens,
+    pub fee: Tokens,
+    pub memo: u64,
+}
+
+/// The details of a transfer shown for confirmation.
+pub struct TransferSummary {
+    pub destination: String,
+    /// The word fingerprint of the destination.
+    pub words: String,
+    pub amount: String,
+    pub fee: String,
+    pub memo: String,
+    /// Set if the canister isn't a known ledger, so the amounts are only what
+    /// the arguments claim.
+    pub warning: Option<String>,
+}
+
+/// Returns the details of a transfer on the ICP ledger or an ICRC-1 ledger, or
+/// `None` if the message isn't a transfer. Amounts are annotated with their
+/// estimated value if rates are given. Transfers to a canister which isn't a
+/// known ledger, i.e. the ICP ledger or a cached SNS ledger, carry a warning.
+pub fn transfer_summary(
+    message: &Ingress,
+    rates: Option<&Rates>,
+) -> AnyhowResult<Option<TransferSummary>> {
+    let (_, _, canister_id, method_name, _) = message.inspect()?;
+    let content = envelope::content(&hex::decode(&message.content)?)?;
+    let arg = match envelope::arg(&content) {
+        Some(arg) => arg,
+        None => return Ok(None),
+    };
+    let is_icp_ledger = canister_id == ledger_canister_id();
+    let token = match method_name.as_str() {
+        "send_dfx" if is_icp_ledger => "ICP",
+        "icrc1_transfer" if is_icp_ledger => "ICP",
+        "icrc1_transfer" if is_ckbtc_ledger(canister_id) => "ckBTC",
+        "icrc1_transfer" if is_sns_ledger(canister_id)? => "tokens",
+        "send_dfx" | "icrc1_transfer" => {
+            let mut summary = match transfer_args(&method_name, &arg, "tokens", None)? {
+                Some(summary) => summary,
+                None => return Ok(None),
+            };
+            summary.warning = Some(format!(
+                "Unknown canister: {} isn't a known ledger, so the transfer may not be what it claims",
+                canister_id
+            ));
+            return Ok(Some(summary));
+        }
+        _ => return Ok(None),
+    };
+    transfer_args(&method_name, &arg, token, rates)
+}
+
+#[cfg(feature = "ckbtc")]
+fn is_ckbtc_ledger(canister_id: Principal) -> bool {
+    canister_id.to_text() == CKBTC_LEDGER_CANISTER_ID
+}
+
+#[cfg(not(feature = "ckbtc"))]
+fn is_ckbtc_ledger(_: Principal) -> bool {
+    false
+}
+
+// Returns whether the canister is the ledger of an SNS whose canister ids are cached.
+#[cfg(feature = "sns")]
+fn is_sns_ledger(canister_id: Principal) -> AnyhowResult<bool> {
+    Ok(cached_ledger_ids()?.contains(&canister_id))
+}
+
+#[cfg(not(feature = "sns"))]
+fn is_sns_ledger(_: Principal) -> AnyhowResult<bool> {
+    Ok(false)
+}
+
+// Decodes the arguments of a transfer, with the amounts in the given token.
+fn transfer_args(
+    method_name: &str,
+    arg: &[u8],
+    token: &str,
+    rates: Option<&Rates>,
+) -> AnyhowResult<Option<TransferSummary>> {
+    Ok(match method_name {
+        "send_dfx" => {
+            let args = Decode!(arg, SendArgs)?;
+            let words = fingerprint(&hex::decode(&args.to)?);
+            let destination = match address_name(&args.to)? {
+                Some(name) => format!("{} ({})", args.to, name),
+                None => args.to,
+            };
+            Some(TransferSummary {
+                destination,
+                words,
+                amount: amount(args.amount.e8s, token, rates),
+                fee: amount(args.fee.e8s, token, rates),
+                memo: args.memo.to_string(),
+                warning: None,
+            })
+        }
+        "icrc1_transfer" => {
+            let args = Decode!(arg, TransferArg)?;
+            let tokens = |units: candid::Nat| match units.0.to_string().parse() {
+                Ok(units) => amount(units, token, rates),
+                Err(_) => format!("{} {}", units.0, token),
+            };
+            Some(TransferSummary {
+                destination: args.to.to_string(),
+                words: icrc1_fingerprint(&args.to),
+                amount: tokens(args.amount),
+                fee: args.fee.map_or_else(|| "default".to_string(), tokens),
+                memo: args.memo.map_or_else(|| "none".to_string(), hex::encode),
+                warning: None,
+            })
+        }
+        _ => None,
+    })
+}
+
+/// Reads an answer to a confirmation from the terminal: from STDIN, or from
+/// the controlling terminal if STDIN is a pipe, e.g. of the message itself.
+pub fn read_answer() -> AnyhowResult<String> {
+    let mut input = String::new();
+    if io::stdin().is_terminal() {
+        io::stdin().read_line(&mut input)?;
+        return Ok(input);
+    }
+    let tty = std::fs::File::open("/dev/tty").map_err(|_| {
+        QuillError::Validation(
+            "Can't ask for confirmation without a terminal, use --yes to send without it"
+                .to_string(),
+        )
+    })?;
+    io::BufReader::new(tty).read_line(&mut input)?;
+    Ok(input)
+}
+
+// Formats an amount with grouped digits and its estimated value, if known.
+fn amount(units: u64, token: &str, rates: Option<&Rates>) -> String {
+    let amount = format!("{} {}", group_digits(&format_tokens(units, 8)), token);
+    match rates.and_then(|rates| rates.estimate(token, units as f64 / 1e8)) {
+        Some(estimate) => format!("{} ({})", amount, estimate),
+        None => amount,
+    }
+}
+
+/// Shows the transfer on the alternate screen, or inline without the `tui`
+/// feature, and asks to type the amount. Returns whether the typed amount
+/// matched.
+pub fn confirm_transfer(message: &Ingress, summary: &TransferSummary) -> AnyhowResult<bool> {
+    let envelope = hex::decode(&message.content)?;
+    let verification = envelope::verify(&envelope)?;
+    let fingerprint = envelope::sender_pubkey(&envelope)?.map_or_else(
+        || "(unsigned)".to_string(),
+        |public_key| hex::encode_upper(&sha256(&public_key)[..8]),
+    );
+    // Only the number is typed, without the unit.
+    let amount = summary.amount.split(' ').next().unwrap_or_default();
+
+    let mut stdout = io::stdout();
+    #[cfg(feature = "tui")]
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        Clear(ClearType::All),
+        MoveTo(0, 0)
+    )?;
+    let input = (|| -> AnyhowResult<String> {
+        println!("Confirm the transfer\n");
+        if let Some(warning) = &summary.warning {
+            println!("  WARNING: {}\n", warning);
+        }
+        println!("  Destination:  {}", summary.destination);
+        println!("  Words:        {}", summary.words);
+        println!("  Amount:       {}", summary.amount);
+        println!("  Fee:          {}", summary.fee);
+        println!("  Memo:         {}", summary.memo);
+        println!("  Expiry:       {}", verification.ingress_expiry);
+        println!("  Sender:       {}", verification.sender);
+        println!("  Key:          {}", fingerprint);
+        print!("\nType the amount ({}) to send the transfer: ", amount);
+        stdout.flush()?;
+        read_answer()
+    })();
+    #[cfg(feature = "tui")]
+    execute!(stdout, LeaveAlternateScreen)?;
+    // The digit groups don't have to be typed.
+    Ok(input?.trim().replace(',', "") == amount.replace(',', ""))
+}
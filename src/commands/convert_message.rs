@@ -0,0 +1,94 @@
+use crate::commands::{checksum::print_checksum, print, send::read_bundle, OutputOpts};
+use clap::Clap;
+use quill_core::{
+    bundle::Bundle, dfx::DfxMessage, error::QuillError, ic_url, to_canonical_json, AnyhowResult,
+    Ingress, IngressWithRequestId,
+};
+use std::path::Path;
+
+/// Converts signed messages between the formats of quill and dfx, e.g. to send
+/// messages signed with `dfx canister sign` with quill or the other way round.
+/// `send` and `verify` also read the message files of dfx directly.
+#[derive(Clap)]
+pub struct ConvertMessageOpts {
+    /// Path to the signed messages, or - for STDIN.
+    file_name: String,
+
+    /// The format to convert to.
+    #[clap(long, possible_values(&["dfx", "quill"]))]
+    to: String,
+
+    /// The directory for the message files of dfx, which hold one message each.
+    /// Without it, a single message is printed.
+    #[clap(long)]
+    output_dir: Option<String>,
+}
+
+pub async fn exec(opts: ConvertMessageOpts, output: &OutputOpts) -> AnyhowResult {
+    let bundle = read_bundle(&opts.file_name)?;
+    if opts.to == "quill" {
+        return print_quill(bundle, output).await;
+    }
+
+    let network = ic_url();
+    let messages = bundle
+        .messages
+        .iter()
+        .map(|message| {
+            DfxMessage::from_quill(&message.ingress, message.request_status.as_ref(), &network)
+        })
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    match (&opts.output_dir, messages.as_slice()) {
+        (Some(dir), _) => {
+            let dir = Path::new(dir);
+            std::fs::create_dir_all(dir)?;
+            for (i, message) in messages.iter().enumerate() {
+                let path = dir.join(format!("{}-{}.json", i + 1, message.method_name));
+                let content = serde_json::to_string_pretty(message)?;
+                std::fs::write(&path, &content)?;
+                print_checksum(&path.display().to_string(), content.as_bytes());
+            }
+        }
+        (None, [message]) => println!("{}", serde_json::to_string_pretty(message)?),
+        (None, _) => {
+            return Err(QuillError::Validation(format!(
+                "A message file of dfx holds one message, but there are {}; write them to \
+                 separate files with --output-dir",
+                messages.len()
+            ))
+            .into())
+        }
+    }
+    Ok(())
+}
+
+// Prints the messages in the plain format of quill, or as a bundle if only some
+// of them have a request status.
+pub(crate) async fn print_quill(bundle: Bundle, output: &OutputOpts) -> AnyhowResult {
+    let messages = bundle.messages.clone();
+    if messages
+        .iter()
+        .all(|message| message.request_status.is_some())
+    {
+        let messages: Vec<_> = messages
+            .into_iter()
+            .map(|message| IngressWithRequestId {
+                ingress: message.ingress,
+                request_status: message.request_status.unwrap_or_default(),
+            })
+            .collect();
+        print(&None, &messages, output).await
+    } else if messages
+        .iter()
+        .all(|message| message.request_status.is_none())
+    {
+        let messages: Vec<Ingress> = messages
+            .into_iter()
+            .map(|message| message.ingress)
+            .collect();
+        print(&None, &messages, output).await
+    } else {
+        println!("{}", to_canonical_json(&bundle)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+//! A signing daemon, which serves JSON-RPC 2.0 requests on a unix socket, so
+//! that local services can have messages signed with the identity without
+//! access to the key. What it signs is restricted by the policy given on the
+//! command line.
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use crate::api;
+use crate::commands::{address::resolve_account_id, public::identity_ids, transfer};
+use clap::Clap;
+use ic_types::Principal;
+use ledger_canister::AccountIdentifier;
+use quill_core::{
+    error::{exit_code, QuillError},
+    AnyhowResult, Identity,
+};
+use serde_json::{json, Value};
+
+const METHODS: &[&str] = &["sign_transfer", "sign_neuron_command"];
+
+/// Serves JSON-RPC requests for signing messages on a unix socket, one request
+/// and response per line. Besides the signing methods `sign_transfer` and
+/// `sign_neuron_command`, `public_ids` and `verify_message` are always
+/// available.
+#[derive(Clap)]
+pub struct DaemonOpts {
+    /// The path of the socket, which only the user running quill can access.
+    #[clap(long)]
+    socket: String,
+
+    /// Allows only the given signing methods, by default all are allowed.
+    #[clap(long, possible_values(METHODS))]
+    allow: Vec<String>,
+
+    /// The largest amount of ICP a transfer may have. Neuron commands, whose
+    /// amounts aren't checked, are then refused unless allowed with --allow.
+    #[clap(long, validator(transfer::icpts_amount_validator))]
+    max_amount: Option<String>,
+
+    /// Only allows transfers to the given accounts, or their names in the
+    /// address book. Neuron commands, whose destinations aren't checked, are
+    /// then refused unless allowed with --allow.
+    #[clap(long)]
+    allow_destination: Vec<String>,
+}
+
+// What the daemon may sign.
+struct Policy {
+    methods: Vec<String>,
+    // The methods given with --allow, rather than allowed by default.
+    explicit_methods: Vec<String>,
+    max_e8s: Option<u64>,
+    destinations: Option<Vec<String>>,
+}
+
+impl Policy {
+    // Returns the reason why the request is refused, if it is.
+    fn check(&self, method: &str, params: &Value) -> AnyhowResult<Option<String>> {
+        if METHODS.contains(&method) && !self.methods.iter().any(|allowed| allowed == method) {
+            return Ok(Some(format!("The method {} isn't allowed", method)));
+        }
+        // The amounts and destinations of neuron commands, e.g. of splits or
+        // disbursements, aren't checked, so they must be allowed explicitly.
+        if method == "sign_neuron_command"
+            && (self.max_e8s.is_some() || self.destinations.is_some())
+            && !self
+                .explicit_methods
+                .iter()
+                .any(|allowed| allowed == method)
+        {
+            return Ok(Some(
+                "Neuron commands aren't limited by --max-amount or --allow-destination, \
+                 allow them with --allow sign_neuron_command"
+                    .to_string(),
+            ));
+        }
+        if method != "sign_transfer" {
+            return Ok(None);
+        }
+        if let Some(max_e8s) = self.max_e8s {
+            let amount = transfer::parse_icpts(&string_param(params, "amount")?)
+                .map_err(QuillError::Validation)?;
+            if amount.get_e8s() > max_e8s {
+                return Ok(Some(format!(
+                    "The amount {} exceeds the maximum of the policy",
+                    amount
+                )));
+            }
+        }
+        if let Some(destinations) = &self.destinations {
+            let to = resolve_account_id(&string_param(params, "to")?)?.to_hex();
+            if !destinations.contains(&to) {
+                return Ok(Some(format!("The destination {} isn't allowed", to)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(unix)]
+pub fn exec(pem: &Option<String>, opts: DaemonOpts) -> AnyhowResult {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::fs::FileTypeExt;
+    use std::sync::Arc;
+
+    let pem = pem
+        .clone()
+        .ok_or_else(|| QuillError::Identity("The daemon needs a PEM file".to_string()))?;
+    // The key is loaded once, so that an invalid one fails before any request.
+    let ids = Arc::new(identity_ids(&Identity::from_pem(&pem)?)?);
+    let policy = Arc::new(Policy {
+        methods: if opts.allow.is_empty() {
+            METHODS.iter().map(|method| method.to_string()).collect()
+        } else {
+            opts.allow.clone()
+        },
+        explicit_methods: opts.allow,
+        max_e8s: opts
+            .max_amount
+            .map(|amount| transfer::parse_icpts(&amount).map(|amount| amount.get_e8s()))
+            .transpose()
+            .map_err(QuillError::Validation)?,
+        destinations: if opts.allow_destination.is_empty() {
+            None
+        } else {
+            Some(
+                opts.allow_destination
+                    .iter()
+                    .map(|to| resolve_account_id(to).map(|account| account.to_hex()))
+                    .collect::<AnyhowResult<_>>()?,
+            )
+        },
+    });
+
+    // A socket left over by a daemon which didn't exit cleanly is replaced.
+    if let Ok(metadata) = std::fs::metadata(&opts.socket) {
+        if !metadata.file_type().is_socket() {
+            return Err(QuillError::Validation(format!(
+                "{} exists and isn't a socket",
+                opts.socket
+            ))
+            .into());
+        }
+        std::fs::remove_file(&opts.socket)?;
+    }
+    let listener = bind_private(std::path::Path::new(&opts.socket)).map_err(|err| {
+        QuillError::Validation(format!("Couldn't listen on {}: {}", opts.socket, err))
+    })?;
+    eprintln!("Listening on {}", opts.socket);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Couldn't accept a connection: {}", err);
+                continue;
+            }
+        };
+        let (pem, ids, policy) = (pem.clone(), ids.clone(), policy.clone());
+        std::thread::spawn(move || {
+            let mut writer = match stream.try_clone() {
+                Ok(writer) => writer,
+                Err(_) => return,
+            };
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(line) if line.trim().is_empty() => continue,
+                    Ok(line) => line,
+                    Err(_) => return,
+                };
+                let response = handle(&pem, &ids, &policy, &line);
+                if writeln!(writer, "{}", response).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+// Binds the socket in a new directory which only the user can access and moves
+// it to its path once only the user can connect to it, so that other users
+// can't connect before its permissions are set.
+#[cfg(unix)]
+fn bind_private(path: &std::path::Path) -> std::io::Result<std::os::unix::net::UnixListener> {
+    use std::fs::{DirBuilder, Permissions};
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::UnixListener;
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let dir = parent.join(format!(".quill-daemon-{}", std::process::id()));
+    DirBuilder::new().mode(0o700).create(&dir)?;
+    let private = dir.join("socket");
+    let listener = UnixListener::bind(&private).and_then(|listener| {
+        std::fs::set_permissions(&private, Permissions::from_mode(0o600))?;
+        std::fs::rename(&private, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&private);
+    let _ = std::fs::remove_dir(&dir);
+    listener
+}
+
+#[cfg(not(unix))]
+pub fn exec(_pem: &Option<String>, _opts: DaemonOpts) -> AnyhowResult {
+    Err(QuillError::Validation("The daemon needs unix sockets".to_string()).into())
+}
+
+// Answers a request, logging the decision on the signing methods to STDERR.
+fn handle(
+    pem: &str,
+    (principal_id, account_id): &(Principal, AccountIdentifier),
+    policy: &Policy,
+    line: &str,
+) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => return error(Value::Null, -32700, &err.to_string(), None),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = match request.get("method").and_then(Value::as_str) {
+        Some(method) => method,
+        None => return error(id, -32600, "The request has no method", None),
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let refusal = match policy.check(method, &params) {
+        Ok(refusal) => refusal,
+        Err(err) => return error(id, -32602, &err.to_string(), Some(exit_code(&err))),
+    };
+    if METHODS.contains(&method) {
+        // A machine-readable record of the decision for supervising tools.
+        eprintln!(
+            "{}",
+            json!({
+                "method": method,
+                "params": params,
+                "decision": if refusal.is_some() { "refused" } else { "allowed" },
+                "reason": refusal,
+            })
+        );
+    }
+    if let Some(reason) = refusal {
+        return error(
+            id,
+            -32001,
+            &format!("Refused by the policy: {}", reason),
+            None,
+        );
+    }
+
+    let result = match method {
+        "public_ids" => Ok(json!({
+            "principal_id": principal_id.to_text(),
+            "account_id": account_id.to_string(),
+        })),
+        "sign_transfer" => (|| -> AnyhowResult<Value> {
+            let messages = api::sign_transfer(
+                pem,
+                &string_param(&params, "to")?,
+                &string_param(&params, "amount")?,
+                optional_param(&params, "fee")?.as_deref(),
+                optional_param(&params, "memo")?.as_deref(),
+            )?;
+            Ok(serde_json::from_str(&messages)?)
+        })(),
+        "sign_neuron_command" => serde_json::from_value::<api::NeuronCommand>(params.clone())
+            .map_err(|err| {
+                QuillError::Validation(format!("Invalid neuron command: {}", err)).into()
+            })
+            .and_then(|command| api::sign_neuron_command(pem, &command))
+            .and_then(|messages| Ok(serde_json::from_str::<Value>(&messages)?)),
+        "verify_message" => string_param(&params, "message")
+            .and_then(|message| api::verify_message(&message))
+            .map(|lines| json!(lines)),
+        _ => return error(id, -32601, &format!("Unknown method {}", method), None),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error(id, -32000, &err.to_string(), Some(exit_code(&err))),
+    }
+}
+
+// A JSON-RPC error, with the exit code quill would have for it.
+fn error(id: Value, code: i64, message: &str, exit_code: Option<i32>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(exit_code) = exit_code {
+        error["data"] = json!({ "exit_code": exit_code });
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn string_param(params: &Value, name: &str) -> AnyhowResult<String> {
+    optional_param(params, name)?
+        .ok_or_else(|| QuillError::Validation(format!("The parameter {} is missing", name)).into())
+}
+
+fn optional_param(params: &Value, name: &str) -> AnyhowResult<Option<String>> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => {
+            Err(QuillError::Validation(format!("The parameter {} must be a string", name)).into())
+        }
+    }
+}
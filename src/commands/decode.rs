@@ -0,0 +1,102 @@
+use crate::commands::{send::read_bundle, OutputOpts};
+use anyhow::anyhow;
+use chrono::Utc;
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{
+    envelope, get_response_string, governance_canister_id, to_canonical_json, AnyhowResult,
+};
+
+/// Decodes a response of the governance or ledger canister, or inspects the
+/// messages of a message file without sending them.
+#[derive(Clap)]
+pub struct DecodeOpts {
+    /// Path to a message file, as accepted by `send` (use "-" for STDIN).
+    #[clap(required_unless_present("response"))]
+    file_name: Option<String>,
+
+    /// The hex-encoded candid response.
+    #[clap(long, conflicts_with("file-name"))]
+    response: Option<String>,
+
+    /// The method which returned the response.
+    #[clap(long, default_value("manage_neuron"))]
+    method_name: String,
+
+    /// The canister which returned the response, default is the governance canister.
+    #[clap(long)]
+    canister_id: Option<Principal>,
+}
+
+pub fn exec(opts: DecodeOpts, output: &OutputOpts) -> AnyhowResult {
+    let response = match (&opts.file_name, &opts.response) {
+        (Some(file_name), _) => return inspect(file_name, output),
+        (_, Some(response)) => response,
+        _ => return Err(anyhow!("Either a message file or a response is required")),
+    };
+    let blob = hex::decode(response)?;
+    let canister_id = opts.canister_id.unwrap_or_else(governance_canister_id);
+    let response = get_response_string(&blob, canister_id, &opts.method_name)?;
+    if output.json {
+        println!(
+            "{}",
+            to_canonical_json(&serde_json::json!({ "response": response }))?
+        );
+    } else {
+        println!("{}", response);
+    }
+    Ok(())
+}
+
+// Prints the content of every message in the file, including expired ones.
+fn inspect(file_name: &str, output: &OutputOpts) -> AnyhowResult {
+    let messages: Vec<_> = read_bundle(file_name)?
+        .messages
+        .into_iter()
+        .map(|message| message.ingress)
+        .collect();
+
+    let mut summaries = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        let (expiration, sender, canister_id, method_name, args) = message.inspect()?;
+        let request_id =
+            envelope::request_id(&envelope::content(&hex::decode(&message.content)?)?)?;
+        let expired = Utc::now() > expiration;
+        if output.json {
+            summaries.push(serde_json::json!({
+                "call_type": message.call_type,
+                "sender": sender.to_text(),
+                "canister_id": canister_id.to_text(),
+                "method_name": method_name,
+                "arguments": args,
+                "ingress_expiry": expiration.to_rfc3339(),
+                "expired": expired,
+                "request_id": format!("0x{}", String::from(request_id)),
+            }));
+            continue;
+        }
+        let status = if expired {
+            "expired"
+        } else {
+            "not expired yet"
+        };
+        if i > 0 {
+            println!();
+        }
+        println!("Message {} of {}\n", i + 1, messages.len());
+        println!("  Call type:   {}", message.call_type);
+        println!("  Sender:      {}", sender);
+        println!("  Canister id: {}", canister_id);
+        println!("  Method name: {}", method_name);
+        println!("  Arguments:   {}", args);
+        println!("  Expiry:      {} ({})", expiration, status);
+        println!("  Request id:  0x{}", String::from(request_id));
+    }
+    if output.json {
+        println!(
+            "{}",
+            to_canonical_json(&serde_json::json!({ "messages": summaries }))?
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Comparison of re-signed messages with the messages reviewed before, e.g.
+//! after they expired, so that only the changes have to be approved again.
+
+use crate::commands::send::read_bundle;
+use quill_core::{
+    bundle::{Bundle, BundledMessage},
+    envelope,
+    error::QuillError,
+    AnyhowResult,
+};
+use serde_cbor::Value;
+
+// The fields which differ whenever the same messages are signed again.
+const EXPECTED_CHANGES: &[&str] = &["ingress_expiry", "nonce"];
+
+/// Prints the fields of the messages which differ from the reviewed messages
+/// in the file to STDERR. Changes of other fields than the expiry and the
+/// nonce are warned about, or fail if `strict`.
+pub fn print_diff(reviewed_file: &str, bundle: &Bundle, strict: bool) -> AnyhowResult {
+    let reviewed = read_bundle(reviewed_file)?;
+    let mut unexpected = false;
+    eprintln!(
+        "Changes against the reviewed messages in {}:",
+        reviewed_file
+    );
+    if reviewed.messages.len() != bundle.messages.len() {
+        unexpected = true;
+        eprintln!(
+            "  Number of messages: {} -> {}",
+            reviewed.messages.len(),
+            bundle.messages.len()
+        );
+    }
+    for (old, new) in reviewed.messages.iter().zip(&bundle.messages) {
+        let (old_fields, new_fields) = (fields(old)?, fields(new)?);
+        for ((name, old_value), (_, new_value)) in old_fields.iter().zip(&new_fields) {
+            if old_value != new_value {
+                unexpected |= !EXPECTED_CHANGES.contains(name);
+                eprintln!(
+                    "  Message {} {}: {} -> {}",
+                    new.index + 1,
+                    name,
+                    old_value,
+                    new_value
+                );
+            }
+        }
+    }
+    if !unexpected {
+        eprintln!("Only the expiries and nonces changed.");
+        return Ok(());
+    }
+    let message = format!(
+        "The messages differ from those reviewed in {} in more than their expiries and nonces",
+        reviewed_file
+    );
+    if strict {
+        return Err(QuillError::Validation(message).into());
+    }
+    eprintln!("Warning: {}; review them again.", message);
+    Ok(())
+}
+
+// Returns the compared fields of a message, in the order they are printed.
+fn fields(message: &BundledMessage) -> AnyhowResult<Vec<(&'static str, String)>> {
+    let (expiration, sender, canister_id, method_name, args) = message.ingress.inspect()?;
+    let content = envelope::content(&hex::decode(&message.ingress.content)?)?;
+    let nonce = match &content {
+        Value::Map(m) => match m.get(&Value::Text("nonce".to_string())) {
+            Some(Value::Bytes(nonce)) => hex::encode(nonce),
+            _ => "(none)".to_string(),
+        },
+        _ => "(none)".to_string(),
+    };
+    Ok(vec![
+        ("call_type", message.ingress.call_type.clone()),
+        ("sender", sender.to_text()),
+        ("canister_id", canister_id.to_text()),
+        ("method_name", method_name),
+        ("arguments", args),
+        ("depends_on", format!("{:?}", message.depends_on)),
+        ("ingress_expiry", expiration.to_string()),
+        ("nonce", nonce),
+    ])
+}
@@ -0,0 +1,85 @@
+use crate::commands::checksum::print_checksum;
+use clap::Clap;
+use quill_core::{bundle::Bundle, ic_url, read_from_file, AnyhowResult};
+use std::path::{Path, PathBuf};
+
+/// Writes the envelopes of signed messages as raw CBOR files, the exact bytes
+/// submitted to the replica, e.g. with `curl`.
+#[derive(Clap)]
+pub struct ExportCborOpts {
+    /// Path to the signed message
+    file_name: String,
+
+    /// The directory for the CBOR files.
+    #[clap(long, default_value("."))]
+    output_dir: String,
+}
+
+/// An envelope written to a file, with the endpoint it's submitted to.
+pub struct ExportedEnvelope {
+    pub path: PathBuf,
+    pub canister_id: String,
+    pub endpoint: &'static str,
+}
+
+pub fn exec(opts: ExportCborOpts) -> AnyhowResult {
+    for envelope in write_envelopes(&opts.file_name, &opts.output_dir)? {
+        print_curl(&envelope.path, &envelope.canister_id, envelope.endpoint);
+    }
+    Ok(())
+}
+
+/// Writes the envelopes of the signed messages in the file to the directory,
+/// followed by those of their request status queries, and prints their
+/// checksums.
+pub fn write_envelopes(file_name: &str, output_dir: &str) -> AnyhowResult<Vec<ExportedEnvelope>> {
+    let bundle = Bundle::from_json(&read_from_file(file_name)?)?;
+    let messages = bundle
+        .messages
+        .into_iter()
+        .map(|message| (message.ingress, message.request_status));
+
+    let dir = Path::new(output_dir);
+    std::fs::create_dir_all(dir)?;
+    let mut envelopes = Vec::new();
+    for (i, (ingress, request_status)) in messages.enumerate() {
+        let (_, canister_id, method_name, _) = ingress.parse()?;
+        let endpoint = if ingress.call_type == "query" {
+            "query"
+        } else {
+            "call"
+        };
+        let path = dir.join(format!("{}-{}.cbor", i + 1, method_name));
+        let content = hex::decode(&ingress.content)?;
+        std::fs::write(&path, &content)?;
+        print_checksum(&path.display().to_string(), &content);
+        envelopes.push(ExportedEnvelope {
+            path,
+            canister_id: canister_id.to_text(),
+            endpoint,
+        });
+        if let Some(request_status) = request_status {
+            let path = dir.join(format!("{}-{}-status.cbor", i + 1, method_name));
+            let content = hex::decode(&request_status.content)?;
+            std::fs::write(&path, &content)?;
+            print_checksum(&path.display().to_string(), &content);
+            envelopes.push(ExportedEnvelope {
+                path,
+                canister_id: request_status.canister_id,
+                endpoint: "read_state",
+            });
+        }
+    }
+    Ok(envelopes)
+}
+
+// Prints the command submitting the envelope to the given endpoint.
+fn print_curl(path: &Path, canister_id: &str, endpoint: &str) {
+    println!(
+        "curl -X POST -H 'Content-Type: application/cbor' --data-binary @{} {}/api/v2/canister/{}/{}",
+        path.display(),
+        ic_url(),
+        canister_id,
+        endpoint
+    );
+}
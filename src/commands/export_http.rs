@@ -0,0 +1,46 @@
+use crate::commands::export_cbor::write_envelopes;
+use clap::Clap;
+use quill_core::{http_transport, ic_url, AnyhowResult};
+
+/// Writes the bodies of the HTTP requests submitting signed messages and
+/// prints the complete `curl` commands sending them, so that they can be sent
+/// from environments where quill can't run.
+#[derive(Clap)]
+pub struct ExportHttpOpts {
+    /// Path to the signed message
+    file_name: String,
+
+    /// The directory for the request bodies and the responses.
+    #[clap(long, default_value("."))]
+    output_dir: String,
+}
+
+pub fn exec(opts: ExportHttpOpts) -> AnyhowResult {
+    // The options connecting to the IC like quill, e.g. with `--ca-cert`.
+    let options = http_transport::curl_options()
+        .into_iter()
+        .map(|option| format!("{} ", option))
+        .collect::<String>();
+    for envelope in write_envelopes(&opts.file_name, &opts.output_dir)? {
+        let path = envelope.path.display().to_string();
+        // Calls are only accepted, while queries and status requests are
+        // answered with CBOR, which is saved next to the request.
+        let output = match envelope.endpoint {
+            "call" => String::new(),
+            _ => format!(
+                " --output {}",
+                shell_words::quote(&format!("{}.response", path.trim_end_matches(".cbor")))
+            ),
+        };
+        println!(
+            "curl --fail --request POST {}--header 'Content-Type: application/cbor' --data-binary @{} {}/api/v2/canister/{}/{}{}",
+            options,
+            shell_words::quote(&path),
+            ic_url(),
+            envelope.canister_id,
+            envelope.endpoint,
+            output
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::commands::{checksum::print_checksum, read_state};
+use candid::IDLProg;
+use clap::Clap;
+use ic_types::hash_tree::{Label, LookupResult};
+use ic_types::Principal;
+use quill_core::{cached_candid_path, error::QuillError, AnyhowResult};
+
+/// Fetches the candid interface of a canister from its public metadata and
+/// caches it, so that calls to the canister are signed and decoded offline
+/// with the right types. Copy the cached file to the same place on the
+/// signing computer, or import it there with --from-file.
+#[derive(Clap)]
+pub struct FetchDidOpts {
+    canister_id: Principal,
+
+    /// Caches the interface from the given .did file instead of fetching it,
+    /// e.g. on an air-gapped computer.
+    #[clap(long)]
+    from_file: Option<String>,
+}
+
+pub async fn exec(opts: FetchDidOpts) -> AnyhowResult {
+    let canister_id = opts.canister_id;
+    let did = match &opts.from_file {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|err| QuillError::Validation(format!("Couldn't read {}: {}", path, err)))?,
+        None => fetch(canister_id).await?,
+    };
+    candid::pretty_parse::<IDLProg>("candid:service", &did).map_err(|err| {
+        QuillError::Candid(format!(
+            "The candid interface of {} is invalid: {}",
+            canister_id, err
+        ))
+    })?;
+
+    let path = cached_candid_path(canister_id)?;
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, &did)?;
+    println!(
+        "Cached the candid interface of {} in {}",
+        canister_id,
+        path.display()
+    );
+    print_checksum(&path.display().to_string(), did.as_bytes());
+    Ok(())
+}
+
+// Reads the certified `candid:service` metadata of the canister.
+async fn fetch(canister_id: Principal) -> AnyhowResult<String> {
+    let path = vec![
+        Label::from("canister"),
+        Label::from(canister_id.as_slice()),
+        Label::from("metadata"),
+        Label::from("candid:service"),
+    ];
+    let certificate = read_state::verified_agent()
+        .await?
+        .read_state_raw(vec![path.clone()], canister_id)
+        .await
+        .map_err(|err| {
+            QuillError::Network(format!(
+                "Couldn't read the metadata of {}: {}",
+                canister_id, err
+            ))
+        })?;
+    match certificate.tree.lookup_path(&path) {
+        LookupResult::Found(did) => String::from_utf8(did.to_vec()).map_err(|_| {
+            QuillError::Candid(format!(
+                "The candid interface of {} isn't valid UTF-8",
+                canister_id
+            ))
+            .into()
+        }),
+        _ => Err(QuillError::Network(format!(
+            "The canister {} has no public candid interface in its metadata",
+            canister_id
+        ))
+        .into()),
+    }
+}
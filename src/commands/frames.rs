@@ -0,0 +1,31 @@
+use clap::Clap;
+use quill_core::{error::QuillError, frames, read_from_file, AnyhowResult};
+
+/// Splits a signed message into frames for an animated QR code, one per line.
+/// The `send` command reassembles the message from the frames in any order.
+#[derive(Clap)]
+pub struct FramesOpts {
+    /// Path to the signed message
+    file_name: String,
+
+    /// Maximum number of message bytes per frame.
+    #[clap(long, default_value("300"))]
+    fragment_bytes: usize,
+}
+
+pub fn exec(opts: FramesOpts) -> AnyhowResult {
+    let message = read_from_file(&opts.file_name)?;
+    let frames = frames::encode(message.as_bytes(), opts.fragment_bytes);
+    if frames.len() > frames::MAX_FRAMES {
+        return Err(QuillError::Validation(format!(
+            "The message needs {} frames, more than the maximum of {}; use a larger --fragment-bytes",
+            frames.len(),
+            frames::MAX_FRAMES
+        ))
+        .into());
+    }
+    for frame in frames {
+        println!("{}", frame);
+    }
+    Ok(())
+}
@@ -1,8 +1,6 @@
-use crate::{
-    commands::sign::sign_ingress,
-    lib::{governance_canister_id, sign::signed_message::Ingress, AnyhowResult},
-};
+use crate::commands::sign::sign_ingress;
 use candid::{CandidType, Encode};
+use quill_core::{governance_canister_id, sign::signed_message::Ingress, AnyhowResult};
 
 #[derive(CandidType)]
 pub struct ListNeurons {
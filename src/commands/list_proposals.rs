@@ -0,0 +1,88 @@
+use crate::commands::{neuron_manage::NeuronId, sign::sign_ingress};
+use candid::{CandidType, Encode};
+use clap::Clap;
+use quill_core::{governance_canister_id, sign::signed_message::Ingress, AnyhowResult};
+
+/// Proposal topics in the order of their numeric values, starting at 1.
+pub const TOPICS: &[&str] = &[
+    "neuron-management",
+    "exchange-rate",
+    "network-economics",
+    "governance",
+    "node-admin",
+    "participant-management",
+    "subnet-management",
+    "network-canister-management",
+    "kyc",
+    "node-provider-rewards",
+];
+
+/// Proposal statuses in the order of their numeric values, starting at 1.
+pub const STATUSES: &[&str] = &["open", "rejected", "adopted", "executed", "failed"];
+
+#[derive(CandidType)]
+pub struct ListProposalInfo {
+    pub limit: u32,
+    pub before_proposal: Option<NeuronId>,
+    pub exclude_topic: Vec<i32>,
+    pub include_reward_status: Vec<i32>,
+    pub include_status: Vec<i32>,
+}
+
+/// Signs a query for the proposals, optionally filtered by topic and status.
+#[derive(Clap)]
+pub struct ListProposalsOpts {
+    /// Only list proposals of the given topic. The governance canister can only
+    /// exclude topics, so all the other topics known to quill are excluded, and
+    /// proposals of topics added after this version of quill are listed too.
+    #[clap(long, possible_values(TOPICS))]
+    topic: Option<String>,
+
+    /// Only list proposals with the given status (can be repeated).
+    #[clap(long, possible_values(STATUSES))]
+    status: Vec<String>,
+
+    /// Only list proposals with an id lower than the given one (for pagination).
+    #[clap(long)]
+    before_proposal: Option<u64>,
+
+    /// Maximum number of proposals to list.
+    #[clap(long, default_value("100"))]
+    limit: u32,
+}
+
+pub async fn exec(pem: &Option<String>, opts: ListProposalsOpts) -> AnyhowResult<Vec<Ingress>> {
+    // The governance canister only supports excluding topics, so we exclude
+    // all topics except the requested one (including the unspecified topic 0).
+    let exclude_topic = match &opts.topic {
+        Some(topic) => {
+            let topic = position(TOPICS, topic);
+            (0..=TOPICS.len() as i32).filter(|t| *t != topic).collect()
+        }
+        None => Vec::new(),
+    };
+    let include_status = opts
+        .status
+        .iter()
+        .map(|status| position(STATUSES, status))
+        .collect();
+    let args = Encode!(&ListProposalInfo {
+        limit: opts.limit,
+        before_proposal: opts.before_proposal.map(|id| NeuronId { id }),
+        exclude_topic,
+        include_reward_status: Vec::new(),
+        include_status,
+    })?;
+    Ok(vec![
+        sign_ingress(pem, governance_canister_id(), "list_proposals", args).await?,
+    ])
+}
+
+// Returns the numeric value of an enum name, which is its position plus one.
+fn position(names: &[&str], name: &str) -> i32 {
+    names
+        .iter()
+        .position(|n| *n == name)
+        .expect("Unknown value") as i32
+        + 1
+}
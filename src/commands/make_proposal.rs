@@ -0,0 +1,112 @@
+use crate::commands::{
+    neuron_alias::resolve_neuron_id,
+    neuron_manage::{Command, ManageNeuron, NeuronId},
+    sign::sign_ingress_with_request_status_query,
+};
+use anyhow::{anyhow, Context};
+use candid::{CandidType, Encode, IDLArgs};
+use clap::Clap;
+use quill_core::{
+    get_candid_type, governance_canister_id, read_from_file,
+    sign::signed_message::IngressWithRequestId, AnyhowResult,
+};
+
+#[derive(CandidType)]
+pub struct ExecuteNnsFunction {
+    pub nns_function: i32,
+    pub payload: Vec<u8>,
+}
+
+#[derive(CandidType)]
+pub enum Action {
+    ExecuteNnsFunction(ExecuteNnsFunction),
+}
+
+#[derive(CandidType)]
+pub struct Proposal {
+    pub url: String,
+    pub action: Option<Action>,
+    pub summary: String,
+}
+
+/// Signs a proposal to execute an NNS function.
+#[derive(Clap)]
+pub struct MakeProposalOpts {
+    /// The id or the alias of the proposing neuron.
+    neuron_id: String,
+
+    /// The id of the NNS function to execute.
+    #[clap(long)]
+    nns_function: i32,
+
+    /// Path to the file with the candid arguments of the NNS function.
+    #[clap(long)]
+    payload: String,
+
+    /// Path to the candid interface of the canister executing the NNS function,
+    /// used to encode the arguments with the right types.
+    #[clap(long, requires("method-name"))]
+    candid: Option<String>,
+
+    /// The method of the candid interface whose argument types are used.
+    #[clap(long, requires("candid"))]
+    method_name: Option<String>,
+
+    /// The summary of the proposal.
+    #[clap(long)]
+    summary: String,
+
+    /// The URL with more details on the proposal.
+    #[clap(long, default_value(""))]
+    url: String,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    opts: MakeProposalOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let id = resolve_neuron_id(&opts.neuron_id)?;
+    let args: IDLArgs = read_from_file(&opts.payload)?
+        .parse()
+        .context("Couldn't parse the candid arguments of the payload")?;
+
+    // Encode with the types from the interface, if one is given, and decode
+    // the result again for the preview.
+    let (payload, preview) = match (&opts.candid, &opts.method_name) {
+        (Some(candid), Some(method_name)) => {
+            let (env, func) = get_candid_type(read_from_file(candid)?, method_name)
+                .ok_or_else(|| anyhow!("Method {} not found in {}", method_name, candid))?;
+            let payload = args.to_bytes_with_types(&env, &func.args)?;
+            let preview = IDLArgs::from_bytes_with_types(&payload, &env, &func.args)?;
+            (payload, preview)
+        }
+        _ => {
+            let payload = args.to_bytes()?;
+            let preview = IDLArgs::from_bytes(&payload)?;
+            (payload, preview)
+        }
+    };
+    eprintln!("NNS function: {}", opts.nns_function);
+    eprintln!("Payload:      {}", preview);
+
+    let args = Encode!(&ManageNeuron {
+        id: Some(NeuronId { id }),
+        command: Some(Command::MakeProposal(Proposal {
+            url: opts.url,
+            action: Some(Action::ExecuteNnsFunction(ExecuteNnsFunction {
+                nns_function: opts.nns_function,
+                payload,
+            })),
+            summary: opts.summary,
+        })),
+    })?;
+    Ok(vec![
+        sign_ingress_with_request_status_query(
+            pem,
+            governance_canister_id(),
+            "manage_neuron",
+            args,
+        )
+        .await?,
+    ])
+}
@@ -1,18 +1,67 @@
 //! This module implements the command-line API.
 
-use crate::lib::AnyhowResult;
+use anyhow::Context;
 use clap::Clap;
+use quill_core::{
+    bundle::{Bundle, BundledMessage, Copies},
+    config::Defaults,
+    error::QuillError,
+    set_ic_url, set_offline,
+    sign::signed_message::SIGNED_MESSAGE_V2,
+    to_canonical_json,
+    unsigned::UnsignedMessage,
+    AnyhowResult,
+};
 use std::io::{self, Write};
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
+mod account_id;
+mod address;
+mod attach_signatures;
+mod canister_info;
+mod checksum;
+mod combine;
+mod config;
+mod confirm;
+mod convert_message;
+#[cfg(feature = "daemon")]
+mod daemon;
+mod decode;
+mod diff;
+mod export_cbor;
+mod export_http;
+mod fetch_did;
+#[cfg(feature = "qr")]
+mod frames;
 mod list_neurons;
-mod neuron_manage;
+mod list_proposals;
+mod make_proposal;
+mod neuron_alias;
+pub(crate) mod neuron_manage;
 mod neuron_stake;
+mod neuron_top_up;
+mod pager;
+#[cfg(feature = "plugins")]
+mod plugin;
 mod public;
+mod read_state;
+mod receipt;
 mod request_status;
-mod send;
+mod schema;
+pub(crate) mod send;
+#[cfg(feature = "tui")]
+mod shell;
 mod sign;
-mod transfer;
+#[cfg(feature = "hardware-wallet")]
+mod sign_payload;
+#[cfg(feature = "sns")]
+mod sns;
+mod status;
+mod summarize;
+pub(crate) mod transfer;
+pub(crate) mod verify;
+mod vote;
 
 pub use public::get_ids;
 
@@ -20,35 +69,297 @@ pub use public::get_ids;
 pub enum Command {
     /// Prints the principal id and the account id.
     PublicIds,
+    AccountId(account_id::AccountIdOpts),
     Send(send::SendOpts),
     Transfer(transfer::TransferOpts),
     NeuronStake(neuron_stake::StakeOpts),
     NeuronManage(neuron_manage::ManageOpts),
+    NeuronAlias(neuron_alias::NeuronAliasOpts),
+    Address(address::AddressOpts),
+    NeuronTopUp(neuron_top_up::TopUpOpts),
     /// Signs the query for all neurons belonging to the signin principal.
     ListNeurons,
+    ListProposals(list_proposals::ListProposalsOpts),
+    Decode(decode::DecodeOpts),
+    Config(config::ConfigOpts),
+    Vote(vote::VoteOpts),
+    MakeProposal(make_proposal::MakeProposalOpts),
+    #[cfg(feature = "sns")]
+    Sns(sns::SnsOpts),
+    #[cfg(feature = "qr")]
+    Frames(frames::FramesOpts),
+    ExportCbor(export_cbor::ExportCborOpts),
+    ExportHttp(export_http::ExportHttpOpts),
+    ConvertMessage(convert_message::ConvertMessageOpts),
+    Combine(combine::CombineOpts),
+    #[cfg(feature = "hardware-wallet")]
+    SignPayload(sign_payload::SignPayloadOpts),
+    AttachSignatures(attach_signatures::AttachSignaturesOpts),
+    Verify(verify::VerifyOpts),
+    Checksum(checksum::ChecksumOpts),
+    Summarize(summarize::SummarizeOpts),
+    ReadState(read_state::ReadStateOpts),
+    CanisterInfo(canister_info::CanisterInfoOpts),
+    FetchDid(fetch_did::FetchDidOpts),
+    Status(status::StatusOpts),
+    Schema(schema::SchemaOpts),
+    #[cfg(feature = "daemon")]
+    Daemon(daemon::DaemonOpts),
+    /// Runs an operation of a plugin, `quill <plugin> <operation>`.
+    #[cfg(feature = "plugins")]
+    #[clap(external_subcommand)]
+    Plugin(Vec<String>),
+    /// Starts an interactive session, which runs commands with the PEM file
+    /// read once.
+    #[cfg(feature = "tui")]
+    Shell,
 }
 
-pub fn exec(pem: &Option<String>, cmd: Command) -> AnyhowResult {
+/// Options for printing the generated messages.
+#[derive(Clap)]
+pub struct OutputOpts {
+    /// Prints the messages as a single base64 line, e.g. for copying them
+    /// through serial consoles; `send` accepts this format as well.
+    #[clap(long)]
+    pub compact: bool,
+
+    /// Builds the messages and prints their summary without signing them.
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Forbids all network access, e.g. on an air-gapped computer: messages
+    /// are signed locally, and commands which need the network fail.
+    #[clap(long)]
+    pub offline: bool,
+
+    /// Prints the output of commands as JSON instead of text, e.g. the decoded
+    /// responses of `send` or the ids of `public-ids`.
+    #[clap(long)]
+    pub json: bool,
+
+    /// Prints the signed messages as a versioned bundle with their order,
+    /// dependencies and a checksum, which `send` can resume after a failure.
+    #[clap(long)]
+    pub bundle: bool,
+
+    /// A JSON file with exchange rates, e.g. `{"symbol": "$", "rates": {"ICP": 10.5}}`,
+    /// used to show the estimated value of transfers when sending them.
+    #[clap(long)]
+    pub rates_file: Option<String>,
+
+    /// Prints long outputs directly instead of through the pager ($PAGER).
+    #[clap(long)]
+    pub no_pager: bool,
+
+    /// Writes the signed messages to the given file instead of STDOUT.
+    #[clap(long)]
+    pub file: Option<String>,
+
+    /// Signs the given number of copies of the messages with staggered expiries
+    /// and distinct nonces; `send` picks the first copy which is still valid, so
+    /// that the messages can be sent long after they were signed.
+    #[clap(long, default_value("1"))]
+    pub copies: usize,
+
+    /// The number of seconds between the expiries of consecutive copies.
+    #[clap(long, default_value("240"))]
+    pub copy_interval: u64,
+
+    /// The format of the signed messages: 1 prints the plain messages, 2 adds
+    /// a content hash, the quill version, a readable summary and the position
+    /// in the bundle to each message.
+    #[clap(long, default_value("1"), possible_values(&["1", "2"]))]
+    pub message_version: u32,
+
+    /// The time after which the signed messages expire, in seconds or with the
+    /// unit s, m, h or d, at most the 300 seconds the IC accepts; replaces the
+    /// expiry of the config.
+    #[clap(long, parse(try_from_str = sign::parse_expiry))]
+    pub expire_after: Option<u64>,
+
+    /// A file of messages reviewed before, which the signed messages replace,
+    /// e.g. after they expired; the fields which changed are printed.
+    #[clap(long)]
+    pub reviewed: Option<String>,
+
+    /// Compares the local clock with the certified time of the IC before
+    /// signing, or warns that it has to be checked by hand with --offline.
+    #[clap(long)]
+    pub check_clock: bool,
+
+    /// Fails instead of warning when the messages would expire before the
+    /// handoff estimated in the config is done, or when they differ from the
+    /// reviewed messages in more than their expiries and nonces.
+    #[clap(long)]
+    pub strict: bool,
+
+    /// Builds the messages for an external signer with the given public key, a
+    /// PEM file or hex-encoded DER, and prints them unsigned with the bytes to
+    /// sign; `attach-signatures` turns the signatures into messages to send.
+    #[clap(long)]
+    pub unsigned_envelope: Option<String>,
+
+    /// Signs at the fixed time $QUILL_DETERMINISTIC_TIME (seconds since the
+    /// epoch) with nonces derived from the hex seed $QUILL_DETERMINISTIC_NONCE,
+    /// so that the signed messages can be compared byte for byte. Only for
+    /// tests: the messages are expired.
+    #[clap(long, hidden = true)]
+    pub deterministic: bool,
+}
+
+/// Applies the defaults of the configuration to the options which weren't given.
+pub fn apply_defaults(
+    defaults: &Defaults,
+    output: &mut OutputOpts,
+    cmd: &mut Command,
+) -> AnyhowResult {
+    output.json |= defaults.json.unwrap_or_default();
+    output.compact |= defaults.compact.unwrap_or_default();
+    if let Some(urls) = &defaults.url {
+        for url in urls.split(',').map(str::trim) {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(QuillError::Validation(format!(
+                    "Invalid URL of the IC, it must start with http:// or https://: {}",
+                    url
+                ))
+                .into());
+            }
+        }
+        set_ic_url(urls.clone());
+    }
+    if let Some(expiry) = defaults.expiry {
+        sign::set_ingress_expiry(expiry, output.copy_interval)?;
+    }
+    if let Some(secs) = defaults.handoff_secs {
+        let method = defaults
+            .handoff
+            .clone()
+            .unwrap_or_else(|| "unnamed".to_string());
+        sign::set_handoff(method, secs);
+    }
+    if let Command::Transfer(opts) = cmd {
+        opts.fee = opts.fee.take().or_else(|| defaults.fee.clone());
+    }
+    Ok(())
+}
+
+pub fn exec(pem: &Option<String>, output: &OutputOpts, cmd: Command) -> AnyhowResult {
     let runtime = Runtime::new().expect("Unable to create a runtime");
+    // The options of a command are kept in globals, which are all set for every
+    // command, so that none leaks into the next command of a shell.
+    sign::set_dry_run(output.dry_run);
+    set_offline(output.offline);
+    request_status::set_concurrent(false);
+    sign::set_external_signer(output.unsigned_envelope.as_deref())?;
+    sign::set_deterministic(output.deterministic)?;
+    sign::set_expire_after(output.expire_after, output.copy_interval)?;
+    if signs_messages(&cmd) && !output.dry_run {
+        let extra_secs = (output.copies.max(1) as u64 - 1) * output.copy_interval;
+        sign::check_expiry_window(extra_secs, output.strict)?;
+        if output.check_clock {
+            runtime.block_on(sign::check_clock(output.offline))?;
+        }
+    }
     match cmd {
-        Command::PublicIds => public::exec(pem),
+        Command::PublicIds => public::exec(pem, output),
+        Command::AccountId(opts) => account_id::exec(pem, opts, output),
         Command::Transfer(opts) => {
-            runtime.block_on(async { transfer::exec(pem, opts).await.and_then(|out| print(&out)) })
+            runtime.block_on(async { print(pem, &transfer::exec(pem, opts).await?, output).await })
+        }
+        Command::NeuronStake(opts) => runtime
+            .block_on(async { print(pem, &neuron_stake::exec(pem, opts).await?, output).await }),
+        Command::NeuronManage(opts) => runtime
+            .block_on(async { print(pem, &neuron_manage::exec(pem, opts).await?, output).await }),
+        Command::NeuronAlias(opts) => neuron_alias::exec(opts, output),
+        Command::Address(opts) => address::exec(opts, output),
+        Command::NeuronTopUp(opts) => runtime
+            .block_on(async { print(pem, &neuron_top_up::exec(pem, opts).await?, output).await }),
+        Command::Vote(opts) => {
+            runtime.block_on(async { print(pem, &vote::exec(pem, opts).await?, output).await })
+        }
+        Command::MakeProposal(opts) => runtime
+            .block_on(async { print(pem, &make_proposal::exec(pem, opts).await?, output).await }),
+        #[cfg(feature = "sns")]
+        Command::Sns(opts) => runtime.block_on(async { sns::exec(pem, output, opts).await }),
+        Command::Send(mut opts) => {
+            opts.dry_run |= output.dry_run;
+            runtime.block_on(async { send::exec(pem, opts, output).await })
         }
-        Command::NeuronStake(opts) => runtime.block_on(async {
-            neuron_stake::exec(pem, opts)
-                .await
-                .and_then(|out| print(&out))
-        }),
-        Command::NeuronManage(opts) => runtime.block_on(async {
-            neuron_manage::exec(pem, opts)
-                .await
-                .and_then(|out| print(&out))
-        }),
-        Command::Send(opts) => runtime.block_on(async { send::exec(pem, opts).await }),
         Command::ListNeurons => {
-            runtime.block_on(async { list_neurons::exec(pem).await.and_then(|out| print(&out)) })
+            runtime.block_on(async { print(pem, &list_neurons::exec(pem).await?, output).await })
+        }
+        Command::Decode(opts) => decode::exec(opts, output),
+        Command::Config(opts) => config::exec(opts),
+        #[cfg(feature = "qr")]
+        Command::Frames(opts) => frames::exec(opts),
+        Command::ExportCbor(opts) => export_cbor::exec(opts),
+        Command::AttachSignatures(opts) => {
+            runtime.block_on(async { attach_signatures::exec(opts, output).await })
+        }
+        #[cfg(feature = "hardware-wallet")]
+        Command::SignPayload(opts) => {
+            runtime.block_on(async { sign_payload::exec(pem, opts, output).await })
+        }
+        Command::ConvertMessage(opts) => {
+            runtime.block_on(async { convert_message::exec(opts, output).await })
+        }
+        Command::Combine(opts) => combine::exec(opts, output),
+        Command::ExportHttp(opts) => export_http::exec(opts),
+        Command::Verify(opts) => verify::exec(opts),
+        Command::Checksum(opts) => checksum::exec(opts),
+        Command::Summarize(opts) => summarize::exec(opts, output),
+        Command::ReadState(opts) => {
+            runtime.block_on(async { read_state::exec(opts, output).await })
+        }
+        Command::CanisterInfo(opts) => {
+            runtime.block_on(async { canister_info::exec(opts, output).await })
+        }
+        Command::FetchDid(opts) => runtime.block_on(async { fetch_did::exec(opts).await }),
+        Command::Status(opts) => runtime.block_on(async { status::exec(opts, output).await }),
+        Command::Schema(opts) => schema::exec(opts),
+        #[cfg(feature = "daemon")]
+        Command::Daemon(opts) => daemon::exec(pem, opts),
+        #[cfg(feature = "plugins")]
+        Command::Plugin(words) => {
+            runtime.block_on(async { plugin::exec(pem, words, output).await })
         }
+        #[cfg(feature = "tui")]
+        Command::Shell => shell::exec(pem),
+        Command::ListProposals(opts) => runtime
+            .block_on(async { print(pem, &list_proposals::exec(pem, opts).await?, output).await }),
+    }
+}
+
+// Returns true if the command signs messages which are sent later.
+fn signs_messages(cmd: &Command) -> bool {
+    match cmd {
+        Command::PublicIds
+        | Command::AccountId(_)
+        | Command::Send(_)
+        | Command::NeuronAlias(_)
+        | Command::Address(_)
+        | Command::Decode(_)
+        | Command::Config(_)
+        | Command::ExportCbor(_)
+        | Command::ConvertMessage(_)
+        | Command::Combine(_)
+        | Command::AttachSignatures(_)
+        | Command::ExportHttp(_)
+        | Command::Verify(_)
+        | Command::Checksum(_)
+        | Command::Summarize(_)
+        | Command::ReadState(_)
+        | Command::CanisterInfo(_)
+        | Command::FetchDid(_)
+        | Command::Status(_)
+        | Command::Schema(_) => false,
+        #[cfg(feature = "qr")]
+        Command::Frames(_) => false,
+        #[cfg(feature = "daemon")]
+        Command::Daemon(_) => false,
+        #[cfg(feature = "tui")]
+        Command::Shell => false,
+        _ => true,
     }
 }
 
@@ -56,11 +367,78 @@ pub fn exec(pem: &Option<String>, cmd: Command) -> AnyhowResult {
 // the problem that when the other tool closes its stream, the println! macro
 // panics on the error and the whole binary crashes. This function provides a
 // graceful handling of the error.
-fn print<T>(arg: &T) -> AnyhowResult
+async fn print<T>(pem: &Option<String>, arg: &T, output: &OutputOpts) -> AnyhowResult
 where
     T: ?Sized + serde::ser::Serialize,
 {
-    if let Err(e) = io::stdout().write_all(serde_json::to_string(&arg)?.as_bytes()) {
+    if output.dry_run {
+        return print_dry_run(arg);
+    }
+    if output.message_version == SIGNED_MESSAGE_V2 && (output.bundle || output.copies > 1) {
+        return Err(QuillError::Validation(
+            "Messages of version 2 can't be printed as a bundle or with copies".to_string(),
+        )
+        .into());
+    }
+    let external_signer = sign::external_signer();
+    if external_signer.is_some()
+        && (output.bundle || output.copies > 1 || output.message_version == SIGNED_MESSAGE_V2)
+    {
+        return Err(QuillError::Validation(
+            "Unsigned envelopes can't be printed as a bundle, with copies or as messages of \
+             version 2"
+                .to_string(),
+        )
+        .into());
+    }
+    let mut out = serde_json::to_string(&arg)?;
+    if let Some(reviewed) = &output.reviewed {
+        diff::print_diff(reviewed, &Bundle::from_json(&out)?, output.strict)?;
+    }
+    if let Some(public_key) = external_signer {
+        let messages = Bundle::from_json(&out)?
+            .messages
+            .iter()
+            .map(|message| {
+                UnsignedMessage::new(
+                    &message.ingress,
+                    message.request_status.as_ref(),
+                    &public_key,
+                )
+            })
+            .collect::<AnyhowResult<Vec<_>>>()?;
+        out = to_canonical_json(&messages)?;
+    } else if output.copies > 1 {
+        let bundle = Bundle::from_json(&out)?;
+        let mut copies = Vec::new();
+        for copy in 1..output.copies {
+            let delay = Duration::from_secs(copy as u64 * output.copy_interval);
+            copies.push(sign::sign_copy(pem, &bundle, delay).await?);
+        }
+        copies.insert(0, bundle);
+        out = to_canonical_json(&Copies::new(copies))?;
+    } else if output.bundle {
+        out = to_canonical_json(&Bundle::from_json(&out)?)?;
+    } else if output.message_version == SIGNED_MESSAGE_V2 {
+        out = to_canonical_json(&Bundle::from_json(&out)?.to_v2()?)?;
+    } else {
+        out = to_canonical_json(arg)?;
+    }
+    write_output(out, output)
+}
+
+// Writes the printed messages to STDOUT or the file of `--file`, base64-encoded
+// with `--compact`.
+fn write_output(mut out: String, output: &OutputOpts) -> AnyhowResult {
+    if output.compact {
+        out = base64::encode(out) + "\n";
+    }
+    if let Some(path) = &output.file {
+        std::fs::write(path, &out).with_context(|| format!("Couldn't write {}", path))?;
+        checksum::print_checksum(path, out.as_bytes());
+        return Ok(());
+    }
+    if let Err(e) = io::stdout().write_all(out.as_bytes()) {
         if e.kind() != std::io::ErrorKind::BrokenPipe {
             eprintln!("{}", e);
             std::process::exit(1);
@@ -68,3 +446,21 @@ where
     }
     Ok(())
 }
+
+// Prints the summary of the unsigned messages built with `--dry-run`.
+fn print_dry_run<T>(arg: &T) -> AnyhowResult
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let bundle = Bundle::from_json(&serde_json::to_string(arg)?)?;
+    println!("Dry run, no message was signed.");
+    for BundledMessage { ingress, .. } in bundle.messages {
+        let (_, canister_id, method_name, args) = ingress.parse()?;
+        println!();
+        println!("  Call type:   {}", ingress.call_type);
+        println!("  Canister id: {}", canister_id);
+        println!("  Method name: {}", method_name);
+        println!("  Arguments:   {}", args);
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::commands::OutputOpts;
+use anyhow::anyhow;
+use clap::Clap;
+use quill_core::{aliases::AliasBook, error::QuillError, to_canonical_json, AnyhowResult};
+
+const NEURON_ALIASES_FILE: &str = "neurons.json";
+
+/// Manages the local book of neuron aliases.
+#[derive(Clap)]
+pub struct NeuronAliasOpts {
+    #[clap(subcommand)]
+    command: AliasCommand,
+}
+
+#[derive(Clap)]
+enum AliasCommand {
+    /// Adds (or replaces) an alias for a neuron id.
+    Add { name: String, neuron_id: String },
+    /// Removes an alias.
+    Remove { name: String },
+    /// Lists all aliases.
+    List,
+}
+
+pub fn exec(opts: NeuronAliasOpts, output: &OutputOpts) -> AnyhowResult {
+    let mut book = AliasBook::load(NEURON_ALIASES_FILE)?;
+    match opts.command {
+        AliasCommand::Add { name, neuron_id } => {
+            // Names which parse as neuron ids, e.g. `1_000`, would never be resolved.
+            if parse_neuron_id(&name).is_ok() {
+                return Err(
+                    QuillError::Validation("An alias can't be a number".to_string()).into(),
+                );
+            }
+            let id = parse_neuron_id(&neuron_id)?;
+            book.aliases.insert(name, id.to_string());
+            book.save()
+        }
+        AliasCommand::Remove { name } => {
+            book.aliases
+                .remove(&name)
+                .ok_or_else(|| anyhow!("Unknown alias: {}", name))?;
+            book.save()
+        }
+        AliasCommand::List if output.json => {
+            println!("{}", to_canonical_json(&book.aliases)?);
+            Ok(())
+        }
+        AliasCommand::List => {
+            for (name, id) in &book.aliases {
+                println!("{}: {}", name, id);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Returns the neuron id, resolving it from the alias book if it isn't a number.
+pub fn resolve_neuron_id(id: &str) -> AnyhowResult<u64> {
+    match parse_neuron_id(id) {
+        Ok(id) => Ok(id),
+        Err(_) => parse_neuron_id(AliasBook::load(NEURON_ALIASES_FILE)?.get(id)?),
+    }
+}
+
+fn parse_neuron_id(id: &str) -> AnyhowResult<u64> {
+    id.replace("_", "")
+        .parse()
+        .map_err(|_| anyhow!("Couldn't parse the neuron id: {}", id))
+}
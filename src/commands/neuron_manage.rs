@@ -1,12 +1,20 @@
-use crate::{
-    commands::sign::sign_ingress_with_request_status_query,
-    lib::{governance_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult},
+use crate::commands::{
+    make_proposal::Proposal, neuron_alias::resolve_neuron_id,
+    neuron_stake::ClaimOrRefreshNeuronFromAccount, sign::sign_ingress_with_request_status_query,
+    transfer::parse_icpts,
 };
 use anyhow::anyhow;
 use candid::{CandidType, Encode};
 use clap::Clap;
 use ic_types::Principal;
-use ledger_canister::{AccountIdentifier, ICPTs};
+use ledger_canister::{AccountIdentifier, ICPTs, TRANSACTION_FEE};
+use quill_core::{
+    config::Config, error::QuillError, governance_canister_id,
+    sign::signed_message::IngressWithRequestId, AnyhowResult,
+};
+
+// The minimum stake of a neuron.
+const MINIMUM_STAKE_E8S: u64 = 100_000_000;
 
 #[derive(CandidType)]
 pub struct IncreaseDissolveDelay {
@@ -54,7 +62,7 @@ pub struct Disburse {
     pub amount: Option<ICPTs>,
 }
 
-#[derive(CandidType, Default)]
+#[derive(CandidType)]
 pub struct Spawn {
     pub new_controller: Option<Principal>,
 }
@@ -64,57 +72,82 @@ pub struct Split {
     pub amount_e8s: u64,
 }
 
+#[derive(CandidType)]
+pub struct RegisterVote {
+    pub vote: i32,
+    pub proposal: Option<NeuronId>,
+}
+
+#[derive(CandidType)]
+pub enum By {
+    MemoAndController(ClaimOrRefreshNeuronFromAccount),
+    Memo(u64),
+}
+
+#[derive(CandidType)]
+pub struct ClaimOrRefresh {
+    pub by: Option<By>,
+}
+
 #[derive(CandidType)]
 pub enum Command {
+    ClaimOrRefresh(ClaimOrRefresh),
     Configure(Configure),
+    RegisterVote(RegisterVote),
+    MakeProposal(Proposal),
     Disburse(Disburse),
     Spawn(Spawn),
     Split(Split),
 }
 
 #[derive(CandidType)]
-struct ManageNeuron {
-    id: Option<NeuronId>,
-    command: Option<Command>,
+pub struct ManageNeuron {
+    pub id: Option<NeuronId>,
+    pub command: Option<Command>,
 }
 
 /// Signs a neuron configuration change.
 #[derive(Clap)]
 pub struct ManageOpts {
-    /// The id of the neuron to manage.
-    neuron_id: String,
+    /// The id or the alias of the neuron to manage.
+    pub(crate) neuron_id: String,
 
     /// Principal to be used as a hot key.
     #[clap(long)]
-    add_hot_key: Option<Principal>,
+    pub(crate) add_hot_key: Option<Principal>,
 
     /// Principal hot key to be removed.
     #[clap(long)]
-    remove_hot_key: Option<Principal>,
+    pub(crate) remove_hot_key: Option<Principal>,
 
     /// Number of dissolve seconds to add.
     #[clap(short, long)]
-    additional_dissolve_delay_seconds: Option<u32>,
+    pub(crate) additional_dissolve_delay_seconds: Option<u32>,
 
     /// Start dissolving.
     #[clap(long)]
-    start_dissolving: bool,
+    pub(crate) start_dissolving: bool,
 
     /// Stop dissolving.
     #[clap(long)]
-    stop_dissolving: bool,
+    pub(crate) stop_dissolving: bool,
 
     /// Disburse the entire staked amount to the controller's account.
     #[clap(long)]
-    disburse: bool,
+    pub(crate) disburse: bool,
 
-    /// Spawn rewards to a new neuron under the controller's account.
+    /// Spawn rewards to a new neuron under the controller's account, or under the
+    /// maturity destination if one is configured.
     #[clap(long)]
-    spawn: bool,
+    pub(crate) spawn: bool,
 
-    /// Split off the given number of ICP from a neuron
-    #[clap(long)]
-    split: Option<u64>,
+    /// Split off the given number of ICP from a neuron, which requires its current stake with `--stake`.
+    #[clap(long, requires("stake"))]
+    pub(crate) split: Option<u64>,
+
+    /// The current stake of the neuron (in ICP), used to check that the neuron keeps the minimum stake after a split.
+    #[clap(long, requires("split"))]
+    pub(crate) stake: Option<String>,
 }
 
 pub async fn exec(
@@ -124,7 +157,7 @@ pub async fn exec(
     let mut msgs = Vec::new();
 
     let id = Some(NeuronId {
-        id: parse_neuron_id(opts.neuron_id),
+        id: resolve_neuron_id(&opts.neuron_id)?,
     });
     if opts.add_hot_key.is_some() {
         let args = Encode!(&ManageNeuron {
@@ -194,19 +227,28 @@ pub async fn exec(
     };
 
     if opts.spawn {
+        let new_controller = Config::load()?.maturity_destination()?;
+        if let Some(destination) = new_controller {
+            eprintln!(
+                "Spawning maturity to the configured destination: {}",
+                destination
+            );
+        }
         let args = Encode!(&ManageNeuron {
             id,
-            command: Some(Command::Spawn(Default::default()))
+            command: Some(Command::Spawn(Spawn { new_controller }))
         })?;
         msgs.push(args);
     };
 
     if let Some(amount) = opts.split {
+        let amount_e8s = amount.checked_mul(100_000_000).ok_or_else(|| {
+            QuillError::Validation(format!("The split amount {} ICP is too large", amount))
+        })?;
+        check_split(amount_e8s, opts.stake.as_deref().unwrap_or_default())?;
         let args = Encode!(&ManageNeuron {
             id,
-            command: Some(Command::Split(Split {
-                amount_e8s: amount * 100_000_000
-            }))
+            command: Some(Command::Split(Split { amount_e8s }))
         })?;
         msgs.push(args);
     };
@@ -230,8 +272,36 @@ pub async fn exec(
     Ok(generated)
 }
 
-fn parse_neuron_id(id: String) -> u64 {
-    id.replace("_", "")
-        .parse()
-        .expect("Couldn't parse the neuron id")
+// Prints the stakes resulting from a split and checks that both neurons keep
+// the minimum stake. The fee is deducted from the split amount.
+fn check_split(amount_e8s: u64, stake: &str) -> AnyhowResult {
+    let fee_e8s = TRANSACTION_FEE.get_e8s();
+    let child_e8s = amount_e8s
+        .checked_sub(fee_e8s)
+        .filter(|child_e8s| *child_e8s >= MINIMUM_STAKE_E8S)
+        .ok_or_else(|| {
+            QuillError::Validation(format!(
+                "The split amount must cover the minimum stake of the new neuron ({}) and the fee ({})",
+                ICPTs::from_e8s(MINIMUM_STAKE_E8S),
+                TRANSACTION_FEE
+            ))
+        })?;
+    let stake_e8s = parse_icpts(stake)
+        .map_err(|err| QuillError::Validation(format!("Invalid stake: {}", err)))?
+        .get_e8s();
+    let parent_e8s = stake_e8s
+        .checked_sub(amount_e8s)
+        .filter(|parent_e8s| *parent_e8s >= MINIMUM_STAKE_E8S)
+        .ok_or_else(|| {
+            QuillError::Validation(format!(
+                "The neuron must keep the minimum stake ({}) after the split",
+                ICPTs::from_e8s(MINIMUM_STAKE_E8S)
+            ))
+        })?;
+    eprintln!("New neuron stake:       {} ICP", ICPTs::from_e8s(child_e8s));
+    eprintln!(
+        "Remaining neuron stake: {} ICP",
+        ICPTs::from_e8s(parent_e8s)
+    );
+    Ok(())
 }
@@ -1,13 +1,13 @@
-use crate::{
-    commands::{send::Memo, sign::sign_ingress_with_request_status_query, transfer},
-    lib::{governance_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult},
-};
+use crate::commands::{send::Memo, sign::sign_ingress_with_request_status_query, transfer};
 use anyhow::anyhow;
 use candid::{CandidType, Encode};
 use clap::Clap;
 use ic_nns_constants::GOVERNANCE_CANISTER_ID;
 use ic_types::Principal;
 use ledger_canister::{AccountIdentifier, Subaccount};
+use quill_core::{
+    governance_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult,
+};
 
 #[derive(CandidType)]
 pub struct ClaimOrRefreshNeuronFromAccount {
@@ -26,8 +26,8 @@ pub struct StakeOpts {
     #[clap(long, validator(neuron_name_validator))]
     name: Option<String>,
 
-    /// The nonce of the neuron.
-    #[clap(long, validator(neuron_name_validator), conflicts_with("name"))]
+    /// The nonce of the neuron (used as the memo of the staking transfer).
+    #[clap(long, conflicts_with("name"))]
     nonce: Option<u64>,
 
     /// Transaction fee, default is 10000 e8s.
@@ -47,6 +47,7 @@ pub async fn exec(
     };
     let gov_subaccount = get_neuron_subaccount(&controller, nonce);
     let account = AccountIdentifier::new(GOVERNANCE_CANISTER_ID.get(), Some(gov_subaccount));
+    print_neuron_account(nonce, &gov_subaccount, &account);
     let mut messages = match opts.amount {
         Some(amount) => {
             transfer::exec(
@@ -82,7 +83,7 @@ pub async fn exec(
 
 // This function _must_ correspond to how the governance canister computes the
 // subaccount.
-fn get_neuron_subaccount(controller: &Principal, nonce: u64) -> Subaccount {
+pub fn get_neuron_subaccount(controller: &Principal, nonce: u64) -> Subaccount {
     use openssl::sha::Sha256;
     let mut data = Sha256::new();
     data.update(&[0x0c]);
@@ -92,7 +93,14 @@ fn get_neuron_subaccount(controller: &Principal, nonce: u64) -> Subaccount {
     Subaccount(data.finish())
 }
 
-fn convert_name_to_nonce(name: &str) -> u64 {
+/// Prints the derived neuron account to STDERR, so it can be verified before transferring.
+pub fn print_neuron_account(nonce: u64, subaccount: &Subaccount, account: &AccountIdentifier) {
+    eprintln!("Neuron nonce:      {}", nonce);
+    eprintln!("Neuron subaccount: {}", hex::encode(subaccount.0));
+    eprintln!("Neuron account id: {}", account);
+}
+
+pub fn convert_name_to_nonce(name: &str) -> u64 {
     let mut bytes = std::collections::VecDeque::from(name.as_bytes().to_vec());
     while bytes.len() < 8 {
         bytes.push_front(0)
@@ -102,7 +110,7 @@ fn convert_name_to_nonce(name: &str) -> u64 {
     u64::from_be_bytes(arr)
 }
 
-fn neuron_name_validator(name: &str) -> Result<(), String> {
+pub fn neuron_name_validator(name: &str) -> Result<(), String> {
     // Convert to bytes before checking the length to restrict it to ASCII only
     if name.as_bytes().len() > 8 {
         return Err("The neuron name must be 8 character or less".to_string());
@@ -0,0 +1,93 @@
+use crate::commands::{
+    neuron_manage::{By, ClaimOrRefresh, Command, ManageNeuron},
+    neuron_stake::{
+        convert_name_to_nonce, get_neuron_subaccount, neuron_name_validator, print_neuron_account,
+        ClaimOrRefreshNeuronFromAccount,
+    },
+    send::Memo,
+    sign::sign_ingress_with_request_status_query,
+    transfer,
+};
+use anyhow::anyhow;
+use candid::Encode;
+use clap::Clap;
+use ic_nns_constants::GOVERNANCE_CANISTER_ID;
+use ic_types::Principal;
+use ledger_canister::AccountIdentifier;
+use quill_core::{
+    governance_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult,
+};
+
+/// Signs a top-up of an existing neuron: a transfer to the neuron's subaccount
+/// followed by a refresh of its stake.
+#[derive(Clap)]
+pub struct TopUpOpts {
+    /// ICPs to be added to the neuron's stake.
+    #[clap(long)]
+    amount: String,
+
+    /// The name of the neuron (up to 8 ASCII characters).
+    #[clap(long, validator(neuron_name_validator))]
+    name: Option<String>,
+
+    /// The nonce of the neuron.
+    #[clap(long, conflicts_with("name"))]
+    nonce: Option<u64>,
+
+    /// The controller of the neuron, default is the signing principal.
+    #[clap(long)]
+    controller: Option<Principal>,
+
+    /// Transaction fee, default is 10000 e8s.
+    #[clap(long)]
+    fee: Option<String>,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    opts: TopUpOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let controller = match opts.controller {
+        Some(controller) => controller,
+        None => crate::commands::public::get_ids(pem)?.0,
+    };
+    let nonce = match (&opts.nonce, &opts.name) {
+        (Some(nonce), _) => *nonce,
+        (_, Some(name)) => convert_name_to_nonce(name),
+        _ => return Err(anyhow!("Either a nonce or a name should be specified")),
+    };
+    let gov_subaccount = get_neuron_subaccount(&controller, nonce);
+    let account = AccountIdentifier::new(GOVERNANCE_CANISTER_ID.get(), Some(gov_subaccount));
+    print_neuron_account(nonce, &gov_subaccount, &account);
+    let mut messages = transfer::exec(
+        pem,
+        transfer::TransferOpts {
+            to: account.to_hex(),
+            amount: opts.amount,
+            fee: opts.fee,
+            memo: Some(nonce.to_string()),
+        },
+    )
+    .await?;
+
+    let args = Encode!(&ManageNeuron {
+        id: None,
+        command: Some(Command::ClaimOrRefresh(ClaimOrRefresh {
+            by: Some(By::MemoAndController(ClaimOrRefreshNeuronFromAccount {
+                memo: Memo(nonce),
+                controller: Some(controller),
+            }))
+        })),
+    })?;
+    messages.push(
+        sign_ingress_with_request_status_query(
+            pem,
+            governance_canister_id(),
+            "manage_neuron",
+            args,
+        )
+        .await?,
+    );
+
+    Ok(messages)
+}
@@ -0,0 +1,57 @@
+//! Shows long outputs page by page on terminals.
+
+use crate::commands::OutputOpts;
+#[cfg(feature = "tui")]
+use crossterm::terminal;
+use quill_core::AnyhowResult;
+#[cfg(feature = "tui")]
+use std::io::{self, IsTerminal, Write};
+#[cfg(feature = "tui")]
+use std::process::{Command, Stdio};
+
+#[cfg(feature = "tui")]
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Prints the text directly, since there is no pager without the `tui` feature.
+#[cfg(not(feature = "tui"))]
+pub fn page(text: &str, _: &OutputOpts) -> AnyhowResult {
+    print!("{}", text);
+    Ok(())
+}
+
+/// Prints the text through `$PAGER` if STDOUT is a terminal and the text
+/// doesn't fit on the screen, or directly otherwise.
+#[cfg(feature = "tui")]
+pub fn page(text: &str, output: &OutputOpts) -> AnyhowResult {
+    let fits = terminal::size().map_or(true, |(_, rows)| text.lines().count() < rows as usize);
+    if output.no_pager || fits || !io::stdout().is_terminal() {
+        print!("{}", text);
+        return Ok(());
+    }
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let words = shell_words::split(&pager).unwrap_or_default();
+    let mut child = match words.split_first().and_then(|(program, args)| {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    }) {
+        Some(child) => child,
+        // Without a usable pager, the text is printed as it is.
+        None => {
+            print!("{}", text);
+            return Ok(());
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when it's quit early, which isn't an error.
+        if let Err(err) = stdin.write_all(text.as_bytes()) {
+            if err.kind() != io::ErrorKind::BrokenPipe {
+                return Err(err.into());
+            }
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
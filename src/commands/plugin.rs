@@ -0,0 +1,102 @@
+//! The subcommands of plugins, which sign calls to other canisters as described
+//! by their manifests.
+
+use crate::commands::{
+    print,
+    sign::{sign_ingress, sign_ingress_with_request_status_query},
+    OutputOpts,
+};
+use anyhow::{anyhow, Context};
+use candid::IDLArgs;
+use clap::{App, AppSettings, Arg};
+use quill_core::{get_candid_type, plugin::Manifest, AnyhowResult};
+use std::collections::BTreeMap;
+
+/// Runs `quill <plugin> <operation> [--<name> <value>]...`, given the words
+/// after quill's own options.
+pub async fn exec(pem: &Option<String>, words: Vec<String>, output: &OutputOpts) -> AnyhowResult {
+    let name = words.first().cloned().unwrap_or_default();
+    let manifest = Manifest::load(&name)?;
+
+    let operations: Vec<_> = manifest
+        .operations
+        .iter()
+        .map(|(name, operation)| (name, operation, operation.params()))
+        .collect();
+    let mut app = App::new(name.as_str()).setting(AppSettings::SubcommandRequiredElseHelp);
+    if let Some(description) = &manifest.description {
+        app = app.about(description.as_str());
+    }
+    for (name, operation, params) in &operations {
+        let mut subcommand = App::new(name.as_str());
+        if let Some(description) = &operation.description {
+            subcommand = subcommand.about(description.as_str());
+        }
+        for param in params {
+            subcommand = subcommand.arg(
+                Arg::new(param.as_str())
+                    .long(param.as_str())
+                    .takes_value(true)
+                    .required(true),
+            );
+        }
+        app = app.subcommand(subcommand);
+    }
+    let matches = app
+        .try_get_matches_from(&words)
+        .unwrap_or_else(|err| err.exit());
+    let (operation_name, matches) = matches
+        .subcommand()
+        .ok_or_else(|| anyhow!("No operation given"))?;
+    let (_, operation, params) = operations
+        .iter()
+        .find(|(name, _, _)| name.as_str() == operation_name)
+        .ok_or_else(|| anyhow!("Unknown operation {}", operation_name))?;
+    let values: BTreeMap<_, _> = params
+        .iter()
+        .map(|param| {
+            let value = matches.value_of(param.as_str()).unwrap_or_default();
+            (param.clone(), value.to_string())
+        })
+        .collect();
+
+    let method_name = &operation.method;
+    let (env, func) =
+        get_candid_type(manifest.candid(method_name)?, method_name).ok_or_else(|| {
+            anyhow!(
+                "The candid interface of the plugin {} has no method {}",
+                name,
+                method_name
+            )
+        })?;
+    let args: IDLArgs = operation.fill(&values)?.parse().with_context(|| {
+        format!(
+            "Couldn't parse the arguments of {} {}",
+            name, operation_name
+        )
+    })?;
+    let args = args
+        .to_bytes_with_types(&env, &func.args)
+        .with_context(|| {
+            format!(
+                "The arguments of {} {} don't match the candid interface",
+                name, operation_name
+            )
+        })?;
+    // The arguments are shown with their types, which the messages can't be
+    // decoded with unless the interface is cached.
+    eprintln!(
+        "Arguments: {}",
+        IDLArgs::from_bytes_with_types(&args, &env, &func.args)?
+    );
+
+    let canister_id = manifest.canister_id;
+    if operation.query {
+        let message = sign_ingress(pem, canister_id, method_name, args).await?;
+        print(pem, &vec![message], output).await
+    } else {
+        let message =
+            sign_ingress_with_request_status_query(pem, canister_id, method_name, args).await?;
+        print(pem, &vec![message], output).await
+    }
+}
@@ -1,13 +1,32 @@
-use crate::lib::{get_identity, AnyhowResult};
+use crate::commands::OutputOpts;
 use anyhow::anyhow;
+use ic_agent::Identity;
 use ic_base_types::PrincipalId;
 use ic_types::principal::Principal;
 use ledger_canister::AccountIdentifier;
+use quill_core::{error::QuillError, get_identity, to_canonical_json, AnyhowResult};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::convert::TryFrom;
 
+/// The ids printed with `--json`.
+#[derive(Serialize, JsonSchema)]
+pub struct PublicIds {
+    pub principal_id: String,
+    pub account_id: String,
+}
+
 /// Prints the account and the principal ids.
-pub fn exec(pem: &Option<String>) -> AnyhowResult {
+pub fn exec(pem: &Option<String>, output: &OutputOpts) -> AnyhowResult {
     let (principal_id, account_id) = get_ids(pem)?;
+    if output.json {
+        let ids = PublicIds {
+            principal_id: principal_id.to_text(),
+            account_id: account_id.to_string(),
+        };
+        println!("{}", to_canonical_json(&ids)?);
+        return Ok(());
+    }
     println!("Principal id: {}", principal_id.to_text());
     println!("Account id: {}", account_id);
     Ok(())
@@ -15,12 +34,14 @@ pub fn exec(pem: &Option<String>) -> AnyhowResult {
 
 /// Returns the account id and the principal id if the private key was provided.
 pub fn get_ids(pem: &Option<String>) -> AnyhowResult<(Principal, AccountIdentifier)> {
-    let principal_id = get_identity(
-        pem.as_ref()
-            .ok_or_else(|| anyhow!("No PEM file provided"))?,
-    )
-    .sender()
-    .map_err(|e| anyhow!(e))?;
+    identity_ids(&get_identity(pem.as_ref().ok_or_else(|| {
+        QuillError::Identity("No PEM file provided".to_string())
+    })?)?)
+}
+
+/// Returns the principal id and the account id of a loaded identity.
+pub fn identity_ids(identity: &impl Identity) -> AnyhowResult<(Principal, AccountIdentifier)> {
+    let principal_id = identity.sender().map_err(|e| anyhow!(e))?;
     let base_types_principal =
         PrincipalId::try_from(principal_id.as_slice()).map_err(|err| anyhow!(err))?;
     let account_id = AccountIdentifier::new(base_types_principal, None);
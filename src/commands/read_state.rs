@@ -0,0 +1,161 @@
+use crate::commands::OutputOpts;
+use anyhow::anyhow;
+use chrono::{DateTime, TimeZone, Utc};
+use clap::Clap;
+use ic_agent::Agent;
+use ic_types::hash_tree::{Label, LookupResult};
+use ic_types::Principal;
+use quill_core::{
+    certificate::leb128, error::QuillError, get_agent, governance_canister_id, root_key,
+    to_canonical_json, AnyhowResult,
+};
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+/// Reads paths of the certified state of the IC, such as `time`,
+/// `canister/<id>/module_hash`, `canister/<id>/controllers` or
+/// `subnet/<id>/public_key`, and prints their decoded values.
+#[derive(Clap)]
+pub struct ReadStateOpts {
+    /// The paths, with labels separated by "/". Labels which are principals are
+    /// read as their bytes, labels starting with 0x as hex, others as text.
+    #[clap(required(true))]
+    paths: Vec<String>,
+
+    /// The canister whose subnet is asked, by default the one of the first
+    /// canister path or the governance canister.
+    #[clap(long)]
+    effective_canister_id: Option<Principal>,
+}
+
+pub async fn exec(opts: ReadStateOpts, output: &OutputOpts) -> AnyhowResult {
+    let paths = opts
+        .paths
+        .iter()
+        .map(|path| parse_path(path))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    let effective_canister_id = opts.effective_canister_id.unwrap_or_else(|| {
+        paths
+            .iter()
+            .find_map(|path| match path.as_slice() {
+                [kind, id, ..] if kind.as_slice() == b"canister" => {
+                    Principal::try_from(id.as_slice()).ok()
+                }
+                _ => None,
+            })
+            .unwrap_or_else(governance_canister_id)
+    });
+
+    let values = read(&paths, effective_canister_id).await?;
+
+    let mut json = serde_json::Map::new();
+    for (text, value) in opts.paths.iter().zip(values) {
+        let value = value.unwrap_or_else(|| "(absent)".to_string());
+        if !output.json {
+            println!("{}: {}", text, value);
+        }
+        json.insert(text.clone(), value.into());
+    }
+    if output.json {
+        println!("{}", to_canonical_json(&json)?);
+    }
+    Ok(())
+}
+
+/// Reads the paths from the state of the subnet of the canister and returns
+/// their decoded values, or `None` for absent paths. The agent verifies the
+/// signature of the certificate with the root key.
+pub async fn read(
+    paths: &[Vec<Vec<u8>>],
+    effective_canister_id: Principal,
+) -> AnyhowResult<Vec<Option<String>>> {
+    let agent = verified_agent().await?;
+    let labels = |path: &Vec<Vec<u8>>| path.iter().cloned().map(Label::from).collect::<Vec<_>>();
+    let certificate = agent
+        .read_state_raw(paths.iter().map(labels).collect(), effective_canister_id)
+        .await
+        .map_err(|err| QuillError::Network(format!("Couldn't read the state: {}", err)))?;
+    Ok(paths
+        .iter()
+        .map(|path| match certificate.tree.lookup_path(&labels(path)) {
+            LookupResult::Found(value) => Some(decode(path, value)),
+            LookupResult::Absent => None,
+            LookupResult::Unknown => Some("(unknown, pruned from the certificate)".to_string()),
+            _ => Some("(invalid path)".to_string()),
+        })
+        .collect())
+}
+
+/// Returns the certified time of the IC.
+pub async fn ic_time() -> AnyhowResult<DateTime<Utc>> {
+    let certificate = verified_agent()
+        .await?
+        .read_state_raw(vec![vec![Label::from("time")]], governance_canister_id())
+        .await
+        .map_err(|err| QuillError::Network(format!("Couldn't read the time of the IC: {}", err)))?;
+    match certificate.tree.lookup_path(&[Label::from("time")]) {
+        LookupResult::Found(value) => leb128(value)
+            .map(|nanos| Utc.timestamp_nanos(nanos as i64))
+            .ok_or_else(|| anyhow!("The certified time of the IC is invalid")),
+        _ => Err(anyhow!("The certificate has no time")),
+    }
+}
+
+/// Returns an anonymous agent which only trusts the root key of the mainnet,
+/// unless `--fetch-root-key` was given.
+pub async fn verified_agent() -> AnyhowResult<Agent> {
+    let agent = get_agent(&None)?;
+    root_key::verify(&agent).await?;
+    root_key::trust(&agent).await?;
+    Ok(agent)
+}
+
+// Splits a path into its labels.
+fn parse_path(path: &str) -> AnyhowResult<Vec<Vec<u8>>> {
+    path.trim_matches('/')
+        .split('/')
+        .map(|label| {
+            if let Some(hex) = label.strip_prefix("0x") {
+                return hex::decode(hex).map_err(|_| {
+                    QuillError::Validation(format!("Invalid hex label {} in {}", label, path))
+                        .into()
+                });
+            }
+            Ok(match Principal::from_text(label) {
+                Ok(principal) => principal.as_slice().to_vec(),
+                Err(_) => label.as_bytes().to_vec(),
+            })
+        })
+        .collect()
+}
+
+// Decodes the value at a path as far as its type is known from the last label.
+fn decode(path: &[Vec<u8>], value: &[u8]) -> String {
+    match path.last().map(Vec::as_slice) {
+        Some(b"time") => match leb128(value) {
+            Some(nanos) => format!(
+                "{} ({} ns)",
+                Utc.timestamp_nanos(nanos as i64).to_rfc3339(),
+                nanos
+            ),
+            None => hex::encode(value),
+        },
+        Some(b"controllers") => match serde_cbor::from_slice(value) {
+            Ok(Value::Array(controllers)) => controllers
+                .iter()
+                .map(|controller| match controller {
+                    Value::Bytes(bytes) => Principal::try_from(bytes.as_slice())
+                        .map_or_else(|_| hex::encode(bytes), |principal| principal.to_text()),
+                    _ => "?".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => hex::encode(value),
+        },
+        Some(b"module_hash") | Some(b"public_key") => hex::encode(value),
+        _ => match std::str::from_utf8(value) {
+            Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+            _ => hex::encode(value),
+        },
+    }
+}
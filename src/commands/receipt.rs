@@ -0,0 +1,69 @@
+//! Receipts of sent messages, e.g. for the books of a treasury.
+
+use crate::commands::{checksum::print_checksum, request_status::CertifiedReply};
+use anyhow::Context;
+use candid::{Decode, Nat, Reserved};
+use chrono::{DateTime, Utc};
+use quill_core::{
+    crypto::sha256, get_response_string, sign::signed_message::Ingress, to_canonical_json,
+    AnyhowResult,
+};
+use std::path::Path;
+
+/// Writes the receipt of a replied message as `<request id>.json` to the
+/// directory. The proof of the receipt is the certificate of the IC with the
+/// reply, signed by the IC, and the signed content of the message, whose hash
+/// is the request id the certificate refers to. The receipt also records its
+/// own SHA-256 hash, computed over its canonical JSON without the `sha256`
+/// field, which only detects accidental changes.
+pub fn write(
+    dir: &str,
+    message: &Ingress,
+    reply: &CertifiedReply,
+    submitted_at: DateTime<Utc>,
+) -> AnyhowResult {
+    let (expiration, sender, canister_id, method_name, args) = message.inspect()?;
+    let request_id = message.request_id.clone().unwrap_or_default();
+    let CertifiedReply { reply, certificate } = reply;
+    let mut receipt = serde_json::json!({
+        "quill_version": env!("CARGO_PKG_VERSION"),
+        "request_id": format!("0x{}", request_id),
+        "sender": sender.to_text(),
+        "canister_id": canister_id.to_text(),
+        "method_name": method_name,
+        "arguments": args,
+        "response": get_response_string(reply, canister_id, &method_name)?,
+        "block_height": block_height(&method_name, reply),
+        "ingress_expiry": expiration.to_rfc3339(),
+        "submitted_at": submitted_at.to_rfc3339(),
+        "replied_at": Utc::now().to_rfc3339(),
+        "content": message.content,
+        "certificate": certificate.as_ref().map(hex::encode),
+    });
+    receipt["sha256"] = hex::encode(sha256(to_canonical_json(&receipt)?.as_bytes())).into();
+
+    let path = Path::new(dir).join(format!("{}.json", request_id));
+    let content = to_canonical_json(&receipt)?;
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, &content))
+        .with_context(|| {
+            format!(
+                "The message was sent, but its receipt couldn't be written to {}",
+                path.display()
+            )
+        })?;
+    print_checksum(&path.display().to_string(), content.as_bytes());
+    Ok(())
+}
+
+// Returns the index of the ledger block of a transfer, if the reply is one.
+fn block_height(method_name: &str, reply: &[u8]) -> Option<String> {
+    match method_name {
+        "send_dfx" => Decode!(reply, u64).ok().map(|height| height.to_string()),
+        "icrc1_transfer" => match Decode!(reply, Result<Nat, Reserved>) {
+            Ok(Ok(height)) => Some(height.0.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
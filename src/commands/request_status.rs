@@ -1,23 +1,40 @@
-use crate::lib::sign::sign_transport::{SignReplicaV2Transport, SignedMessageWithRequestId};
-use crate::lib::IC_URL;
-use crate::lib::{get_agent, get_idl_string, sign::signed_message::RequestStatus, AnyhowResult};
+use crate::commands::sign;
 use anyhow::{anyhow, Context};
 use ic_agent::agent::{Replied, RequestStatusResponse};
 use ic_agent::{AgentError, RequestId};
 use ic_types::Principal;
+use quill_core::sign::sign_transport::{SignReplicaV2Transport, SignedMessageWithRequestId};
+use quill_core::{
+    certificate, envelope,
+    error::QuillError,
+    get_agent,
+    http_transport::{self, HttpTransport},
+    retry::{retry, RequestKind},
+    root_key,
+    sign::signed_message::RequestStatus,
+    AnyhowResult,
+};
+use serde_cbor::Value;
 use std::convert::TryInto;
+use std::io::{self, IsTerminal};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
 
 pub async fn sign(
     pem: &Option<String>,
     request_id: RequestId,
     canister_id: Principal,
+    timeout: Duration,
 ) -> AnyhowResult<RequestStatus> {
-    let mut agent = get_agent(pem)?;
+    if sign::is_deterministic() {
+        return sign_at(pem, request_id, canister_id, sign::now() + timeout);
+    }
     let transport = SignReplicaV2Transport::new(Some(request_id));
     let data = transport.data.clone();
-    agent.set_transport(transport);
+    let agent = sign::signing_agent(pem, timeout, transport)?;
     match agent.request_status_raw(&request_id, canister_id).await {
         Err(AgentError::MissingReplicaTransport()) => {
             let message_with_id: SignedMessageWithRequestId =
@@ -28,27 +45,80 @@ pub async fn sign(
     }
 }
 
+// Signs the request status expiring at the given time. The agent always takes
+// the expiry of read_state requests from the clock, so in the deterministic
+// mode the request is built here like the agent would.
+fn sign_at(
+    pem: &Option<String>,
+    request_id: RequestId,
+    canister_id: Principal,
+    expiry: SystemTime,
+) -> AnyhowResult<RequestStatus> {
+    let identity = sign::signing_identity(pem)?;
+    let sender = match &identity {
+        Some(identity) => ic_agent::Identity::sender(identity).map_err(|err| anyhow!(err))?,
+        None => Principal::anonymous(),
+    };
+    let path = Value::Array(vec![
+        Value::Bytes(b"request_status".to_vec()),
+        Value::Bytes(hex::decode(String::from(request_id))?),
+    ]);
+    let expiry = expiry.duration_since(UNIX_EPOCH)?.as_nanos();
+    let content = Value::Map(
+        vec![
+            ("request_type", Value::Text("read_state".to_string())),
+            ("ingress_expiry", Value::Integer(expiry as i128)),
+            ("sender", Value::Bytes(sender.as_slice().to_vec())),
+            ("paths", Value::Array(vec![path])),
+        ]
+        .into_iter()
+        .map(|(key, value)| (Value::Text(key.to_string()), value))
+        .collect(),
+    );
+    let envelope = envelope::sign_content(&content, identity.as_ref())?;
+    Ok(RequestStatus {
+        canister_id: canister_id.to_text(),
+        request_id: String::from(request_id),
+        content: hex::encode(envelope),
+    })
+}
+
+/// The reply to a request, with the certificate of the IC which contains it.
+pub struct CertifiedReply {
+    pub reply: Vec<u8>,
+    /// The certificate of the `read_state` response with the reply.
+    pub certificate: Option<Vec<u8>>,
+}
+
+/// Polls the status of the request until it's replied, and returns the reply.
+/// Fails if it's rejected, or still pending after the timeout.
 pub async fn submit(
     pem: &Option<String>,
     req: &RequestStatus,
-    method_name: Option<String>,
-) -> AnyhowResult<String> {
+    timeout: Option<Duration>,
+    retries: u32,
+) -> AnyhowResult<CertifiedReply> {
     let canister_id = Principal::from_text(&req.canister_id).expect("Couldn't parse canister id");
     let request_id =
         RequestId::from_str(&req.request_id).context("Invalid argument: request_id")?;
     let mut agent = get_agent(pem)?;
+    root_key::trust(&agent).await?;
+    let last_response = Arc::new(Mutex::new(None));
     agent.set_transport(ProxySignReplicaV2Transport {
         req: req.clone(),
-        http_transport: Arc::new(
-            ic_agent::agent::http_transport::ReqwestHttpReplicaV2Transport::create(
-                IC_URL.to_string(),
-            )
-            .unwrap(),
-        ),
+        retries,
+        http_transport: Arc::new(HttpTransport::create()?),
+        last_response: last_response.clone(),
     });
-    let Replied::CallReplied(blob) = async {
+    info!(request_id = %String::from(request_id), "Polling the request status");
+    let mut progress = Progress::new(request_id);
+    let start = Instant::now();
+    let blob = async {
         loop {
-            match agent.request_status_raw(&request_id, canister_id).await? {
+            let status = agent.request_status_raw(&request_id, canister_id).await?;
+            debug!(status = status_name(&status), "Received the request status");
+            progress.update(status_name(&status));
+            match status {
                 RequestStatusResponse::Replied { reply } => return Ok(reply),
                 RequestStatusResponse::Rejected {
                     reject_code,
@@ -61,27 +131,123 @@ pub async fn submit(
                 }
                 RequestStatusResponse::Unknown
                 | RequestStatusResponse::Received
-                | RequestStatusResponse::Processing => {
-                    println!("The request is being processed...");
-                }
+                | RequestStatusResponse::Processing => {}
                 RequestStatusResponse::Done => {
                     return Err(anyhow!(AgentError::RequestStatusDoneNoReply(String::from(
                         request_id
                     ),)))
                 }
             };
+            if let Some(timeout) = timeout.filter(|timeout| start.elapsed() >= *timeout) {
+                return Err(QuillError::Network(format!(
+                    "The request 0x{} is still {} after {} seconds. It may be executed \
+                     later, so check its status before sending it again.",
+                    String::from(request_id),
+                    progress.status,
+                    timeout.as_secs()
+                ))
+                .into());
+            }
+
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+    .await;
+    progress.finish();
+    let Replied::CallReplied(reply) = blob?;
+    let certificate = last_response
+        .lock()
+        .unwrap()
+        .as_deref()
+        .and_then(certificate::certificate);
+    Ok(CertifiedReply { reply, certificate })
+}
+
+// Set when the messages of a bundle are sent concurrently.
+static CONCURRENT: AtomicBool = AtomicBool::new(false);
 
-            std::thread::sleep(std::time::Duration::from_millis(500));
+pub fn set_concurrent(concurrent: bool) {
+    CONCURRENT.store(concurrent, Ordering::Relaxed);
+}
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+// Shows the status of a request while it's polled on STDERR, as a spinner with
+// the elapsed time on terminals and as one line per transition otherwise.
+struct Progress {
+    request_id: RequestId,
+    start: Instant,
+    status: &'static str,
+    ticks: usize,
+    tty: bool,
+}
+
+impl Progress {
+    fn new(request_id: RequestId) -> Self {
+        Self {
+            request_id,
+            start: Instant::now(),
+            status: "",
+            ticks: 0,
+            // The spinners of concurrently polled requests would overwrite each other.
+            tty: io::stderr().is_terminal() && !CONCURRENT.load(Ordering::Relaxed),
+        }
+    }
+
+    fn update(&mut self, status: &'static str) {
+        let elapsed = self.start.elapsed().as_secs_f32();
+        if self.tty {
+            eprint!(
+                "\r{} The request is {} ({:.1}s)\x1b[K",
+                SPINNER[self.ticks % SPINNER.len()],
+                status,
+                elapsed
+            );
+            self.ticks += 1;
+        } else if CONCURRENT.load(Ordering::Relaxed) {
+            if status != self.status {
+                eprintln!(
+                    "The request 0x{} is {} ({:.1}s)",
+                    String::from(self.request_id),
+                    status,
+                    elapsed
+                );
+            }
+        } else if status != self.status {
+            eprintln!("The request is {} ({:.1}s)", status, elapsed);
+        }
+        self.status = status;
+    }
+
+    fn finish(&self) {
+        if self.tty {
+            eprintln!(
+                "\rThe request is {} after {:.1}s\x1b[K",
+                self.status,
+                self.start.elapsed().as_secs_f32()
+            );
         }
     }
-    .await?;
-    get_idl_string(&blob, canister_id, &method_name.unwrap_or_default(), "rets")
-        .context("Invalid IDL blob.")
+}
+
+fn status_name(status: &RequestStatusResponse) -> &'static str {
+    match status {
+        RequestStatusResponse::Unknown => "unknown",
+        RequestStatusResponse::Received => "received",
+        RequestStatusResponse::Processing => "processing",
+        RequestStatusResponse::Replied { .. } => "replied",
+        RequestStatusResponse::Rejected { .. } => "rejected",
+        RequestStatusResponse::Done => "done",
+    }
 }
 
 pub(crate) struct ProxySignReplicaV2Transport {
     req: RequestStatus,
+    retries: u32,
     http_transport: Arc<dyn 'static + ReplicaV2Transport + Send + Sync>,
+    // The last response to a `read_state` request, whose certificate holds the
+    // reply once the request is replied.
+    last_response: Arc<Mutex<Option<Vec<u8>>>>,
 }
 
 use ic_agent::agent::ReplicaV2Transport;
@@ -94,10 +260,33 @@ impl ReplicaV2Transport for ProxySignReplicaV2Transport {
         _canister_id: Principal,
         _content: Vec<u8>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, AgentError>> + Send + 'a>> {
-        self.http_transport.read_state(
-            Principal::from_text(self.req.canister_id.clone()).unwrap(),
-            hex::decode(self.req.content.clone()).unwrap(),
-        )
+        Box::pin(async move {
+            // The reply of a synchronous call makes the request unnecessary.
+            let request_id = RequestId::from_str(&self.req.request_id)
+                .map_err(|err| AgentError::MessageError(err.to_string()))?;
+            if let Some(response) = http_transport::take_certified_reply(&request_id) {
+                debug!("Using the certified reply of the synchronous call");
+                *self.last_response.lock().unwrap() = Some(response.clone());
+                return Ok(response);
+            }
+            let start = Instant::now();
+            debug!(canister_id = %self.req.canister_id, "Sending the read_state request");
+            let canister_id = Principal::from_text(self.req.canister_id.clone()).unwrap();
+            let content = hex::decode(self.req.content.clone()).unwrap();
+            let response = retry(RequestKind::Read, self.retries, || {
+                self.http_transport.read_state(canister_id, content.clone())
+            })
+            .await;
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            match &response {
+                Ok(response) => {
+                    debug!(elapsed_ms, "The read_state request succeeded");
+                    *self.last_response.lock().unwrap() = Some(response.clone());
+                }
+                Err(err) => warn!(elapsed_ms, error = %err, "The read_state request failed"),
+            }
+            response
+        })
     }
 
     fn call<'a>(
@@ -0,0 +1,92 @@
+use crate::commands::{account_id, canister_info, public, send, status};
+use clap::Clap;
+use quill_core::{
+    error::QuillError, unsigned::UnsignedMessage, AnyhowResult, Bundle, Copies, Ingress,
+    IngressWithRequestId, SignedMessageV2,
+};
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+
+/// Prints the JSON Schema of a format written by quill, e.g. to validate the
+/// signed messages or the `--json` outputs in a pipeline. Without a name, lists
+/// the available schemas.
+#[derive(Clap)]
+pub struct SchemaOpts {
+    /// The name of the schema, e.g. `messages` or `bundle`.
+    name: Option<String>,
+}
+
+// A message of version 1, either a call with its status request or a query.
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum MessageV1 {
+    Call(IngressWithRequestId),
+    Query(Ingress),
+}
+
+// The schemas with their descriptions. They are derived from the types which
+// are serialized, so they can't get out of date.
+const SCHEMAS: &[(&str, &str, fn() -> RootSchema)] = &[
+    (
+        "messages",
+        "The signed messages of version 1 (the default)",
+        || schema_for!(Vec<MessageV1>),
+    ),
+    (
+        "messages-v2",
+        "The signed messages of `--message-version 2`",
+        || schema_for!(Vec<SignedMessageV2>),
+    ),
+    ("bundle", "The signed messages of `--bundle`", || {
+        schema_for!(Bundle)
+    }),
+    ("copies", "The signed messages of `--copies`", || {
+        schema_for!(Copies)
+    }),
+    ("unsigned", "The messages of `--unsigned-envelope`", || {
+        schema_for!(Vec<UnsignedMessage>)
+    }),
+    ("public-ids", "The output of `public-ids --json`", || {
+        schema_for!(public::PublicIds)
+    }),
+    ("account-id", "The output of `account-id --json`", || {
+        schema_for!(account_id::AccountIds)
+    }),
+    ("send", "The output of `send --json` per message", || {
+        schema_for!(send::SendSummary)
+    }),
+    (
+        "canister-info",
+        "The output of `canister-info --json`",
+        || schema_for!(canister_info::CanisterInfo),
+    ),
+    ("status", "The output of `status --json`", || {
+        schema_for!(status::Status)
+    }),
+];
+
+pub fn exec(opts: SchemaOpts) -> AnyhowResult {
+    let name = match opts.name {
+        Some(name) => name,
+        None => {
+            for (name, description, _) in SCHEMAS {
+                println!("{:<14} {}", name, description);
+            }
+            return Ok(());
+        }
+    };
+    let schema = SCHEMAS
+        .iter()
+        .find(|(known, _, _)| *known == name)
+        .map(|(_, _, schema)| schema())
+        .ok_or_else(|| {
+            let names = SCHEMAS.iter().map(|(name, _, _)| *name).collect::<Vec<_>>();
+            QuillError::Validation(format!(
+                "Unknown schema {}, use one of {}",
+                name,
+                names.join(", ")
+            ))
+        })?;
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
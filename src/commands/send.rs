@@ -1,17 +1,40 @@
-use crate::commands::request_status;
-use crate::lib::{
-    read_from_file,
-    sign::signed_message::{parse_query_response, Ingress, IngressWithRequestId},
-    AnyhowResult, IC_URL,
-};
-use anyhow::anyhow;
+#[cfg(feature = "qr")]
+use crate::commands::checksum;
+use crate::commands::{confirm, pager, receipt, request_status, OutputOpts};
+use anyhow::{anyhow, Context};
 use candid::CandidType;
+use chrono::Utc;
 use clap::Clap;
+use futures::stream::{FuturesUnordered, StreamExt};
 use ic_agent::agent::ReplicaV2Transport;
-use ic_agent::{agent::http_transport::ReqwestHttpReplicaV2Transport, RequestId};
+use ic_agent::RequestId;
 use ledger_canister::{AccountIdentifier, ICPTs, Subaccount};
+#[cfg(feature = "qr")]
+use quill_core::frames;
+use quill_core::{
+    bundle::{Bundle, BundledMessage},
+    envelope,
+    error::QuillError,
+    get_agent, get_response_string, governance,
+    http_transport::HttpTransport,
+    ic_url,
+    rates::Rates,
+    read_bytes_from_file,
+    retry::{retry, RequestKind},
+    root_key,
+    sign::signed_message::{get_query_reply, parse_query_response, Ingress, RequestStatus},
+    to_canonical_json, AnyhowResult,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const DASHBOARD_URL: &str = "https://dashboard.internetcomputer.org";
 
 #[derive(
     Serialize, Deserialize, CandidType, Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord,
@@ -42,88 +65,541 @@ pub struct SendArgs {
 /// Sends a signed message or a set of messages.
 #[derive(Clap)]
 pub struct SendOpts {
-    /// Path to the signed message
-    file_name: String,
+    /// Path to the signed message, its frames as produced by the `frames` command,
+    /// or a raw CBOR envelope (use "-" for STDIN)
+    #[cfg_attr(feature = "qr", clap(required_unless_present("scan")))]
+    #[cfg_attr(not(feature = "qr"), clap(required = true))]
+    file_name: Option<String>,
 
-    /// Will display the signed message, but not send it.
+    /// Reads the frames of the message from STDIN as they are scanned, e.g.
+    /// from `zbarcam --raw`, until the message is complete.
+    #[cfg(feature = "qr")]
+    #[clap(long, conflicts_with("file-name"))]
+    scan: bool,
+
+    /// Saves the scanned message to the given file.
+    #[cfg(feature = "qr")]
+    #[clap(long, requires("scan"))]
+    save_scanned: Option<String>,
+
+    /// Skips the messages which an earlier run already sent, as recorded in
+    /// `<file>.progress`.
     #[clap(long)]
-    dry_run: bool,
+    #[cfg_attr(feature = "qr", clap(conflicts_with("scan")))]
+    resume: bool,
 
-    /// Skips confirmation and sends the message directly.
+    /// Checks the signatures and expiries of the messages and displays them
+    /// with the URLs they would be submitted to, but doesn't send them.
     #[clap(long)]
+    pub dry_run: bool,
+
+    /// Sends up to this many messages of a bundle at the same time, each once
+    /// the messages it depends on were sent. Requires --yes if more than 1.
+    #[clap(long, default_value("1"))]
+    parallel: usize,
+
+    /// Skips confirmation and sends the message directly.
+    #[clap(short, long)]
     yes: bool,
+
+    /// Confirms transfers on a full screen showing all their details, which
+    /// requires typing the amount.
+    #[clap(long, conflicts_with("yes"))]
+    confirm_screen: bool,
+
+    /// The number of times a request which failed with a transient error, e.g.
+    /// a rate limit or a connection reset, is retried with exponential backoff.
+    #[clap(long, default_value("3"))]
+    retries: u32,
+
+    /// Stops polling the status of a sent message after this many seconds; by
+    /// default it's polled until the message is replied or rejected.
+    #[clap(long)]
+    timeout: Option<u64>,
+
+    /// Writes a receipt of every replied message to the given directory, with
+    /// its request id, decoded arguments and response, block height and times.
+    #[clap(long)]
+    receipts_dir: Option<String>,
+
+    /// Writes the complete neuron records of a `list-neurons` response as JSON to the given file.
+    #[clap(long)]
+    export_json: Option<String>,
+}
+
+pub async fn exec(pem: &Option<String>, opts: SendOpts, output: &OutputOpts) -> AnyhowResult {
+    let json = match &opts.file_name {
+        Some(file_name) => {
+            let content = read_bytes_from_file(file_name)?;
+            if envelope::is_cbor(&content) {
+                let summary = send(&envelope::to_ingress(&content)?, &opts, output).await?;
+                return print_summary(&summary, output);
+            }
+            String::from_utf8(content).map_err(|_| anyhow!("Invalid message file"))?
+        }
+        #[cfg(feature = "qr")]
+        None => {
+            let message = String::from_utf8(frames::scan(std::io::stdin().lock())?)
+                .map_err(|_| anyhow!("The scanned message is not valid UTF-8"))?;
+            if let Some(path) = &opts.save_scanned {
+                std::fs::write(path, &message)?;
+                checksum::print_checksum(path, message.as_bytes());
+            }
+            message
+        }
+        #[cfg(not(feature = "qr"))]
+        None => unreachable!("The file name is required without scanning"),
+    };
+    let bundle = Bundle::from_json(&message_json(json)?)?;
+    send_bundle(pem, &bundle, &opts, output).await
+}
+
+// The messages of a bundle which were sent, saved for `--resume`. The checksum
+// binds them to the bundle, so that the progress of other messages signed into
+// the same file isn't resumed.
+#[derive(Deserialize, Serialize)]
+struct Progress {
+    checksum: String,
+    sent: BTreeSet<usize>,
 }
 
-pub async fn exec(pem: &Option<String>, opts: SendOpts) -> AnyhowResult {
-    let json = read_from_file(&opts.file_name)?;
-    if let Ok(val) = serde_json::from_str::<Ingress>(&json) {
-        send(&val, &opts).await?;
-    } else if let Ok(vals) = serde_json::from_str::<Vec<Ingress>>(&json) {
-        for msg in vals {
-            send(&msg, &opts).await?;
+// Sends the messages of a bundle in order, up to `--parallel` of them at the
+// same time. A message is only sent once its dependencies were sent; messages
+// whose dependencies failed are skipped, as are the messages recorded in the
+// progress file with `--resume`.
+async fn send_bundle(
+    pem: &Option<String>,
+    bundle: &Bundle,
+    opts: &SendOpts,
+    output: &OutputOpts,
+) -> AnyhowResult {
+    let parallel = opts.parallel.max(1);
+    if parallel > 1 && !opts.yes && !opts.dry_run {
+        return Err(QuillError::Validation(
+            "--parallel requires --yes, since concurrently sent messages can't be confirmed one by one"
+                .to_string(),
+        )
+        .into());
+    }
+    request_status::set_concurrent(parallel > 1);
+    let progress_path = opts
+        .file_name
+        .as_ref()
+        .filter(|file_name| *file_name != "-")
+        .map(|file_name| format!("{}.progress", file_name));
+    // The outcome of every message, with its request id, e.g. to check later
+    // what a batch interrupted by rate limiting has sent.
+    let journal_path = opts
+        .file_name
+        .as_ref()
+        .filter(|file_name| *file_name != "-" && !opts.dry_run)
+        .map(|file_name| format!("{}.journal", file_name));
+    let mut sent = BTreeSet::new();
+    if let (Some(path), true) = (&progress_path, opts.resume) {
+        if Path::new(path).exists() {
+            let progress: Progress = serde_json::from_str(&std::fs::read_to_string(path)?)
+                .with_context(|| format!("Couldn't parse {}", path))?;
+            if progress.checksum != bundle.checksum {
+                return Err(QuillError::Validation(format!(
+                    "{} records the progress of other messages than these, e.g. of messages \
+                     signed into the same file before; delete it to send all messages",
+                    path
+                ))
+                .into());
+            }
+            sent = progress.sent;
         }
-    } else if let Ok(vals) = serde_json::from_str::<Vec<IngressWithRequestId>>(&json) {
-        for tx in vals {
-            submit_ingress_and_check_status(pem, &tx, &opts).await?;
+    }
+
+    let mut pending = Vec::new();
+    for message in &bundle.messages {
+        if sent.contains(&message.index) {
+            eprintln!(
+                "Skipping message {}, which was already sent.",
+                message.index
+            );
+        } else {
+            pending.push(message);
         }
-    } else {
-        return Err(anyhow!("Invalid JSON content"));
     }
+    let mut running = FuturesUnordered::new();
+    let mut running_indices = BTreeSet::new();
+    let mut first_error = None;
+    let mut unsent = 0;
+    loop {
+        // Starts the messages whose dependencies were sent, in order, and skips
+        // those with a dependency which failed or was skipped.
+        let mut waiting = Vec::new();
+        for message in pending {
+            let unsent_dep = message.depends_on.iter().find(|dep| !sent.contains(*dep));
+            let dead_dep = message.depends_on.iter().find(|dep| {
+                !sent.contains(*dep)
+                    && !running_indices.contains(*dep)
+                    && !waiting.iter().any(|m: &&BundledMessage| m.index == **dep)
+            });
+            if let Some(dep) = dead_dep {
+                eprintln!(
+                    "Skipping message {}, which depends on message {}.",
+                    message.index, dep
+                );
+                unsent += 1;
+            } else if unsent_dep.is_none() && running.len() < parallel {
+                if parallel > 1 {
+                    eprintln!("Sending message {}.", message.index);
+                }
+                running_indices.insert(message.index);
+                running.push(send_message(pem, message, opts, output));
+            } else {
+                waiting.push(message);
+            }
+        }
+        pending = waiting;
+        let (index, result) = match running.next().await {
+            Some(outcome) => outcome,
+            None => break,
+        };
+        running_indices.remove(&index);
+        if let Some(path) = &journal_path {
+            let message = &bundle.messages[index];
+            append_journal(path, message, &result)?;
+        }
+        match result {
+            Ok(()) => {
+                sent.insert(index);
+                if let (Some(path), false) = (&progress_path, opts.dry_run) {
+                    let progress = Progress {
+                        checksum: bundle.checksum.clone(),
+                        sent: sent.clone(),
+                    };
+                    std::fs::write(path, serde_json::to_string(&progress)?)?;
+                }
+                if parallel > 1 {
+                    eprintln!("Message {} was sent.", index);
+                }
+            }
+            Err(err) => {
+                eprintln!("Message {} failed: {}", index, err);
+                unsent += 1;
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    match first_error {
+        Some(err) => Err(err.context(format!(
+            "{} of {} messages weren't sent, use --resume to send them later",
+            unsent,
+            bundle.messages.len()
+        ))),
+        None => {
+            if let (Some(path), false) = (&progress_path, opts.dry_run) {
+                let _ = std::fs::remove_file(path);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Appends the outcome of sending a message to the journal as a JSON line.
+fn append_journal(path: &str, message: &BundledMessage, result: &AnyhowResult) -> AnyhowResult {
+    let mut entry = serde_json::json!({
+        "index": message.index,
+        "method_name": message.method_name,
+        "request_id": message.ingress.request_id.as_ref().map(|id| format!("0x{}", id)),
+        "time": Utc::now().to_rfc3339(),
+    });
+    match result {
+        Ok(()) => entry["outcome"] = "sent".into(),
+        Err(err) => {
+            entry["outcome"] = "failed".into();
+            entry["error"] = err.to_string().into();
+        }
+    }
+    let mut journal = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Couldn't write the journal {}", path))?;
+    writeln!(journal, "{}", to_canonical_json(&entry)?)?;
     Ok(())
 }
 
+// Sends a message of a bundle, returning its index with the outcome.
+async fn send_message(
+    pem: &Option<String>,
+    message: &BundledMessage,
+    opts: &SendOpts,
+    output: &OutputOpts,
+) -> (usize, AnyhowResult) {
+    let result = match &message.request_status {
+        Some(request_status) => {
+            submit_ingress_and_check_status(pem, &message.ingress, request_status, opts, output)
+                .await
+        }
+        None => match send(&message.ingress, opts, output).await {
+            Ok(summary) => print_summary(&summary, output),
+            Err(err) => Err(err),
+        },
+    };
+    (message.index, result)
+}
+
+/// Reads the messages of a file in any format accepted by `send`.
+pub fn read_bundle(file_name: &str) -> AnyhowResult<Bundle> {
+    let content = read_bytes_from_file(file_name)?;
+    if envelope::is_cbor(&content) {
+        return Bundle::new(vec![(envelope::to_ingress(&content)?, None)]);
+    }
+    let json = String::from_utf8(content).map_err(|_| anyhow!("Invalid message file"))?;
+    Bundle::from_json(&message_json(json)?)
+}
+
+/// Returns the JSON of a message file, reassembling its frames or decoding
+/// the output of `--compact` if necessary.
+pub fn message_json(content: String) -> AnyhowResult<String> {
+    #[cfg(feature = "qr")]
+    if frames::is_frame(&content) {
+        return String::from_utf8(frames::decode(&content)?)
+            .map_err(|_| anyhow!("The reassembled message is not valid UTF-8"));
+    }
+    Ok(decode_compact(&content).unwrap_or(content))
+}
+
+// Decodes a message printed with `--compact`, i.e. base64-encoded JSON.
+fn decode_compact(content: &str) -> Option<String> {
+    let content = content.trim();
+    if content.starts_with('{') || content.starts_with('[') {
+        return None;
+    }
+    String::from_utf8(base64::decode(content).ok()?).ok()
+}
+
 async fn submit_ingress_and_check_status(
     pem: &Option<String>,
-    message: &IngressWithRequestId,
+    ingress: &Ingress,
+    request_status: &RequestStatus,
     opts: &SendOpts,
+    output: &OutputOpts,
 ) -> AnyhowResult {
-    send(&message.ingress, opts).await?;
+    let mut summary = send(ingress, opts, output).await?;
     if opts.dry_run {
-        return Ok(());
+        check_envelope(
+            "request status",
+            &request_status.content,
+            &request_status.request_id,
+            &format!("canister/{}/read_state", request_status.canister_id),
+        )?;
+        return print_summary(&summary, output);
+    }
+    let (_, canister_id, method_name, _) = &ingress.parse()?;
+    let submitted_at = Utc::now();
+    let timeout = opts.timeout.map(Duration::from_secs);
+    let result = match request_status::submit(pem, request_status, timeout, opts.retries).await {
+        Ok(reply) => {
+            if let Some(dir) = &opts.receipts_dir {
+                receipt::write(dir, ingress, &reply, submitted_at)?;
+            }
+            get_response_string(&reply.reply, *canister_id, method_name)
+                .context("Invalid IDL blob.")
+        }
+        Err(err) => Err(err),
+    };
+    // With `--json`, the outcome of the call is part of the summary, so that
+    // every message prints a single object.
+    if output.json {
+        match result {
+            Ok(response) => summary.response = Some(response),
+            Err(err) => summary.error = Some(err.to_string()),
+        }
+        return print_summary(&summary, output);
     }
-    let (_, _, method_name, _) = &message.ingress.parse()?;
-    match request_status::submit(pem, &message.request_status, Some(method_name.to_string())).await
-    {
+    match result {
         Ok(result) => println!("{}\n", result),
         Err(err) => println!("{}\n", err),
     };
     Ok(())
 }
 
-async fn send(message: &Ingress, opts: &SendOpts) -> AnyhowResult {
+/// The outcome of sending a message, printed with `--json`. The optional fields
+/// are only present if they apply.
+#[derive(Default, Serialize, JsonSchema)]
+pub struct SendSummary {
+    pub call_type: String,
+    pub sender: String,
+    pub canister_id: String,
+    pub method_name: String,
+    /// The arguments, decoded to Candid text.
+    pub arguments: String,
+    /// The URL the message would be submitted to, with `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The seconds until the message expires, with `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dashboard: Option<String>,
+    /// The destination of a transfer, with its name from the address book.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_words: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    /// The file the response of a query was exported to, with `--export-json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exported_to: Option<String>,
+    /// The response of a query, or of an update call once it was replied,
+    /// decoded to Candid text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    /// Why the status of an update call couldn't be read, e.g. its rejection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The request id of an update call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// The endpoint which accepted the message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+// Sends a message, printing it unless `--json` is given, and returns its
+// summary for the caller to print once the outcome is complete.
+async fn send(
+    message: &Ingress,
+    opts: &SendOpts,
+    output: &OutputOpts,
+) -> AnyhowResult<SendSummary> {
     let (sender, canister_id, method_name, args) = message.parse()?;
 
-    println!("Sending message with\n");
-    println!("  Call type:   {}", message.call_type);
-    println!("  Sender:      {}", sender);
-    println!("  Canister id: {}", canister_id);
-    println!("  Method name: {}", method_name);
-    println!("  Arguments:   {}", args);
+    // With `--json`, the message and the outcome are printed as one object.
+    let mut summary = SendSummary {
+        call_type: message.call_type.clone(),
+        sender: sender.to_text(),
+        canister_id: canister_id.to_text(),
+        method_name: method_name.clone(),
+        arguments: args.clone(),
+        ..SendSummary::default()
+    };
+    if !output.json {
+        println!("Sending message with\n");
+        println!("  Call type:   {}", message.call_type);
+        println!("  Sender:      {}", sender);
+        println!("  Canister id: {}", canister_id);
+        println!("  Method name: {}", method_name);
+        println!("  Arguments:   {}", args);
+    }
 
     if opts.dry_run {
-        return Ok(());
+        let endpoint = match message.call_type.as_str() {
+            "query" => "query",
+            _ => "call",
+        };
+        let (url, expires_in) = check_envelope(
+            "message",
+            &message.content,
+            message.request_id.as_deref().unwrap_or_default(),
+            &format!("canister/{}/{}", canister_id, endpoint),
+        )?;
+        summary.url = Some(url);
+        summary.expires_in_secs = Some(expires_in);
+        return Ok(summary);
     }
 
-    if message.call_type == "update" && !opts.yes {
-        println!("\nDo you want to send this message? [y/N]");
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        if !["y", "yes"].contains(&input.to_lowercase().trim()) {
+    // Shown before submitting, so they can be recorded even if it fails.
+    let dashboard = format!("{}/canister/{}", DASHBOARD_URL, canister_id);
+    if !output.json {
+        if let Some(request_id) = &message.request_id {
+            println!("  Request ID:  0x{}", request_id);
+        }
+        println!("  Dashboard:   {}", dashboard);
+    }
+    summary.dashboard = Some(dashboard);
+
+    if message.call_type == "update" {
+        let rates = output.rates_file.as_deref().map(Rates::load).transpose()?;
+        let transfer = confirm::transfer_summary(message, rates.as_ref())?;
+        // The destination is always shown, with its name from the address book.
+        if let Some(transfer) = &transfer {
+            if let Some(warning) = &transfer.warning {
+                eprintln!("Warning: {}", warning);
+            }
+            if output.json {
+                eprintln!("Destination: {}", transfer.destination);
+                eprintln!("Words: {}", transfer.words);
+                eprintln!("Amount: {}", transfer.amount);
+            } else {
+                println!("  Destination: {}", transfer.destination);
+                println!("  Words:       {}", transfer.words);
+                println!("  Amount:      {}", transfer.amount);
+            }
+            summary.destination = Some(transfer.destination.clone());
+            summary.destination_words = Some(transfer.words.clone());
+            summary.amount = Some(transfer.amount.clone());
+        }
+        let transfer = transfer.filter(|_| opts.confirm_screen);
+        let confirmed = opts.yes
+            || if let Some(summary) = &transfer {
+                confirm::confirm_transfer(message, summary)?
+            } else {
+                // The prompt mustn't end up in the JSON output.
+                if output.json {
+                    eprintln!("Do you want to send this message? [y/N]");
+                } else {
+                    println!("\nDo you want to send this message? [y/N]");
+                }
+                let input = confirm::read_answer()?;
+                ["y", "yes"].contains(&input.to_lowercase().trim())
+            };
+        let confirmation = match (confirmed, opts.yes, &transfer) {
+            (false, _, _) => "declined",
+            (true, true, _) => "flag",
+            (true, false, Some(_)) => "screen",
+            (true, false, None) => "prompt",
+        };
+        // A machine-readable record of the decision for supervising tools.
+        eprintln!(
+            "{}",
+            serde_json::json!({
+                "confirmation": confirmation,
+                "sender": sender.to_text(),
+                "canister_id": canister_id.to_text(),
+                "method_name": method_name,
+                "request_id": message.request_id,
+            })
+        );
+        if !confirmed {
             std::process::exit(0);
         }
     }
 
-    let transport = ReqwestHttpReplicaV2Transport::create(IC_URL.to_string())?;
+    root_key::verify(&get_agent(&None)?).await?;
+    let transport = HttpTransport::create()?;
     let content = hex::decode(&message.content)?;
 
     match message.call_type.as_str() {
         "query" => {
-            let response = parse_query_response(
-                transport.query(canister_id, content).await?,
-                canister_id,
-                &method_name,
-            )?;
-            println!("Response: {}", response);
+            let start = Instant::now();
+            info!(url = %ic_url(), %canister_id, %method_name, "Sending the query");
+            let response = retry(RequestKind::Read, opts.retries, || {
+                transport.query(canister_id, content.clone())
+            })
+            .await;
+            log_outcome(&response, start);
+            let response = response?;
+            if let Some(path) = &opts.export_json {
+                let reply = get_query_reply(response)?.map_err(|rejection| anyhow!(rejection))?;
+                std::fs::write(path, governance::export_json(&reply, &method_name)?)?;
+                if !output.json {
+                    println!("Exported the response to {}", path);
+                }
+                summary.exported_to = Some(path.clone());
+            } else {
+                let response = parse_query_response(response, canister_id, &method_name)?;
+                if !output.json {
+                    pager::page(&format!("Response: {}\n", response), output)?;
+                }
+                summary.response = Some(response);
+            }
         }
         "update" => {
             let request_id = RequestId::from_str(
@@ -132,11 +608,93 @@ async fn send(message: &Ingress, opts: &SendOpts) -> AnyhowResult {
                     .request_id
                     .expect("Cannot get request_id from the update message"),
             )?;
-            transport.call(canister_id, content, request_id).await?;
+            let start = Instant::now();
+            info!(
+                url = %ic_url(),
+                %canister_id,
+                %method_name,
+                request_id = %String::from(request_id),
+                "Submitting the update call"
+            );
+            let result = retry(RequestKind::Submission, opts.retries, || {
+                transport.call_sync(canister_id, content.clone(), request_id)
+            })
+            .await;
+            log_outcome(&result, start);
+            result?;
             let request_id = format!("0x{}", String::from(request_id));
-            println!("Request ID: {}", request_id);
+            if !output.json {
+                println!("Request ID: {}", request_id);
+            }
+            summary.request_id = Some(request_id);
         }
         _ => unreachable!(),
     }
+    // The endpoint which accepted the message, one of several given with `--url`.
+    if let Some(endpoint) = transport.endpoint() {
+        if !output.json {
+            println!("Endpoint:   {}", endpoint);
+        }
+        summary.endpoint = Some(endpoint);
+    }
+    Ok(summary)
+}
+
+// Checks the signature, the request id and the expiry of a signed envelope for
+// a dry run, and prints them with the URL it would be submitted to, which is
+// returned with the seconds until it expires.
+fn check_envelope(
+    kind: &str,
+    content: &str,
+    request_id: &str,
+    endpoint: &str,
+) -> AnyhowResult<(String, i64)> {
+    let verification = envelope::verify(&hex::decode(content)?)?;
+    match verification.signature_valid {
+        Some(true) => (),
+        Some(false) => {
+            return Err(
+                QuillError::Validation(format!("The signature of the {} is invalid", kind)).into(),
+            )
+        }
+        None => return Err(QuillError::Validation(format!("The {} isn't signed", kind)).into()),
+    }
+    let computed = String::from(verification.request_id);
+    if !request_id.is_empty() && computed != request_id {
+        return Err(QuillError::Validation(format!(
+            "The recorded request id 0x{} of the {} doesn't match its content",
+            request_id, kind
+        ))
+        .into());
+    }
+    let expires_in = (verification.ingress_expiry - Utc::now()).num_seconds();
+    if expires_in < 0 {
+        return Err(QuillError::Validation(format!(
+            "The {} expired at {}",
+            kind, verification.ingress_expiry
+        ))
+        .into());
+    }
+    let url = format!("{}/api/v2/{}", ic_url().trim_end_matches('/'), endpoint);
+    eprintln!(
+        "Dry run: the {} 0x{} is validly signed by {}, expires in {} seconds and would be submitted to {}",
+        kind, computed, verification.sender, expires_in, url
+    );
+    Ok((url, expires_in))
+}
+
+fn print_summary(summary: &SendSummary, output: &OutputOpts) -> AnyhowResult {
+    if output.json {
+        println!("{}", to_canonical_json(summary)?);
+    }
     Ok(())
 }
+
+// Logs the time a request took and its error, if it failed.
+pub(crate) fn log_outcome<T, E: std::fmt::Display>(result: &Result<T, E>, start: Instant) {
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(_) => info!(elapsed_ms, "The request succeeded"),
+        Err(err) => warn!(elapsed_ms, error = %err, "The request failed"),
+    }
+}
@@ -0,0 +1,65 @@
+//! An interactive session, which runs several commands with the PEM file read
+//! only once. The options of each line apply to its command only.
+
+use crate::commands::{exec as exec_command, Command, OutputOpts};
+use clap::{AppSettings, Clap};
+use quill_core::{error::exit_code, AnyhowResult};
+use std::io::{self, BufRead, Write};
+
+/// A line of the session, which takes the same options and commands as quill.
+#[derive(Clap)]
+#[clap(name("quill"), setting = AppSettings::NoBinaryName)]
+struct ShellLine {
+    #[clap(flatten)]
+    output: OutputOpts,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Reads commands from STDIN until `exit` or the end of the input. No history
+/// of the lines is kept, as they may contain secrets.
+pub fn exec(pem: &Option<String>) -> AnyhowResult {
+    if pem.is_none() {
+        eprintln!("No PEM file provided, messages will be signed by the anonymous identity.");
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        eprint!("quill> ");
+        io::stderr().flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => {
+                eprintln!();
+                return Ok(());
+            }
+        };
+        let words = match shell_words::split(&line) {
+            Ok(words) => words,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            }
+        };
+        match words.first().map(String::as_str) {
+            None => continue,
+            Some("exit") | Some("quit") => return Ok(()),
+            _ => {}
+        }
+        let ShellLine { output, command } = match ShellLine::try_parse_from(words) {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            }
+        };
+        if let Command::Shell = command {
+            eprintln!("Already in a shell.");
+            continue;
+        }
+        if let Err(err) = exec_command(pem, &output, command) {
+            eprintln!("{} (exit code {})", err, exit_code(&err));
+        }
+    }
+}
@@ -1,39 +1,336 @@
-use crate::commands::request_status;
-use crate::lib::{
-    get_agent, get_candid_type, get_local_candid,
+use crate::commands::{read_state, request_status};
+use anyhow::anyhow;
+use chrono::Utc;
+use ic_agent::{agent::ReplicaV2Transport, Agent, AgentError};
+use ic_types::principal::Principal;
+use openssl::{pkey::PKey, sha::sha256};
+use quill_core::{
+    bundle::Bundle,
+    envelope,
+    error::QuillError,
+    get_agent_with_identity, get_agent_with_nonce, get_candid_type, get_identity, get_local_candid,
+    read_from_file,
     sign::sign_transport::{SignReplicaV2Transport, SignedMessageWithRequestId},
     sign::signed_message::{Ingress, IngressWithRequestId},
-    AnyhowResult,
+    AnyhowResult, Identity,
 };
-use anyhow::anyhow;
-use ic_agent::AgentError;
-use ic_types::principal::Principal;
 use std::convert::TryInto;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Set by the global `--dry-run` flag. The messages are then built for the
+// anonymous identity, so the private key is never used to sign anything.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+// Returns the key to sign with, which is none in a dry run.
+fn signing_pem(pem: &Option<String>) -> &Option<String> {
+    if DRY_RUN.load(Ordering::Relaxed) {
+        &None
+    } else {
+        pem
+    }
+}
+
+// Set by `--unsigned-envelope`: the DER-encoded public key of the external
+// signer, for which the messages are built but left unsigned.
+static EXTERNAL_SIGNER: RwLock<Option<Vec<u8>>> = RwLock::new(None);
+
+/// Reads the public key of an external signer, as a PEM file or hex-encoded
+/// DER, and builds the messages for it without signing them.
+pub fn set_external_signer(path: Option<&str>) -> AnyhowResult {
+    *EXTERNAL_SIGNER.write().unwrap() = None;
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let content = read_from_file(path)?;
+    let public_key = match PKey::public_key_from_pem(content.as_bytes()) {
+        Ok(key) => key.public_key_to_der()?,
+        Err(_) => hex::decode(content.trim()).map_err(|_| {
+            QuillError::Validation(format!(
+                "{} is neither a PEM public key nor a hex-encoded DER one",
+                path
+            ))
+        })?,
+    };
+    *EXTERNAL_SIGNER.write().unwrap() = Some(public_key);
+    Ok(())
+}
+
+/// Returns the public key of the external signer, if the messages are left
+/// unsigned for one.
+pub fn external_signer() -> Option<Vec<u8>> {
+    EXTERNAL_SIGNER.read().unwrap().clone()
+}
+
+/// Returns the identity which signs the messages: none in a dry run, and the
+/// external signer if the messages are left unsigned.
+pub fn signing_identity(pem: &Option<String>) -> AnyhowResult<Option<Identity>> {
+    match external_signer() {
+        Some(public_key) => Ok(Some(Identity::External(public_key))),
+        None => signing_pem(pem).as_deref().map(get_identity).transpose(),
+    }
+}
+
+/// Returns an agent which builds the messages for the transport, signed with
+/// the key, or left unsigned for the external signer.
+pub fn signing_agent<T>(
+    pem: &Option<String>,
+    timeout: Duration,
+    transport: T,
+) -> AnyhowResult<Agent>
+where
+    T: 'static + ReplicaV2Transport + Send + Sync,
+{
+    let identity = signing_identity(pem)?;
+    match deterministic() {
+        Some(_) => get_agent_with_nonce(identity, next_nonce(), timeout, transport),
+        None => get_agent_with_identity(identity, timeout, transport),
+    }
+}
+
+// The environment variables of the deterministic mode: the time at which the
+// messages are signed, as seconds since the epoch, and the hex-encoded seed of
+// the nonces.
+pub const DETERMINISTIC_TIME_VAR: &str = "QUILL_DETERMINISTIC_TIME";
+pub const DETERMINISTIC_NONCE_VAR: &str = "QUILL_DETERMINISTIC_NONCE";
+
+// 2021-05-10T00:00:00Z, the time of the test vectors.
+const DEFAULT_DETERMINISTIC_TIME: u64 = 1_620_604_800;
+
+// Set by the hidden `--deterministic` flag: the fixed time of signing, the seed
+// of the nonces and the number of nonces taken so far.
+static DETERMINISTIC: RwLock<Option<(SystemTime, Vec<u8>)>> = RwLock::new(None);
+static NONCES: AtomicU64 = AtomicU64::new(0);
+
+/// Makes the signed messages reproducible: they are signed at a fixed time
+/// and with nonces derived from a fixed seed, both taken from the environment,
+/// so that the same command always prints the same bytes.
+pub fn set_deterministic(deterministic: bool) -> AnyhowResult {
+    *DETERMINISTIC.write().unwrap() = None;
+    if !deterministic {
+        return Ok(());
+    }
+    let secs = match std::env::var(DETERMINISTIC_TIME_VAR) {
+        Ok(secs) => secs.trim().parse::<u64>().map_err(|_| {
+            QuillError::Validation(format!(
+                "{} must be the number of seconds since the epoch",
+                DETERMINISTIC_TIME_VAR
+            ))
+        })?,
+        Err(_) => DEFAULT_DETERMINISTIC_TIME,
+    };
+    let seed = match std::env::var(DETERMINISTIC_NONCE_VAR) {
+        Ok(seed) => hex::decode(seed.trim().trim_start_matches("0x")).map_err(|_| {
+            QuillError::Validation(format!("{} must be hex", DETERMINISTIC_NONCE_VAR))
+        })?,
+        Err(_) => Vec::new(),
+    };
+    let time = UNIX_EPOCH + Duration::from_secs(secs);
+    *DETERMINISTIC.write().unwrap() = Some((time, seed));
+    NONCES.store(0, Ordering::Relaxed);
+    Ok(())
+}
+
+fn deterministic() -> Option<(SystemTime, Vec<u8>)> {
+    DETERMINISTIC.read().unwrap().clone()
+}
+
+/// Returns the time of signing, which is fixed in the deterministic mode.
+pub fn now() -> SystemTime {
+    deterministic().map_or_else(SystemTime::now, |(time, _)| time)
+}
+
+// Returns the next nonce of the deterministic mode, the first 16 bytes of the
+// SHA-256 hash of the seed and the number of the nonce, so that identical
+// calls of one command still get distinct request ids.
+fn next_nonce() -> Vec<u8> {
+    let seed = deterministic().map(|(_, seed)| seed).unwrap_or_default();
+    let n = NONCES.fetch_add(1, Ordering::Relaxed);
+    sha256(&[seed, n.to_be_bytes().to_vec()].concat())[..16].to_vec()
+}
+
+/// Returns whether the messages are signed in the deterministic mode.
+pub fn is_deterministic() -> bool {
+    deterministic().is_some()
+}
+
+// The number of seconds after which signed messages expire, as configured. The
+// default is the maximum accepted by the replica at the time of submission.
+const MAX_INGRESS_EXPIRY_SECS: u64 = 5 * 60;
+static INGRESS_EXPIRY_SECS: AtomicU64 = AtomicU64::new(MAX_INGRESS_EXPIRY_SECS);
+
+// Set by `--expire-after` for a single command, replacing the configured expiry;
+// 0 if it isn't given.
+static EXPIRE_AFTER_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// Parses an expiry given in seconds, optionally with the unit s, m, h or d,
+/// e.g. `90`, `5m` or `24h`.
+pub fn parse_expiry(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => text.split_at(i),
+        None => (text, "s"),
+    };
+    let factor = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("Invalid expiry {}, e.g. use 300, 300s or 5m", text)),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(factor))
+        .ok_or_else(|| format!("Invalid expiry {}, e.g. use 300, 300s or 5m", text))
+}
+
+/// Sets the configured expiry of signed messages.
+pub fn set_ingress_expiry(secs: u64, copy_interval: u64) -> AnyhowResult {
+    check_expiry(secs, copy_interval)?;
+    INGRESS_EXPIRY_SECS.store(secs, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Sets the expiry of the messages of one command, or restores the configured
+/// one if it is `None`.
+pub fn set_expire_after(secs: Option<u64>, copy_interval: u64) -> AnyhowResult {
+    EXPIRE_AFTER_SECS.store(0, Ordering::Relaxed);
+    if let Some(secs) = secs {
+        check_expiry(secs, copy_interval)?;
+        EXPIRE_AFTER_SECS.store(secs, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+// The IC rejects messages which expire more than five minutes after their
+// submission, so longer expiries are refused with the number of copies, each
+// expiring `copy_interval` seconds after the previous one, which cover the
+// requested time instead.
+fn check_expiry(secs: u64, copy_interval: u64) -> AnyhowResult {
+    if secs == 0 {
+        return Err(
+            QuillError::Validation("The expiry must be at least 1 second".to_string()).into(),
+        );
+    }
+    if secs > MAX_INGRESS_EXPIRY_SECS {
+        let interval = copy_interval.max(1);
+        let copies = (secs - MAX_INGRESS_EXPIRY_SECS + interval - 1) / interval + 1;
+        return Err(QuillError::Validation(format!(
+            "The expiry of {} seconds exceeds the maximum of {} seconds: the IC rejects messages \
+             which expire more than 5 minutes after they are submitted. To send the messages up \
+             to {} seconds after signing, sign staggered copies with --copies {} instead",
+            secs, MAX_INGRESS_EXPIRY_SECS, secs, copies
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+fn ingress_expiry() -> Duration {
+    Duration::from_secs(match EXPIRE_AFTER_SECS.load(Ordering::Relaxed) {
+        0 => INGRESS_EXPIRY_SECS.load(Ordering::Relaxed),
+        secs => secs,
+    })
+}
+
+// The configured way of carrying signed messages to the online machine, and
+// the estimated number of seconds it takes.
+static HANDOFF: RwLock<Option<(String, u64)>> = RwLock::new(None);
+
+pub fn set_handoff(method: String, secs: u64) {
+    *HANDOFF.write().unwrap() = Some((method, secs));
+}
+
+/// Warns, or fails if `strict`, when the messages would expire before the
+/// configured handoff is likely to be done. `extra_secs` is the additional
+/// time the last of several copies remains valid.
+pub fn check_expiry_window(extra_secs: u64, strict: bool) -> AnyhowResult {
+    let (method, handoff_secs) = match &*HANDOFF.read().unwrap() {
+        Some(handoff) => handoff.clone(),
+        None => return Ok(()),
+    };
+    let window_secs = ingress_expiry().as_secs() + extra_secs;
+    if window_secs >= handoff_secs {
+        return Ok(());
+    }
+    let message = format!(
+        "The messages expire {} seconds after signing, but the handoff ({}) is estimated to take {} seconds",
+        window_secs, method, handoff_secs
+    );
+    if strict {
+        return Err(QuillError::Validation(message).into());
+    }
+    eprintln!(
+        "Warning: {}; they may expire before they are sent.",
+        message
+    );
+    Ok(())
+}
+
+// The largest accepted difference between the local clock and the time of the
+// IC. The replica rejects messages which expire more than five minutes after
+// its time, or before it.
+const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Compares the local clock with the certified time of the IC, and fails if
+/// they differ by more than a minute. Offline, it can only warn that the
+/// clock has to be checked by hand.
+pub async fn check_clock(offline: bool) -> AnyhowResult {
+    let now = Utc::now();
+    if offline {
+        eprintln!(
+            "WARNING: The local clock can't be compared with the time of the IC offline. It is {}; \
+             if it is wrong, the signed messages are already expired or not yet valid when they \
+             are sent, so compare it with a trusted clock.",
+            now.to_rfc3339()
+        );
+        return Ok(());
+    }
+    let skew = (now - read_state::ic_time().await?).num_seconds();
+    if skew.abs() > MAX_CLOCK_SKEW_SECS {
+        return Err(QuillError::Validation(format!(
+            "The local clock is {} seconds {} the time of the IC; fix it before signing",
+            skew.abs(),
+            if skew > 0 { "ahead of" } else { "behind" }
+        ))
+        .into());
+    }
+    eprintln!(
+        "The local clock is within {} seconds of the time of the IC.",
+        MAX_CLOCK_SKEW_SECS
+    );
+    Ok(())
+}
 
 async fn sign(
     pem: &Option<String>,
     canister_id: Principal,
     method_name: &str,
     args: Vec<u8>,
+    timeout: Duration,
 ) -> AnyhowResult<SignedMessageWithRequestId> {
-    let spec = get_local_candid(canister_id)?;
+    let spec = get_local_candid(canister_id, method_name)?;
     let method_type = get_candid_type(spec, method_name);
     let is_query = match &method_type {
         Some((_, f)) => f.is_query(),
         _ => false,
     };
 
-    let mut sign_agent = get_agent(pem)?;
-
-    let timeout = std::time::Duration::from_secs(5 * 60);
-    let expiration_system_time = SystemTime::now()
+    let expiration_system_time = now()
         .checked_add(timeout)
         .ok_or_else(|| anyhow!("Time wrapped around."))?;
 
+    // The messages are only recorded, so signing never touches the network.
     let transport = SignReplicaV2Transport::new(None);
     let data = transport.data.clone();
-    sign_agent.set_transport(transport);
+    let sign_agent = signing_agent(pem, timeout, transport)?;
 
     if is_query {
         match sign_agent
@@ -70,11 +367,12 @@ pub async fn sign_ingress_with_request_status_query(
     method_name: &str,
     args: Vec<u8>,
 ) -> AnyhowResult<IngressWithRequestId> {
-    let msg_with_req_id = sign(pem, canister_id, method_name, args).await?;
+    let msg_with_req_id = sign(pem, canister_id, method_name, args, ingress_expiry()).await?;
     let request_id = msg_with_req_id
         .request_id
         .expect("No request id for transfer call found");
-    let request_status = request_status::sign(pem, request_id, canister_id).await?;
+    let request_status =
+        request_status::sign(signing_pem(pem), request_id, canister_id, ingress_expiry()).await?;
     let message = IngressWithRequestId {
         ingress: msg_with_req_id.message.try_into()?,
         request_status,
@@ -89,6 +387,31 @@ pub async fn sign_ingress(
     method_name: &str,
     args: Vec<u8>,
 ) -> AnyhowResult<Ingress> {
-    let msg = sign(pem, canister_id, method_name, args).await?;
+    let msg = sign(pem, canister_id, method_name, args, ingress_expiry()).await?;
     Ok(msg.message.try_into()?)
 }
+
+/// Signs the messages of a bundle again, with new nonces and expiring `delay`
+/// later than usual, so that the copy can be sent after the original expired.
+pub async fn sign_copy(
+    pem: &Option<String>,
+    bundle: &Bundle,
+    delay: Duration,
+) -> AnyhowResult<Bundle> {
+    let timeout = ingress_expiry() + delay;
+    let mut messages = Vec::new();
+    for message in &bundle.messages {
+        let (_, _, canister_id, method_name, _) = message.ingress.inspect()?;
+        let args = envelope::arg(&envelope::content(&hex::decode(&message.ingress.content)?)?)
+            .ok_or_else(|| anyhow!("Invalid cbor content"))?;
+        let msg = sign(pem, canister_id, &method_name, args, timeout).await?;
+        let request_status = match (&message.request_status, msg.request_id) {
+            (Some(_), Some(request_id)) => Some(
+                request_status::sign(signing_pem(pem), request_id, canister_id, timeout).await?,
+            ),
+            _ => None,
+        };
+        messages.push((msg.message.try_into()?, request_status));
+    }
+    Bundle::new(messages)
+}
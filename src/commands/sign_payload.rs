@@ -0,0 +1,75 @@
+use crate::commands::{
+    print, public,
+    sign::{sign_ingress, sign_ingress_with_request_status_query},
+    OutputOpts,
+};
+use clap::Clap;
+use quill_core::{
+    envelope::{self, call_payload},
+    error::QuillError,
+    get_idl_string, read_bytes_from_file, AnyhowResult,
+};
+
+/// Signs a call prepared by the hardware wallet flow of the NNS dapp, e.g. to
+/// review a transaction in the dapp but sign it on an air-gapped computer. The
+/// payload is the CBOR which the dapp passes to the wallet, as a binary file,
+/// hex or base64. The call is signed by the identity of quill with a new
+/// expiry, and printed like the messages of the other commands.
+#[derive(Clap)]
+pub struct SignPayloadOpts {
+    /// Path to the payload (use "-" for STDIN).
+    file_name: String,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    opts: SignPayloadOpts,
+    output: &OutputOpts,
+) -> AnyhowResult {
+    let payload = call_payload(&decode_payload(read_bytes_from_file(&opts.file_name)?)?)?;
+    let arguments = get_idl_string(
+        &payload.arg,
+        payload.canister_id,
+        &payload.method_name,
+        "args",
+    )?;
+    eprintln!("Call type:   {}", payload.call_type);
+    eprintln!("Canister id: {}", payload.canister_id);
+    eprintln!("Method name: {}", payload.method_name);
+    eprintln!("Arguments:   {}", arguments);
+    // The dapp builds the payload for the principal of the hardware wallet.
+    if let Some(pem) = pem {
+        let (principal, _) = public::get_ids(&Some(pem.clone()))?;
+        if principal != payload.sender {
+            eprintln!(
+                "Warning: the payload was built for the sender {}, but it is signed by {}",
+                payload.sender, principal
+            );
+        }
+    }
+
+    let (canister_id, method_name) = (payload.canister_id, payload.method_name);
+    if payload.call_type == "query" {
+        let message = sign_ingress(pem, canister_id, &method_name, payload.arg).await?;
+        print(pem, &vec![message], output).await
+    } else {
+        let message =
+            sign_ingress_with_request_status_query(pem, canister_id, &method_name, payload.arg)
+                .await?;
+        print(pem, &vec![message], output).await
+    }
+}
+
+// Returns the CBOR of a payload given as binary, hex or base64.
+fn decode_payload(data: Vec<u8>) -> AnyhowResult<Vec<u8>> {
+    if envelope::is_cbor(&data) || std::str::from_utf8(&data).is_err() {
+        return Ok(data);
+    }
+    let text = String::from_utf8(data)?;
+    let text = text.trim();
+    hex::decode(text.trim_start_matches("0x"))
+        .or_else(|_| base64::decode(text))
+        .map_err(|_| {
+            QuillError::Validation("The payload isn't CBOR, hex or base64".to_string()).into()
+        })
+}
@@ -0,0 +1,71 @@
+use crate::commands::{
+    public::get_ids,
+    sign::sign_ingress_with_request_status_query,
+    sns::{
+        list_neurons::fetch_neurons,
+        manage_neuron::{sign_manage_neuron, By, ClaimOrRefresh, Command, Empty},
+        SnsCanisterIds,
+    },
+};
+use anyhow::anyhow;
+use candid::Encode;
+use clap::Clap;
+use quill_core::{sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+/// Signs the claim of the neurons created for a principal by the token swap.
+/// Finalizing the swap creates the neurons, refreshing them makes their stake
+/// available to the signing principal.
+#[derive(Clap)]
+pub struct ClaimSwapNeuronsOpts {
+    /// The ids of the neurons to refresh (their hex-encoded subaccounts).
+    neuron_ids: Vec<String>,
+
+    /// Refresh all neurons of the signing principal (requires network access).
+    #[clap(long, conflicts_with("neuron-ids"))]
+    all_my_neurons: bool,
+
+    /// Also finalize the swap, which creates the neurons of all participants
+    /// once the swap is committed.
+    #[clap(long)]
+    finalize: bool,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: ClaimSwapNeuronsOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let neuron_ids = if opts.all_my_neurons {
+        let (principal, _) = get_ids(pem)?;
+        fetch_neurons(pem, canister_ids, principal)
+            .await?
+            .into_iter()
+            .filter_map(|neuron| neuron.id.map(|id| hex::encode(id.id)))
+            .collect()
+    } else {
+        opts.neuron_ids
+    };
+    if neuron_ids.is_empty() && !opts.finalize {
+        return Err(anyhow!("No neurons to claim"));
+    }
+
+    let mut msgs = Vec::new();
+    if opts.finalize {
+        msgs.push(
+            sign_ingress_with_request_status_query(
+                pem,
+                canister_ids.swap_canister_id,
+                "finalize_swap",
+                Encode!(&Empty {})?,
+            )
+            .await?,
+        );
+    }
+    for neuron_id in neuron_ids {
+        let command = Command::ClaimOrRefresh(ClaimOrRefresh {
+            by: Some(By::NeuronId(Empty {})),
+        });
+        msgs.push(sign_manage_neuron(pem, canister_ids, &neuron_id, command).await?);
+    }
+    Ok(msgs)
+}
@@ -0,0 +1,60 @@
+use crate::commands::sns::{
+    manage_neuron::{
+        sign_manage_neuron, Command, Configure, IncreaseDissolveDelay, Operation, StartDissolving,
+        StopDissolving,
+    },
+    SnsCanisterIds,
+};
+use anyhow::anyhow;
+use clap::Clap;
+use quill_core::{sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+/// Signs the configuration of the dissolve state of an SNS neuron.
+#[derive(Clap)]
+pub struct ConfigureDissolveDelayOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Number of dissolve seconds to add.
+    #[clap(short, long)]
+    additional_dissolve_delay_seconds: Option<u32>,
+
+    /// Start dissolving.
+    #[clap(long)]
+    start_dissolving: bool,
+
+    /// Stop dissolving.
+    #[clap(long, conflicts_with("start-dissolving"))]
+    stop_dissolving: bool,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: ConfigureDissolveDelayOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let mut operations = Vec::new();
+    if let Some(additional_dissolve_delay_seconds) = opts.additional_dissolve_delay_seconds {
+        operations.push(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
+            additional_dissolve_delay_seconds,
+        }));
+    }
+    if opts.start_dissolving {
+        operations.push(Operation::StartDissolving(StartDissolving {}));
+    }
+    if opts.stop_dissolving {
+        operations.push(Operation::StopDissolving(StopDissolving {}));
+    }
+    if operations.is_empty() {
+        return Err(anyhow!("No instructions provided"));
+    }
+
+    let mut msgs = Vec::new();
+    for operation in operations {
+        let command = Command::Configure(Configure {
+            operation: Some(operation),
+        });
+        msgs.push(sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?);
+    }
+    Ok(msgs)
+}
@@ -0,0 +1,51 @@
+use crate::commands::sns::{
+    manage_neuron::{sign_manage_neuron, Amount, Command, Disburse},
+    SnsCanisterIds,
+};
+use anyhow::anyhow;
+use clap::Clap;
+use quill_core::{
+    icrc1::{parse_tokens, Account},
+    sign::signed_message::IngressWithRequestId,
+    AnyhowResult,
+};
+
+/// Signs the disbursal of the stake of a dissolved SNS neuron.
+#[derive(Clap)]
+pub struct DisburseOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Amount of tokens to disburse, default is the whole stake.
+    #[clap(long)]
+    amount: Option<String>,
+
+    /// Destination ICRC-1 account, default is the account of the signing principal.
+    #[clap(long)]
+    to: Option<String>,
+
+    /// The number of decimals of the token.
+    #[clap(long, default_value("8"))]
+    decimals: u32,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: DisburseOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let to_account = opts
+        .to
+        .map(|to| to.parse::<Account>())
+        .transpose()?
+        .map(Into::into);
+    let amount = opts
+        .amount
+        .map(|amount| parse_tokens(&amount, opts.decimals).map(|e8s| Amount { e8s }))
+        .transpose()
+        .map_err(|err| anyhow!(err))?;
+    let command = Command::Disburse(Disburse { to_account, amount });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
@@ -0,0 +1,40 @@
+use crate::commands::sns::{
+    manage_neuron::{percentage_validator, sign_manage_neuron, Command, DisburseMaturity},
+    SnsCanisterIds,
+};
+use clap::Clap;
+use quill_core::{icrc1::Account, sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+/// Signs the disbursal of the maturity of an SNS neuron as tokens.
+#[derive(Clap)]
+pub struct DisburseMaturityOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Percentage of the maturity to disburse.
+    #[clap(long, default_value("100"), validator(percentage_validator))]
+    percentage: u32,
+
+    /// Destination ICRC-1 account, default is the account of the signing principal.
+    #[clap(long)]
+    to: Option<String>,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: DisburseMaturityOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let to_account = opts
+        .to
+        .map(|to| to.parse::<Account>())
+        .transpose()?
+        .map(Into::into);
+    let command = Command::DisburseMaturity(DisburseMaturity {
+        to_account,
+        percentage_to_disburse: opts.percentage,
+    });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
@@ -0,0 +1,58 @@
+use crate::commands::sns::{
+    fetch_functions,
+    manage_neuron::{parse_neuron_id, sign_manage_neuron, Command, Follow, NeuronId},
+    SnsCanisterIds,
+};
+use anyhow::anyhow;
+use clap::Clap;
+use quill_core::{sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+/// Signs the update of the followees of an SNS neuron for a nervous system function.
+#[derive(Clap)]
+pub struct FollowNeuronOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// The id of the nervous system function, or its name (requires network access).
+    #[clap(long)]
+    function: String,
+
+    /// The id of a neuron to follow (can be repeated). Without followees, the
+    /// followees of the function are cleared.
+    #[clap(long)]
+    followee: Vec<String>,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: FollowNeuronOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let function_id = match opts.function.parse::<u64>() {
+        Ok(id) => id,
+        Err(_) => {
+            let function = fetch_functions(pem, canister_ids)
+                .await?
+                .into_iter()
+                .find(|function| function.name.eq_ignore_ascii_case(&opts.function))
+                .ok_or_else(|| anyhow!("Unknown nervous system function {}", opts.function))?;
+            eprintln!("Function {} has id {}.", function.name, function.id);
+            function.id
+        }
+    };
+    let followees = opts
+        .followee
+        .iter()
+        .map(|id| parse_neuron_id(id).map(|id| NeuronId { id }))
+        .collect::<AnyhowResult<Vec<_>>>()?;
+    if followees.is_empty() {
+        eprintln!("Clearing the followees of function {}.", function_id);
+    }
+    let command = Command::Follow(Follow {
+        function_id,
+        followees,
+    });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
@@ -0,0 +1,46 @@
+use crate::commands::{
+    public::get_ids, sign::sign_ingress_with_request_status_query, sns::SnsCanisterIds,
+};
+use candid::{CandidType, Encode};
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+#[derive(CandidType)]
+pub struct ErrorRefundIcpRequest {
+    pub source_principal_id: Option<Principal>,
+}
+
+/// Signs a request to refund the ICP of a swap participation that couldn't be
+/// used, e.g. because the swap failed or was oversubscribed.
+#[derive(Clap)]
+pub struct GetSwapRefundOpts {
+    /// The principal that participated in the swap and receives the refund,
+    /// default is the signing principal.
+    #[clap(long)]
+    principal: Option<Principal>,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: GetSwapRefundOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let principal = match opts.principal {
+        Some(principal) => principal,
+        None => get_ids(pem)?.0,
+    };
+    eprintln!("Refunding to the default account of {}.", principal);
+    let args = Encode!(&ErrorRefundIcpRequest {
+        source_principal_id: Some(principal),
+    })?;
+    Ok(vec![
+        sign_ingress_with_request_status_query(
+            pem,
+            canister_ids.swap_canister_id,
+            "error_refund_icp",
+            args,
+        )
+        .await?,
+    ])
+}
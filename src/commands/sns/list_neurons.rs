@@ -0,0 +1,151 @@
+use crate::commands::{pager::page, public::get_ids, sns::SnsCanisterIds, OutputOpts};
+use candid::{CandidType, Decode, Encode};
+use chrono::{TimeZone, Utc};
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{
+    get_agent,
+    icrc1::format_tokens,
+    sns_governance::{permission_name, DissolveState, ListNeuronsResponse, Neuron},
+    to_canonical_json, AnyhowResult,
+};
+use std::fmt::{self, Write};
+
+// The number of neurons fetched per query.
+const PAGE_SIZE: u32 = 100;
+
+#[derive(CandidType)]
+pub struct NeuronId {
+    pub id: Vec<u8>,
+}
+
+#[derive(CandidType)]
+pub struct ListNeurons {
+    pub of_principal: Option<Principal>,
+    pub limit: u32,
+    pub start_page_at: Option<NeuronId>,
+}
+
+/// Queries the SNS governance canister for the neurons of a principal (requires network access).
+#[derive(Clap)]
+pub struct ListNeuronsOpts {
+    /// The principal whose neurons are listed, default is the signing principal.
+    #[clap(long)]
+    principal: Option<Principal>,
+
+    /// Print the neurons as JSON, which the global `--json` implies.
+    #[clap(long)]
+    pub json: bool,
+
+    /// The number of decimals of the token.
+    #[clap(long, default_value("8"))]
+    decimals: u32,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: ListNeuronsOpts,
+    output: &OutputOpts,
+) -> AnyhowResult {
+    let principal = match opts.principal {
+        Some(principal) => principal,
+        None => get_ids(pem)?.0,
+    };
+    let neurons = fetch_neurons(pem, canister_ids, principal).await?;
+    if opts.json {
+        println!("{}", to_canonical_json(&neurons)?);
+        return Ok(());
+    }
+    let mut text = String::new();
+    if neurons.is_empty() {
+        writeln!(text, "No neurons found for {}.", principal)?;
+    }
+    for neuron in &neurons {
+        write_neuron(&mut text, neuron, opts.decimals)?;
+    }
+    page(&text, output)
+}
+
+/// Fetches all neurons of the principal, page by page.
+pub async fn fetch_neurons(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    principal: Principal,
+) -> AnyhowResult<Vec<Neuron>> {
+    let agent = get_agent(pem)?;
+    let mut neurons: Vec<Neuron> = Vec::new();
+    loop {
+        let start_page_at = neurons
+            .last()
+            .and_then(|neuron| neuron.id.as_ref())
+            .map(|id| NeuronId { id: id.id.clone() });
+        let args = Encode!(&ListNeurons {
+            of_principal: Some(principal),
+            limit: PAGE_SIZE,
+            start_page_at,
+        })?;
+        let response = agent
+            .query(&canister_ids.governance_canister_id, "list_neurons")
+            .with_arg(&args)
+            .call()
+            .await?;
+        let page = Decode!(&response, ListNeuronsResponse)?.neurons;
+        let done = page.len() < PAGE_SIZE as usize;
+        neurons.extend(page);
+        if done {
+            return Ok(neurons);
+        }
+    }
+}
+
+fn write_neuron(text: &mut String, neuron: &Neuron, decimals: u32) -> fmt::Result {
+    let id = neuron
+        .id
+        .as_ref()
+        .map_or_else(|| "(unknown id)".to_string(), |id| hex::encode(&id.id));
+    let stake = neuron
+        .cached_neuron_stake_e8s
+        .saturating_sub(neuron.neuron_fees_e8s);
+    writeln!(text, "Neuron {}", id)?;
+    writeln!(
+        text,
+        "  Stake:           {}",
+        format_tokens(stake, decimals)
+    )?;
+    writeln!(
+        text,
+        "  Maturity:        {}",
+        format_tokens(neuron.maturity_e8s_equivalent, decimals)
+    )?;
+    if let Some(staked_maturity) = neuron.staked_maturity_e8s_equivalent {
+        writeln!(
+            text,
+            "  Staked maturity: {}",
+            format_tokens(staked_maturity, decimals)
+        )?;
+    }
+    let dissolve_state = match &neuron.dissolve_state {
+        Some(DissolveState::DissolveDelaySeconds(seconds)) => {
+            format!("not dissolving, delay of {} seconds", seconds)
+        }
+        Some(DissolveState::WhenDissolvedTimestampSeconds(timestamp)) => {
+            format!("dissolving until {}", Utc.timestamp(*timestamp as i64, 0))
+        }
+        None => "unknown".to_string(),
+    };
+    writeln!(text, "  Dissolve state:  {}", dissolve_state)?;
+    writeln!(text, "  Permissions:")?;
+    for permission in &neuron.permissions {
+        let principal = permission
+            .principal
+            .map_or_else(|| "(none)".to_string(), |principal| principal.to_text());
+        let types: Vec<_> = permission
+            .permission_type
+            .iter()
+            .map(|p| permission_name(*p))
+            .collect();
+        writeln!(text, "    {}: {}", principal, types.join(", "))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::commands::{
+    sign::sign_ingress_with_request_status_query,
+    sns::{manage_neuron::parse_neuron_id, SnsCanisterIds},
+};
+use anyhow::{anyhow, Context};
+use candid::{types::Type, IDLArgs};
+use clap::Clap;
+use quill_core::{
+    get_candid_type, json_candid::json_to_candid, read_from_file,
+    sign::signed_message::IngressWithRequestId, sns_governance, AnyhowResult,
+};
+
+/// Signs a proposal submitted by an SNS neuron.
+#[derive(Clap)]
+pub struct MakeProposalOpts {
+    /// The id of the proposing neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Path to the file with the proposal, either a candid value or a JSON object
+    /// of the `Proposal` type of the SNS governance canister.
+    #[clap(long)]
+    proposal: String,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: MakeProposalOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let subaccount = parse_neuron_id(&opts.neuron_id)?;
+    let (env, func) = get_candid_type(sns_governance::CANDID.to_string(), "manage_neuron")
+        .ok_or_else(|| anyhow!("Couldn't load the SNS governance interface"))?;
+
+    let content = read_from_file(&opts.proposal)?;
+    let proposal = match serde_json::from_str(&content) {
+        Ok(json) => json_to_candid(&env, &json, &Type::Var("Proposal".to_string()))
+            .context("Couldn't convert the JSON proposal")?,
+        Err(_) => content,
+    };
+    let subaccount: Vec<_> = subaccount.iter().map(|b| b.to_string()).collect();
+    let args: IDLArgs = format!(
+        "(record {{ subaccount = vec {{ {} }}; command = opt variant {{ MakeProposal = {} }} }})",
+        subaccount.join("; "),
+        proposal
+    )
+    .parse()
+    .context("Couldn't parse the proposal")?;
+    let args = args
+        .to_bytes_with_types(&env, &func.args)
+        .context("The proposal doesn't match the SNS governance interface")?;
+    eprintln!(
+        "Proposal: {}",
+        IDLArgs::from_bytes_with_types(&args, &env, &func.args)?
+    );
+
+    Ok(vec![
+        sign_ingress_with_request_status_query(
+            pem,
+            canister_ids.governance_canister_id,
+            "manage_neuron",
+            args,
+        )
+        .await?,
+    ])
+}
@@ -0,0 +1,185 @@
+//! The `manage_neuron` interface of the SNS governance canister.
+
+use crate::commands::{sign::sign_ingress_with_request_status_query, sns::SnsCanisterIds};
+use anyhow::anyhow;
+use candid::{CandidType, Encode};
+use ic_types::Principal;
+use quill_core::{icrc1, sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+#[derive(CandidType)]
+pub struct Subaccount {
+    pub subaccount: Vec<u8>,
+}
+
+#[derive(CandidType)]
+pub struct Account {
+    pub owner: Option<Principal>,
+    pub subaccount: Option<Subaccount>,
+}
+
+impl From<icrc1::Account> for Account {
+    fn from(account: icrc1::Account) -> Self {
+        Self {
+            owner: Some(account.owner),
+            subaccount: account
+                .subaccount
+                .map(|subaccount| Subaccount { subaccount }),
+        }
+    }
+}
+
+#[derive(CandidType)]
+pub struct IncreaseDissolveDelay {
+    pub additional_dissolve_delay_seconds: u32,
+}
+
+#[derive(CandidType)]
+pub struct StartDissolving {}
+
+#[derive(CandidType)]
+pub struct StopDissolving {}
+
+#[derive(CandidType)]
+pub enum Operation {
+    StopDissolving(StopDissolving),
+    StartDissolving(StartDissolving),
+    IncreaseDissolveDelay(IncreaseDissolveDelay),
+}
+
+#[derive(CandidType)]
+pub struct Configure {
+    pub operation: Option<Operation>,
+}
+
+#[derive(CandidType)]
+pub struct Amount {
+    pub e8s: u64,
+}
+
+#[derive(CandidType)]
+pub struct Disburse {
+    pub to_account: Option<Account>,
+    pub amount: Option<Amount>,
+}
+
+#[derive(CandidType)]
+pub struct NeuronId {
+    pub id: Vec<u8>,
+}
+
+#[derive(CandidType)]
+pub struct Follow {
+    pub function_id: u64,
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType)]
+pub struct UpgradeSnsControlledCanister {
+    pub new_canister_wasm: Vec<u8>,
+    pub canister_id: Option<Principal>,
+    pub canister_upgrade_arg: Option<Vec<u8>>,
+}
+
+#[derive(CandidType)]
+pub enum Action {
+    UpgradeSnsControlledCanister(UpgradeSnsControlledCanister),
+}
+
+#[derive(CandidType)]
+pub struct Proposal {
+    pub url: String,
+    pub title: String,
+    pub action: Option<Action>,
+    pub summary: String,
+}
+
+#[derive(CandidType)]
+pub struct Empty {}
+
+#[derive(CandidType)]
+pub enum By {
+    NeuronId(Empty),
+}
+
+#[derive(CandidType)]
+pub struct ClaimOrRefresh {
+    pub by: Option<By>,
+}
+
+#[derive(CandidType)]
+pub struct DisburseMaturity {
+    pub to_account: Option<Account>,
+    pub percentage_to_disburse: u32,
+}
+
+#[derive(CandidType)]
+pub struct Split {
+    pub memo: u64,
+    pub amount_e8s: u64,
+}
+
+#[derive(CandidType)]
+pub struct StakeMaturity {
+    pub percentage_to_stake: Option<u32>,
+}
+
+#[derive(CandidType)]
+pub enum Command {
+    Follow(Follow),
+    Configure(Configure),
+    MakeProposal(Proposal),
+    StakeMaturity(StakeMaturity),
+    Split(Split),
+    DisburseMaturity(DisburseMaturity),
+    ClaimOrRefresh(ClaimOrRefresh),
+    Disburse(Disburse),
+}
+
+#[derive(CandidType)]
+pub struct ManageNeuron {
+    pub subaccount: Vec<u8>,
+    pub command: Option<Command>,
+}
+
+/// Parses the id of an SNS neuron, which is the hex-encoded subaccount of the
+/// governance canister holding its stake.
+pub fn parse_neuron_id(neuron_id: &str) -> AnyhowResult<Vec<u8>> {
+    let subaccount = hex::decode(neuron_id)
+        .map_err(|err| anyhow!("Invalid neuron id {}: {}", neuron_id, err))?;
+    if subaccount.len() != 32 {
+        return Err(anyhow!(
+            "Invalid neuron id {}: expected 32 bytes, found {}",
+            neuron_id,
+            subaccount.len()
+        ));
+    }
+    Ok(subaccount)
+}
+
+/// Signs a `manage_neuron` call on the SNS governance canister.
+pub async fn sign_manage_neuron(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    neuron_id: &str,
+    command: Command,
+) -> AnyhowResult<IngressWithRequestId> {
+    let args = Encode!(&ManageNeuron {
+        subaccount: parse_neuron_id(neuron_id)?,
+        command: Some(command),
+    })?;
+    sign_ingress_with_request_status_query(
+        pem,
+        canister_ids.governance_canister_id,
+        "manage_neuron",
+        args,
+    )
+    .await
+}
+
+/// Validates a percentage between 1 and 100.
+pub fn percentage_validator(percentage: &str) -> Result<(), String> {
+    match percentage.parse::<u32>() {
+        Ok(percentage) if (1..=100).contains(&percentage) => Ok(()),
+        _ => Err("Percentage must be an integer between 1 and 100".to_string()),
+    }
+}
@@ -0,0 +1,256 @@
+//! The commands for interacting with a Service Nervous System (SNS).
+
+use crate::commands::{print, OutputOpts};
+use anyhow::{anyhow, Context};
+use candid::{Decode, Deserialize, Encode};
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{
+    get_agent, read_from_file,
+    sns_governance::{ListNervousSystemFunctionsResponse, NervousSystemFunction},
+    AnyhowResult,
+};
+use serde::Serialize;
+
+mod claim_swap_neurons;
+mod configure_dissolve_delay;
+mod disburse;
+mod disburse_maturity;
+mod follow_neuron;
+mod get_swap_refund;
+mod list_neurons;
+mod make_proposal;
+mod manage_neuron;
+mod proposal;
+mod resolve;
+mod split_neuron;
+mod stake_maturity;
+mod status;
+mod transfer;
+mod upgrade_canister_proposal;
+
+pub use resolve::cached_ledger_ids;
+
+/// The canister ids of an SNS.
+pub struct SnsCanisterIds {
+    pub governance_canister_id: Principal,
+    pub ledger_canister_id: Principal,
+    pub root_canister_id: Principal,
+    pub swap_canister_id: Principal,
+    pub index_canister_id: Option<Principal>,
+}
+
+/// The canister ids file, in the format of `sns_canister_ids.json`.
+#[derive(Deserialize, Serialize)]
+struct SnsCanisterIdsFile {
+    governance_canister_id: String,
+    ledger_canister_id: String,
+    root_canister_id: String,
+    swap_canister_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index_canister_id: Option<String>,
+}
+
+impl SnsCanisterIds {
+    pub fn from_json(json: &str) -> AnyhowResult<Self> {
+        let file: SnsCanisterIdsFile = serde_json::from_str(json)?;
+        let parse = |id: &str| {
+            Principal::from_text(id).map_err(|err| anyhow!("Invalid canister id {}: {}", id, err))
+        };
+        Ok(Self {
+            governance_canister_id: parse(&file.governance_canister_id)?,
+            ledger_canister_id: parse(&file.ledger_canister_id)?,
+            root_canister_id: parse(&file.root_canister_id)?,
+            swap_canister_id: parse(&file.swap_canister_id)?,
+            index_canister_id: file.index_canister_id.as_deref().map(parse).transpose()?,
+        })
+    }
+
+    pub fn to_json(&self) -> AnyhowResult<String> {
+        Ok(serde_json::to_string_pretty(&SnsCanisterIdsFile {
+            governance_canister_id: self.governance_canister_id.to_text(),
+            ledger_canister_id: self.ledger_canister_id.to_text(),
+            root_canister_id: self.root_canister_id.to_text(),
+            swap_canister_id: self.swap_canister_id.to_text(),
+            index_canister_id: self.index_canister_id.map(|id| id.to_text()),
+        })?)
+    }
+}
+
+/// Commands for the governance, ledger and swap canisters of an SNS.
+#[derive(Clap)]
+pub struct SnsOpts {
+    /// Path to the JSON file with the canister ids of the SNS.
+    #[clap(long)]
+    canister_ids_file: Option<String>,
+
+    /// The root canister id of the SNS, used to look up the other canister ids
+    /// with the SNS-W canister (requires network access unless cached).
+    #[clap(long, conflicts_with("canister-ids-file"))]
+    root_canister_id: Option<Principal>,
+
+    /// The name of the SNS project, used to look up its canister ids with the
+    /// SNS-W canister (requires network access unless cached).
+    #[clap(long, conflicts_with_all(&["canister-ids-file", "root-canister-id"]))]
+    name: Option<String>,
+
+    /// Look up the canister ids again instead of using the cached ones.
+    #[clap(long)]
+    refresh: bool,
+
+    #[clap(subcommand)]
+    command: SnsCommand,
+}
+
+#[derive(Clap)]
+enum SnsCommand {
+    /// Prints the canister ids of the SNS in the format of the canister ids file.
+    CanisterIds,
+    Transfer(transfer::TransferOpts),
+    ConfigureDissolveDelay(configure_dissolve_delay::ConfigureDissolveDelayOpts),
+    Disburse(disburse::DisburseOpts),
+    FollowNeuron(follow_neuron::FollowNeuronOpts),
+    MakeProposal(make_proposal::MakeProposalOpts),
+    UpgradeCanisterProposal(upgrade_canister_proposal::UpgradeCanisterProposalOpts),
+    GetSwapRefund(get_swap_refund::GetSwapRefundOpts),
+    ListNeurons(list_neurons::ListNeuronsOpts),
+    /// Prints an overview of the SNS and its token swap (requires network access).
+    Status,
+    StakeMaturity(stake_maturity::StakeMaturityOpts),
+    SplitNeuron(split_neuron::SplitNeuronOpts),
+    Proposal(proposal::ProposalOpts),
+    DisburseMaturity(disburse_maturity::DisburseMaturityOpts),
+    ClaimSwapNeurons(claim_swap_neurons::ClaimSwapNeuronsOpts),
+}
+
+pub async fn exec(pem: &Option<String>, output: &OutputOpts, opts: SnsOpts) -> AnyhowResult {
+    let canister_ids = match (&opts.canister_ids_file, &opts.root_canister_id, &opts.name) {
+        (Some(path), _, _) => SnsCanisterIds::from_json(&read_from_file(path)?)
+            .with_context(|| format!("Couldn't parse the canister ids file {}", path))?,
+        (_, Some(root_canister_id), _) => {
+            resolve::resolve_root(pem, *root_canister_id, opts.refresh).await?
+        }
+        (_, _, Some(name)) => resolve::resolve_name(pem, name, opts.refresh).await?,
+        _ => {
+            return Err(anyhow!(
+                "Either a canister ids file, a root canister id or a project name is required"
+            ))
+        }
+    };
+    match opts.command {
+        SnsCommand::CanisterIds => {
+            println!("{}", canister_ids.to_json()?);
+            Ok(())
+        }
+        SnsCommand::Transfer(opts) => {
+            print(
+                pem,
+                &transfer::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::ConfigureDissolveDelay(opts) => {
+            print(
+                pem,
+                &configure_dissolve_delay::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::Disburse(opts) => {
+            print(
+                pem,
+                &disburse::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::FollowNeuron(opts) => {
+            print(
+                pem,
+                &follow_neuron::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::MakeProposal(opts) => {
+            print(
+                pem,
+                &make_proposal::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::UpgradeCanisterProposal(opts) => {
+            print(
+                pem,
+                &upgrade_canister_proposal::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::GetSwapRefund(opts) => {
+            print(
+                pem,
+                &get_swap_refund::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::ListNeurons(mut opts) => {
+            opts.json |= output.json;
+            list_neurons::exec(pem, &canister_ids, opts, output).await
+        }
+        SnsCommand::Status => status::exec(pem, &canister_ids, output).await,
+        SnsCommand::StakeMaturity(opts) => {
+            print(
+                pem,
+                &stake_maturity::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::SplitNeuron(opts) => {
+            print(
+                pem,
+                &split_neuron::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::Proposal(opts) => proposal::exec(pem, &canister_ids, opts, output).await,
+        SnsCommand::DisburseMaturity(opts) => {
+            print(
+                pem,
+                &disburse_maturity::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+        SnsCommand::ClaimSwapNeurons(opts) => {
+            print(
+                pem,
+                &claim_swap_neurons::exec(pem, &canister_ids, opts).await?,
+                output,
+            )
+            .await
+        }
+    }
+}
+
+/// Queries the governance canister of an SNS for its nervous system functions.
+pub async fn fetch_functions(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+) -> AnyhowResult<Vec<NervousSystemFunction>> {
+    let response = get_agent(pem)?
+        .query(
+            &canister_ids.governance_canister_id,
+            "list_nervous_system_functions",
+        )
+        .with_arg(&Encode!()?)
+        .call()
+        .await?;
+    Ok(Decode!(&response, ListNervousSystemFunctionsResponse)?.functions)
+}
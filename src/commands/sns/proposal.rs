@@ -0,0 +1,147 @@
+use crate::commands::{
+    sns::{fetch_functions, SnsCanisterIds},
+    OutputOpts,
+};
+use anyhow::anyhow;
+use candid::{Decode, Encode, IDLArgs};
+use chrono::{TimeZone, Utc};
+use clap::Clap;
+use quill_core::{
+    get_agent,
+    governance::describe_error,
+    sns_governance::{
+        Action, GetProposal, GetProposalResponse, GetProposalResult, ProposalData, ProposalId,
+    },
+    to_canonical_json, AnyhowResult,
+};
+
+/// Fetches and decodes an SNS proposal for review (requires network access).
+#[derive(Clap)]
+pub struct ProposalOpts {
+    /// The id of the proposal.
+    proposal_id: u64,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: ProposalOpts,
+    output: &OutputOpts,
+) -> AnyhowResult {
+    let args = Encode!(&GetProposal {
+        proposal_id: Some(ProposalId {
+            id: opts.proposal_id
+        }),
+    })?;
+    let response = get_agent(pem)?
+        .query(&canister_ids.governance_canister_id, "get_proposal")
+        .with_arg(&args)
+        .call()
+        .await?;
+    let proposal = match Decode!(&response, GetProposalResponse)?.result {
+        Some(GetProposalResult::Proposal(proposal)) => proposal,
+        Some(GetProposalResult::Error(err)) => return Err(anyhow!(describe_error(&err))),
+        None => return Err(anyhow!("Proposal {} not found", opts.proposal_id)),
+    };
+    let function = fetch_functions(pem, canister_ids)
+        .await?
+        .into_iter()
+        .find(|function| function.id == proposal.action)
+        .map_or_else(|| "(unknown)".to_string(), |function| function.name);
+    if output.json {
+        println!(
+            "{}",
+            to_canonical_json(&to_json(opts.proposal_id, &function, &proposal))?
+        );
+        return Ok(());
+    }
+
+    println!("Proposal {}", opts.proposal_id);
+    println!("  Type:     {} ({})", function, proposal.action);
+    println!("  Status:   {}", status(&proposal));
+    if let Some(proposer) = &proposal.proposer {
+        println!("  Proposer: {}", hex::encode(&proposer.id));
+    }
+    println!(
+        "  Created:  {}",
+        Utc.timestamp(proposal.proposal_creation_timestamp_seconds as i64, 0)
+    );
+    if let Some(tally) = &proposal.latest_tally {
+        println!(
+            "  Tally:    {} yes, {} no of {} total voting power",
+            tally.yes, tally.no, tally.total
+        );
+    }
+    if let Some(err) = &proposal.failure_reason {
+        println!("  Failure:  {}", describe_error(err));
+    }
+    if let Some(body) = &proposal.proposal {
+        println!("  Title:    {}", body.title);
+        println!("  URL:      {}", body.url);
+        println!("\n{}\n", body.summary);
+        match &body.action {
+            Some(Action::Motion(motion)) => println!("Motion: {}", motion.motion_text),
+            Some(Action::ExecuteGenericNervousSystemFunction(call)) => {
+                println!("Payload: {}", decode_payload(&call.payload));
+            }
+            _ => (),
+        }
+    }
+    if let Some(rendering) = &proposal.payload_text_rendering {
+        println!("{}", rendering);
+    }
+    Ok(())
+}
+
+fn status(proposal: &ProposalData) -> &'static str {
+    if proposal.decided_timestamp_seconds == 0 {
+        "open"
+    } else if proposal.executed_timestamp_seconds > 0 {
+        "executed"
+    } else if proposal.failed_timestamp_seconds > 0 {
+        "failed"
+    } else if matches!(&proposal.latest_tally, Some(tally) if tally.yes > tally.no) {
+        "adopted"
+    } else {
+        "rejected"
+    }
+}
+
+// Decodes a generic function payload, falling back to hex.
+fn decode_payload(payload: &[u8]) -> String {
+    IDLArgs::from_bytes(payload).map_or_else(|_| hex::encode(payload), |args| args.to_string())
+}
+
+// The proposal as printed with `--json`.
+fn to_json(id: u64, function: &str, proposal: &ProposalData) -> serde_json::Value {
+    let body = proposal.proposal.as_ref().map(|body| {
+        let (motion_text, payload) = match &body.action {
+            Some(Action::Motion(motion)) => (Some(motion.motion_text.clone()), None),
+            Some(Action::ExecuteGenericNervousSystemFunction(call)) => {
+                (None, Some(decode_payload(&call.payload)))
+            }
+            _ => (None, None),
+        };
+        serde_json::json!({
+            "title": body.title,
+            "url": body.url,
+            "summary": body.summary,
+            "motion_text": motion_text,
+            "payload": payload,
+        })
+    });
+    serde_json::json!({
+        "id": id,
+        "action": proposal.action,
+        "function": function,
+        "status": status(proposal),
+        "proposer": proposal.proposer.as_ref().map(|proposer| hex::encode(&proposer.id)),
+        "proposal_creation_timestamp_seconds": proposal.proposal_creation_timestamp_seconds,
+        "latest_tally": proposal.latest_tally.as_ref().map(|tally| {
+            serde_json::json!({ "yes": tally.yes, "no": tally.no, "total": tally.total })
+        }),
+        "failure_reason": proposal.failure_reason.as_ref().map(describe_error),
+        "proposal": body,
+        "payload_text_rendering": proposal.payload_text_rendering,
+    })
+}
@@ -0,0 +1,164 @@
+//! Resolution of the canister ids of an SNS with the SNS-W canister, which
+//! keeps track of all deployed SNSes, and a local cache for offline use.
+
+use crate::commands::sns::SnsCanisterIds;
+use anyhow::{anyhow, Context};
+use candid::{CandidType, Decode, Deserialize, Encode};
+use ic_types::Principal;
+use quill_core::{
+    aliases::AliasBook, get_agent, get_config_dir, sns_governance::GetMetadataResponse,
+    AnyhowResult,
+};
+use std::path::PathBuf;
+
+const SNS_WASM_CANISTER_ID: &str = "qaa6y-5yaaa-aaaaa-aaafa-cai";
+
+// The book mapping the names of SNS projects to their root canister ids.
+const NAMES_FILE: &str = "sns_names.json";
+
+#[derive(CandidType)]
+struct Empty {}
+
+#[derive(CandidType, Deserialize)]
+struct DeployedSns {
+    root_canister_id: Option<Principal>,
+    governance_canister_id: Option<Principal>,
+    ledger_canister_id: Option<Principal>,
+    swap_canister_id: Option<Principal>,
+    index_canister_id: Option<Principal>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct ListDeployedSnsesResponse {
+    instances: Vec<DeployedSns>,
+}
+
+/// Returns the canister ids of the SNS with the given root canister id, from
+/// the cache or, if missing or `refresh` is set, from the SNS-W canister.
+pub async fn resolve_root(
+    pem: &Option<String>,
+    root_canister_id: Principal,
+    refresh: bool,
+) -> AnyhowResult<SnsCanisterIds> {
+    if !refresh {
+        if let Some(ids) = load_cached(root_canister_id)? {
+            return Ok(ids);
+        }
+    }
+    let ids = list_deployed_snses(pem)
+        .await?
+        .into_iter()
+        .find(|ids| ids.root_canister_id == root_canister_id)
+        .ok_or_else(|| anyhow!("No SNS with root canister {} found", root_canister_id))?;
+    save_cached(&ids)?;
+    Ok(ids)
+}
+
+/// Returns the canister ids of the SNS with the given project name, which is
+/// looked up in the metadata of all deployed SNSes unless it's cached.
+pub async fn resolve_name(
+    pem: &Option<String>,
+    name: &str,
+    refresh: bool,
+) -> AnyhowResult<SnsCanisterIds> {
+    let mut names = AliasBook::load(NAMES_FILE)?;
+    let key = name.to_lowercase();
+    if !refresh {
+        if let Some(root) = names.aliases.get(&key) {
+            let root = Principal::from_text(root).map_err(|err| anyhow!(err))?;
+            if let Some(ids) = load_cached(root)? {
+                return Ok(ids);
+            }
+        }
+    }
+    let agent = get_agent(pem)?;
+    for ids in list_deployed_snses(pem).await? {
+        let response = agent
+            .query(&ids.governance_canister_id, "get_metadata")
+            .with_arg(&Encode!(&Empty {})?)
+            .call()
+            .await?;
+        let metadata = Decode!(&response, GetMetadataResponse)?;
+        if metadata.name.map(|n| n.to_lowercase()).as_ref() == Some(&key) {
+            names.aliases.insert(key, ids.root_canister_id.to_text());
+            names.save()?;
+            save_cached(&ids)?;
+            return Ok(ids);
+        }
+    }
+    Err(anyhow!("No SNS named {} found", name))
+}
+
+async fn list_deployed_snses(pem: &Option<String>) -> AnyhowResult<Vec<SnsCanisterIds>> {
+    let canister_id = Principal::from_text(SNS_WASM_CANISTER_ID).map_err(|err| anyhow!(err))?;
+    let response = get_agent(pem)?
+        .query(&canister_id, "list_deployed_snses")
+        .with_arg(&Encode!(&Empty {})?)
+        .call()
+        .await?;
+    let response = Decode!(&response, ListDeployedSnsesResponse)?;
+    // Instances whose deployment didn't complete lack some canister ids.
+    Ok(response
+        .instances
+        .into_iter()
+        .filter_map(|sns| {
+            Some(SnsCanisterIds {
+                governance_canister_id: sns.governance_canister_id?,
+                ledger_canister_id: sns.ledger_canister_id?,
+                root_canister_id: sns.root_canister_id?,
+                swap_canister_id: sns.swap_canister_id?,
+                index_canister_id: sns.index_canister_id,
+            })
+        })
+        .collect())
+}
+
+/// Returns the ledger canister ids of the SNSes whose canister ids are cached,
+/// i.e. which were looked up by name or root canister id before.
+pub fn cached_ledger_ids() -> AnyhowResult<Vec<Principal>> {
+    let dir = get_config_dir()?.join("sns");
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ledger_ids = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            let json = std::fs::read_to_string(&path)
+                .with_context(|| format!("Couldn't read {}", path.display()))?;
+            let ids = SnsCanisterIds::from_json(&json)
+                .with_context(|| format!("Couldn't parse {}", path.display()))?;
+            ledger_ids.push(ids.ledger_canister_id);
+        }
+    }
+    Ok(ledger_ids)
+}
+
+fn cache_path(root_canister_id: Principal) -> AnyhowResult<PathBuf> {
+    Ok(get_config_dir()?
+        .join("sns")
+        .join(format!("{}.json", root_canister_id)))
+}
+
+fn load_cached(root_canister_id: Principal) -> AnyhowResult<Option<SnsCanisterIds>> {
+    let path = cache_path(root_canister_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)
+        .with_context(|| format!("Couldn't read {}", path.display()))?;
+    Ok(Some(SnsCanisterIds::from_json(&json).with_context(
+        || format!("Couldn't parse {}", path.display()),
+    )?))
+}
+
+fn save_cached(ids: &SnsCanisterIds) -> AnyhowResult {
+    let path = cache_path(ids.root_canister_id)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, ids.to_json()?)
+        .with_context(|| format!("Couldn't write {}", path.display()))?;
+    eprintln!("Cached the canister ids of the SNS in {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::commands::{
+    neuron_stake::get_neuron_subaccount,
+    public::get_ids,
+    sign,
+    sns::{
+        manage_neuron::{sign_manage_neuron, Command, Split},
+        SnsCanisterIds,
+    },
+};
+use anyhow::anyhow;
+use clap::Clap;
+use quill_core::{icrc1::parse_tokens, sign::signed_message::IngressWithRequestId, AnyhowResult};
+use std::time::UNIX_EPOCH;
+
+/// Signs the split of an SNS neuron into two neurons.
+#[derive(Clap)]
+pub struct SplitNeuronOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Amount of tokens to move into the new neuron.
+    #[clap(long)]
+    amount: String,
+
+    /// The memo of the new neuron, default is derived from the current time.
+    #[clap(long)]
+    memo: Option<u64>,
+
+    /// The number of decimals of the token.
+    #[clap(long, default_value("8"))]
+    decimals: u32,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: SplitNeuronOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let amount_e8s = parse_tokens(&opts.amount, opts.decimals).map_err(|err| anyhow!(err))?;
+    let memo = match opts.memo {
+        Some(memo) => memo,
+        None => sign::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+    };
+    // The new neuron is controlled by the principal performing the split.
+    let (controller, _) = get_ids(pem)?;
+    let subaccount = get_neuron_subaccount(&controller, memo);
+    eprintln!("Split memo:     {}", memo);
+    eprintln!("New neuron id:  {}", hex::encode(subaccount.0));
+
+    let command = Command::Split(Split { memo, amount_e8s });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
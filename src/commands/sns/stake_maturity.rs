@@ -0,0 +1,30 @@
+use crate::commands::sns::{
+    manage_neuron::{percentage_validator, sign_manage_neuron, Command, StakeMaturity},
+    SnsCanisterIds,
+};
+use clap::Clap;
+use quill_core::{sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+/// Signs the staking of the maturity of an SNS neuron.
+#[derive(Clap)]
+pub struct StakeMaturityOpts {
+    /// The id of the neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// Percentage of the maturity to stake, default is 100.
+    #[clap(long, validator(percentage_validator))]
+    percentage: Option<u32>,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: StakeMaturityOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let command = Command::StakeMaturity(StakeMaturity {
+        percentage_to_stake: opts.percentage,
+    });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
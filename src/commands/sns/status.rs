@@ -0,0 +1,153 @@
+use crate::commands::{sns::SnsCanisterIds, OutputOpts};
+use candid::{CandidType, Decode, Deserialize, Encode};
+use chrono::{TimeZone, Utc};
+use ledger_canister::ICPTs;
+use quill_core::{
+    get_agent, icrc1::format_tokens, sns_governance::GetMetadataResponse, to_canonical_json,
+    AnyhowResult,
+};
+
+/// Swap lifecycle states in the order of their numeric values.
+const LIFECYCLES: &[&str] = &[
+    "unspecified",
+    "pending",
+    "open",
+    "committed",
+    "aborted",
+    "adopted",
+];
+
+#[derive(CandidType)]
+struct Empty {}
+
+#[derive(CandidType, Deserialize)]
+struct Params {
+    min_participant_icp_e8s: u64,
+    max_icp_e8s: u64,
+    swap_due_timestamp_seconds: u64,
+    min_participants: u32,
+    sns_token_e8s: u64,
+    max_participant_icp_e8s: u64,
+    min_icp_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+struct Swap {
+    lifecycle: i32,
+    params: Option<Params>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct DerivedState {
+    sns_tokens_per_icp: f32,
+    buyer_total_icp_e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+struct GetStateResponse {
+    swap: Option<Swap>,
+    derived: Option<DerivedState>,
+}
+
+// Queries the swap and governance canisters for an overview of the SNS and its token swap.
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    output: &OutputOpts,
+) -> AnyhowResult {
+    let agent = get_agent(pem)?;
+    let response = agent
+        .query(&canister_ids.governance_canister_id, "get_metadata")
+        .with_arg(&Encode!(&Empty {})?)
+        .call()
+        .await?;
+    let metadata = Decode!(&response, GetMetadataResponse)?;
+    let response = agent
+        .query(&canister_ids.swap_canister_id, "get_state")
+        .with_arg(&Encode!(&Empty {})?)
+        .call()
+        .await?;
+    let state = Decode!(&response, GetStateResponse)?;
+    if output.json {
+        println!("{}", to_canonical_json(&to_json(metadata, state))?);
+        return Ok(());
+    }
+
+    let unknown = || "(unknown)".to_string();
+    println!("Name:        {}", metadata.name.unwrap_or_else(unknown));
+    println!(
+        "Description: {}",
+        metadata.description.unwrap_or_else(unknown)
+    );
+    println!("URL:         {}", metadata.url.unwrap_or_else(unknown));
+    let icp = |e8s| ICPTs::from_e8s(e8s).to_string();
+    if let Some(swap) = state.swap {
+        let lifecycle = LIFECYCLES
+            .get(swap.lifecycle as usize)
+            .map_or_else(|| swap.lifecycle.to_string(), |name| name.to_string());
+        println!("Swap:        {}", lifecycle);
+        if let Some(params) = swap.params {
+            println!(
+                "  Tokens for sale:     {}",
+                format_tokens(params.sns_token_e8s, 8)
+            );
+            println!(
+                "  Target:              {} to {} ICP",
+                icp(params.min_icp_e8s),
+                icp(params.max_icp_e8s)
+            );
+            println!(
+                "  Per participant:     {} to {} ICP",
+                icp(params.min_participant_icp_e8s),
+                icp(params.max_participant_icp_e8s)
+            );
+            println!("  Min participants:    {}", params.min_participants);
+            println!(
+                "  Deadline:            {}",
+                Utc.timestamp(params.swap_due_timestamp_seconds as i64, 0)
+            );
+        }
+    }
+    if let Some(derived) = state.derived {
+        println!(
+            "  Participation:       {} ICP",
+            icp(derived.buyer_total_icp_e8s)
+        );
+        println!("  Tokens per ICP:      {}", derived.sns_tokens_per_icp);
+    }
+    Ok(())
+}
+
+// The status with amounts in e8s and timestamps in seconds, as printed with `--json`.
+fn to_json(metadata: GetMetadataResponse, state: GetStateResponse) -> serde_json::Value {
+    let swap = state.swap.map(|swap| {
+        let lifecycle = LIFECYCLES
+            .get(swap.lifecycle as usize)
+            .map_or_else(|| swap.lifecycle.to_string(), |name| name.to_string());
+        let params = swap.params.map(|params| {
+            serde_json::json!({
+                "sns_token_e8s": params.sns_token_e8s,
+                "min_icp_e8s": params.min_icp_e8s,
+                "max_icp_e8s": params.max_icp_e8s,
+                "min_participant_icp_e8s": params.min_participant_icp_e8s,
+                "max_participant_icp_e8s": params.max_participant_icp_e8s,
+                "min_participants": params.min_participants,
+                "swap_due_timestamp_seconds": params.swap_due_timestamp_seconds,
+            })
+        });
+        serde_json::json!({ "lifecycle": lifecycle, "params": params })
+    });
+    let derived = state.derived.map(|derived| {
+        serde_json::json!({
+            "buyer_total_icp_e8s": derived.buyer_total_icp_e8s,
+            "sns_tokens_per_icp": derived.sns_tokens_per_icp,
+        })
+    });
+    serde_json::json!({
+        "name": metadata.name,
+        "description": metadata.description,
+        "url": metadata.url,
+        "swap": swap,
+        "derived": derived,
+    })
+}
@@ -0,0 +1,62 @@
+use crate::commands::{sign::sign_ingress_with_request_status_query, sns::SnsCanisterIds};
+use anyhow::anyhow;
+use candid::{Encode, Nat};
+use clap::Clap;
+use quill_core::{
+    icrc1::{parse_tokens, Account, TransferArg},
+    sign::signed_message::IngressWithRequestId,
+    AnyhowResult,
+};
+
+/// Signs an ICRC-1 transfer of SNS tokens.
+#[derive(Clap)]
+pub struct TransferOpts {
+    /// Destination ICRC-1 account, in the format `<principal>[-<checksum>.<subaccount>]`.
+    to: String,
+
+    /// Amount of tokens to transfer (with up to `--decimals` digits after the point).
+    #[clap(long)]
+    amount: String,
+
+    /// Transaction fee in tokens, default is the fee of the ledger.
+    #[clap(long)]
+    fee: Option<String>,
+
+    /// Reference number.
+    #[clap(long)]
+    memo: Option<u64>,
+
+    /// The number of decimals of the token.
+    #[clap(long, default_value("8"))]
+    decimals: u32,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: TransferOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let to = opts.to.parse::<Account>()?;
+    let amount = parse_tokens(&opts.amount, opts.decimals).map_err(|err| anyhow!(err))?;
+    let fee = opts
+        .fee
+        .map(|fee| parse_tokens(&fee, opts.decimals).map(Nat::from))
+        .transpose()
+        .map_err(|err| anyhow!(err))?;
+    let args = Encode!(&TransferArg {
+        from_subaccount: None,
+        to,
+        amount: Nat::from(amount),
+        fee,
+        memo: opts.memo.map(|memo| memo.to_be_bytes().to_vec()),
+        created_at_time: None,
+    })?;
+    let msg = sign_ingress_with_request_status_query(
+        pem,
+        canister_ids.ledger_canister_id,
+        "icrc1_transfer",
+        args,
+    )
+    .await?;
+    Ok(vec![msg])
+}
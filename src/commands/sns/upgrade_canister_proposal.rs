@@ -0,0 +1,91 @@
+use crate::commands::sns::{
+    manage_neuron::{sign_manage_neuron, Action, Command, Proposal, UpgradeSnsControlledCanister},
+    SnsCanisterIds,
+};
+use anyhow::{anyhow, Context};
+use candid::IDLArgs;
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{read_from_file, sign::signed_message::IngressWithRequestId, AnyhowResult};
+
+// The maximum size of an ingress message, which has to contain the whole wasm module.
+const MAX_INGRESS_BYTES: usize = 2 * 1024 * 1024;
+
+// Bytes reserved for the envelope and the other fields of the proposal.
+const ENVELOPE_BYTES: usize = 16 * 1024;
+
+/// Signs a proposal to upgrade a canister controlled by the SNS to a new wasm module.
+#[derive(Clap)]
+pub struct UpgradeCanisterProposalOpts {
+    /// The id of the proposing neuron (its hex-encoded subaccount).
+    neuron_id: String,
+
+    /// The canister to upgrade.
+    #[clap(long)]
+    target_canister_id: Principal,
+
+    /// Path to the new wasm module (possibly gzipped).
+    #[clap(long)]
+    wasm_path: String,
+
+    /// Path to the file with the candid arguments of the upgrade.
+    #[clap(long)]
+    canister_upgrade_arg: Option<String>,
+
+    /// The title of the proposal.
+    #[clap(long)]
+    title: String,
+
+    /// The summary of the proposal.
+    #[clap(long)]
+    summary: String,
+
+    /// The URL with more details on the proposal.
+    #[clap(long, default_value(""))]
+    url: String,
+}
+
+pub async fn exec(
+    pem: &Option<String>,
+    canister_ids: &SnsCanisterIds,
+    opts: UpgradeCanisterProposalOpts,
+) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let wasm = std::fs::read(&opts.wasm_path)
+        .with_context(|| format!("Couldn't read the wasm module {}", opts.wasm_path))?;
+    if wasm.len() + ENVELOPE_BYTES > MAX_INGRESS_BYTES {
+        return Err(anyhow!(
+            "The wasm module has {} bytes, but a proposal can hold at most {} bytes; try compressing it with gzip",
+            wasm.len(),
+            MAX_INGRESS_BYTES - ENVELOPE_BYTES
+        ));
+    }
+    let canister_upgrade_arg = opts
+        .canister_upgrade_arg
+        .map(|path| {
+            read_from_file(&path)?
+                .parse::<IDLArgs>()
+                .context("Couldn't parse the candid arguments of the upgrade")?
+                .to_bytes()
+                .map_err(|err| anyhow!(err))
+        })
+        .transpose()?;
+    eprintln!("Canister:    {}", opts.target_canister_id);
+    eprintln!("Wasm size:   {} bytes", wasm.len());
+    eprintln!("Wasm SHA256: {}", hex::encode(openssl::sha::sha256(&wasm)));
+
+    let command = Command::MakeProposal(Proposal {
+        url: opts.url,
+        title: opts.title,
+        action: Some(Action::UpgradeSnsControlledCanister(
+            UpgradeSnsControlledCanister {
+                new_canister_wasm: wasm,
+                canister_id: Some(opts.target_canister_id),
+                canister_upgrade_arg,
+            },
+        )),
+        summary: opts.summary,
+    });
+    Ok(vec![
+        sign_manage_neuron(pem, canister_ids, &opts.neuron_id, command).await?,
+    ])
+}
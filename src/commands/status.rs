@@ -0,0 +1,85 @@
+use crate::commands::OutputOpts;
+use clap::Clap;
+use ic_agent::agent::status::Value;
+use quill_core::{
+    error::QuillError, fingerprint::fingerprint, get_agent, ic_url, root_key, to_canonical_json,
+    AnyhowResult,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Prints the status of the endpoint, i.e. the version of the IC it runs and
+/// whether it reports the root key of the IC mainnet, as a check before
+/// sending anything to it.
+#[derive(Clap)]
+pub struct StatusOpts {}
+
+/// The status printed with `--json`. The fields the replica doesn't report are
+/// null.
+#[derive(Serialize, JsonSchema)]
+pub struct Status {
+    pub endpoint: String,
+    pub ic_api_version: String,
+    pub impl_version: Option<String>,
+    pub impl_hash: Option<String>,
+    /// The hex-encoded DER root key.
+    pub root_key: Option<String>,
+    pub root_key_fingerprint: Option<String>,
+    /// Whether the root key is the one of the IC mainnet.
+    pub mainnet_root_key: Option<bool>,
+}
+
+pub async fn exec(_opts: StatusOpts, output: &OutputOpts) -> AnyhowResult {
+    let status = get_agent(&None)?.status().await.map_err(|err| {
+        QuillError::Network(format!("Couldn't get the status of {}: {}", ic_url(), err))
+    })?;
+    let impl_hash = match status.values.get("impl_hash").map(|value| value.as_ref()) {
+        Some(Value::String(hash)) => Some(hash.clone()),
+        _ => None,
+    };
+    let root_key = status.root_key.as_deref();
+    let mainnet_key = root_key.map(root_key::is_mainnet_key);
+
+    if output.json {
+        println!(
+            "{}",
+            to_canonical_json(&Status {
+                endpoint: ic_url(),
+                ic_api_version: status.ic_api_version.clone(),
+                impl_version: status.impl_version.clone(),
+                impl_hash: impl_hash.clone(),
+                root_key: root_key.map(hex::encode),
+                root_key_fingerprint: root_key.map(fingerprint),
+                mainnet_root_key: mainnet_key,
+            })?
+        );
+    } else {
+        println!("Endpoint:     {}", ic_url());
+        println!("API version:  {}", status.ic_api_version);
+        let unknown = || "unknown".to_string();
+        println!(
+            "Version:      {}",
+            status.impl_version.clone().unwrap_or_else(unknown)
+        );
+        println!("Impl hash:    {}", impl_hash.unwrap_or_else(unknown));
+        match (root_key, mainnet_key) {
+            (Some(key), Some(true)) => {
+                println!("Root key:     {} (the IC mainnet key)", fingerprint(key))
+            }
+            (Some(key), _) => println!(
+                "Root key:     {} (NOT the IC mainnet key)",
+                fingerprint(key)
+            ),
+            (None, _) => println!("Root key:     not reported"),
+        }
+    }
+
+    if mainnet_key == Some(false) && !root_key::trusts_reported_key() {
+        return Err(QuillError::Network(format!(
+            "The replica at {} reports a root key which isn't the one of the IC mainnet",
+            ic_url()
+        ))
+        .into());
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::commands::{address::address_name, confirm::SendArgs, send::read_bundle, OutputOpts};
+use candid::Decode;
+use chrono::Utc;
+use clap::Clap;
+use quill_core::{
+    envelope,
+    icrc1::{format_tokens, group_digits, TransferArg},
+    to_canonical_json, AnyhowResult,
+};
+use std::collections::BTreeMap;
+
+// ICRC-1 ledgers reject memos longer than this.
+const MAX_MEMO_BYTES: usize = 32;
+
+/// Summarizes the messages of a file for their review: the ICP sent, the
+/// destinations, the governance operations, and anything unusual.
+#[derive(Clap)]
+pub struct SummarizeOpts {
+    /// Path to a message file, as accepted by `send` (use "-" for STDIN).
+    file_name: String,
+}
+
+#[derive(Default)]
+struct Summary {
+    messages: usize,
+    icp_amount_e8s: u64,
+    icp_fee_e8s: u64,
+    /// The amount and number of transfers by destination.
+    destinations: BTreeMap<String, (u64, usize)>,
+    /// The token units sent on ICRC-1 ledgers, by ledger canister.
+    token_units: BTreeMap<String, u128>,
+    /// The number of calls by method, or by command for `manage_neuron`.
+    operations: BTreeMap<String, usize>,
+    warnings: Vec<String>,
+}
+
+pub fn exec(opts: SummarizeOpts, output: &OutputOpts) -> AnyhowResult {
+    let bundle = read_bundle(&opts.file_name)?;
+    let mut summary = Summary::default();
+    for message in &bundle.messages {
+        let (expiration, _, canister_id, method_name, args) = message.ingress.inspect()?;
+        let number = message.index + 1;
+        summary.messages += 1;
+        if expiration < Utc::now() {
+            summary
+                .warnings
+                .push(format!("Message {}: expired at {}", number, expiration));
+        }
+        let arg = envelope::arg(&envelope::content(&hex::decode(&message.ingress.content)?)?)
+            .unwrap_or_default();
+        match method_name.as_str() {
+            "send_dfx" => {
+                let args = Decode!(&arg, SendArgs)?;
+                summary.icp_amount_e8s += args.amount.e8s;
+                summary.icp_fee_e8s += args.fee.e8s;
+                let destination = summary.destinations.entry(args.to).or_default();
+                destination.0 += args.amount.e8s;
+                destination.1 += 1;
+                if args.fee.e8s == 0 {
+                    summary
+                        .warnings
+                        .push(format!("Message {}: zero fee", number));
+                }
+                if args.amount.e8s == 0 {
+                    summary
+                        .warnings
+                        .push(format!("Message {}: zero amount", number));
+                }
+            }
+            "icrc1_transfer" => {
+                let args = Decode!(&arg, TransferArg)?;
+                let units: u128 = args.amount.0.to_string().parse().unwrap_or(u128::MAX);
+                let total = summary
+                    .token_units
+                    .entry(canister_id.to_text())
+                    .or_default();
+                *total = total.saturating_add(units);
+                if args.fee.map_or(false, |fee| fee.0.to_string() == "0") {
+                    summary
+                        .warnings
+                        .push(format!("Message {}: zero fee", number));
+                }
+                if let Some(memo) = args.memo.filter(|memo| memo.len() > MAX_MEMO_BYTES) {
+                    summary.warnings.push(format!(
+                        "Message {}: memo of {} bytes",
+                        number,
+                        memo.len()
+                    ));
+                }
+            }
+            "manage_neuron" => {
+                let operation = format!("manage_neuron {}", manage_neuron_command(&args));
+                *summary.operations.entry(operation).or_default() += 1;
+            }
+            _ => *summary.operations.entry(method_name).or_default() += 1,
+        }
+    }
+    if output.json {
+        print_json(&summary)
+    } else {
+        print_text(&summary)
+    }
+}
+
+// Returns the name of the command variant in the arguments of `manage_neuron`.
+fn manage_neuron_command(args: &str) -> String {
+    args.split("command = opt variant {")
+        .nth(1)
+        .and_then(|rest| {
+            rest.trim_start()
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or("(unknown)")
+        .to_string()
+}
+
+fn icp(e8s: u64) -> String {
+    format!("{} ICP", group_digits(&format_tokens(e8s, 8)))
+}
+
+fn print_text(summary: &Summary) -> AnyhowResult {
+    println!("Messages:      {}", summary.messages);
+    println!("ICP sent:      {}", icp(summary.icp_amount_e8s));
+    println!("ICP fees:      {}", icp(summary.icp_fee_e8s));
+    if !summary.destinations.is_empty() {
+        println!("Destinations:");
+        for (account, (e8s, transfers)) in &summary.destinations {
+            let name =
+                address_name(account)?.map_or_else(String::new, |name| format!(" ({})", name));
+            println!(
+                "  {}{}: {} in {} transfer(s)",
+                account,
+                name,
+                icp(*e8s),
+                transfers
+            );
+        }
+    }
+    if !summary.token_units.is_empty() {
+        println!("ICRC-1 transfers:");
+        for (ledger, units) in &summary.token_units {
+            println!("  {}: {} token units", ledger, units);
+        }
+    }
+    if !summary.operations.is_empty() {
+        println!("Other calls:");
+        for (operation, count) in &summary.operations {
+            println!("  {}: {}", operation, count);
+        }
+    }
+    if summary.warnings.is_empty() {
+        println!("No warnings.");
+    } else {
+        println!("Warnings:");
+        for warning in &summary.warnings {
+            println!("  {}", warning);
+        }
+    }
+    Ok(())
+}
+
+fn print_json(summary: &Summary) -> AnyhowResult {
+    let destinations: BTreeMap<_, _> = summary
+        .destinations
+        .iter()
+        .map(|(account, (e8s, transfers))| {
+            (
+                account.clone(),
+                serde_json::json!({ "amount_e8s": e8s, "transfers": transfers }),
+            )
+        })
+        .collect();
+    let token_units: BTreeMap<_, _> = summary
+        .token_units
+        .iter()
+        .map(|(ledger, units)| (ledger.clone(), units.to_string()))
+        .collect();
+    let json = serde_json::json!({
+        "messages": summary.messages,
+        "icp_amount_e8s": summary.icp_amount_e8s,
+        "icp_fee_e8s": summary.icp_fee_e8s,
+        "destinations": destinations,
+        "icrc1_token_units": token_units,
+        "operations": summary.operations,
+        "warnings": summary.warnings,
+    });
+    println!("{}", to_canonical_json(&json)?);
+    Ok(())
+}
@@ -1,18 +1,18 @@
 use crate::commands::{
+    address::resolve_account_id,
     send::{Memo, SendArgs},
     sign::sign_ingress_with_request_status_query,
 };
-use crate::lib::{ledger_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult};
 use anyhow::anyhow;
 use candid::Encode;
 use clap::Clap;
-use ledger_canister::{AccountIdentifier, ICPTs, TRANSACTION_FEE};
-use std::str::FromStr;
+use ledger_canister::{ICPTs, TRANSACTION_FEE};
+use quill_core::{ledger_canister_id, sign::signed_message::IngressWithRequestId, AnyhowResult};
 
 /// Signs an ICP transfer transaction.
 #[derive(Default, Clap)]
 pub struct TransferOpts {
-    /// Destination account.
+    /// Destination account id, or its name in the address book.
     pub to: String,
 
     /// Amount of ICPs to transfer (with up to 8 decimal digits after comma).
@@ -43,7 +43,7 @@ pub async fn exec(
             .parse::<u64>()
             .unwrap(),
     );
-    let to = AccountIdentifier::from_str(&opts.to).map_err(|err| anyhow!(err))?;
+    let to = resolve_account_id(&opts.to)?;
 
     let args = Encode!(&SendArgs {
         memo,
@@ -59,7 +59,7 @@ pub async fn exec(
     Ok(vec![msg])
 }
 
-fn parse_icpts(amount: &str) -> Result<ICPTs, String> {
+pub fn parse_icpts(amount: &str) -> Result<ICPTs, String> {
     let mut it = amount.split('.');
     let icpts = it
         .next()
@@ -78,11 +78,11 @@ fn parse_icpts(amount: &str) -> Result<ICPTs, String> {
     ICPTs::new(icpts, e8s)
 }
 
-fn icpts_amount_validator(icpts: &str) -> Result<(), String> {
+pub(crate) fn icpts_amount_validator(icpts: &str) -> Result<(), String> {
     parse_icpts(icpts).map(|_| ())
 }
 
-fn memo_validator(memo: &str) -> Result<(), String> {
+pub(crate) fn memo_validator(memo: &str) -> Result<(), String> {
     if memo.parse::<u64>().is_ok() {
         return Ok(());
     }
@@ -0,0 +1,105 @@
+use crate::commands::send::read_bundle;
+use chrono::Utc;
+use clap::Clap;
+use ic_types::Principal;
+use quill_core::{bundle::Bundle, envelope, error::QuillError, AnyhowResult};
+
+/// Checks a message file offline: recomputes the request ids, verifies the
+/// signatures against the embedded public keys and checks the expiry.
+#[derive(Clap)]
+pub struct VerifyOpts {
+    /// Path to the message file, as accepted by `send` (use "-" for STDIN).
+    file_name: String,
+
+    /// Fails unless every message was signed by this principal.
+    #[clap(long)]
+    expected_sender: Option<Principal>,
+
+    /// Fails unless every message is sent to this canister.
+    #[clap(long)]
+    expected_canister: Option<Principal>,
+}
+
+pub fn exec(opts: VerifyOpts) -> AnyhowResult {
+    let bundle = read_bundle(&opts.file_name)?;
+    let (lines, problems) = check(&bundle, opts.expected_sender, opts.expected_canister)?;
+    for line in lines {
+        println!("{}", line);
+    }
+    if !problems.is_empty() {
+        return Err(QuillError::Validation(format!(
+            "Verification failed:\n  {}",
+            problems.join("\n  ")
+        ))
+        .into());
+    }
+    println!("All {} messages verified.", bundle.messages.len());
+    Ok(())
+}
+
+/// Checks the messages of a bundle and returns a line describing each
+/// envelope, and the problems found.
+pub fn check(
+    bundle: &Bundle,
+    expected_sender: Option<Principal>,
+    expected_canister: Option<Principal>,
+) -> AnyhowResult<(Vec<String>, Vec<String>)> {
+    let mut lines = Vec::new();
+    let mut problems = Vec::new();
+    for message in &bundle.messages {
+        let mut envelopes = vec![("message", message.ingress.content.clone())];
+        if let Some(request_status) = &message.request_status {
+            envelopes.push(("request status", request_status.content.clone()));
+        }
+        for (kind, content) in envelopes {
+            let verification = envelope::verify(&hex::decode(content)?)?;
+            let request_id = String::from(verification.request_id);
+            let mut problem = |text: String| {
+                problems.push(format!("{} {}: {}", kind, message.index, text));
+            };
+            if kind == "message" {
+                if let Some(expected) = &message.ingress.request_id {
+                    if *expected != request_id {
+                        problem(format!(
+                            "the recorded request id 0x{} doesn't match",
+                            expected
+                        ));
+                    }
+                }
+                if let Some(expected) = expected_canister {
+                    if message.canister_id != expected.to_text() {
+                        problem(format!("sent to canister {}", message.canister_id));
+                    }
+                }
+            } else if let Some(request_status) = &message.request_status {
+                if Some(&request_status.request_id) != message.ingress.request_id.as_ref() {
+                    problem("the status isn't requested for this message".to_string());
+                }
+            }
+            match verification.signature_valid {
+                Some(true) => (),
+                Some(false) => problem("the signature is invalid".to_string()),
+                None => problem("the message isn't signed".to_string()),
+            }
+            if let Some(expected) = expected_sender {
+                if verification.sender != expected {
+                    problem(format!("signed by {}", verification.sender));
+                }
+            }
+            if Utc::now() > verification.ingress_expiry {
+                problem(format!("expired at {}", verification.ingress_expiry));
+            }
+            lines.push(format!(
+                "{} {}: {} of {} by {}, request id 0x{}, expires at {}",
+                kind,
+                message.index,
+                message.method_name,
+                message.canister_id,
+                verification.sender,
+                request_id,
+                verification.ingress_expiry
+            ));
+        }
+    }
+    Ok((lines, problems))
+}
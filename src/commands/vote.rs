@@ -0,0 +1,95 @@
+use crate::commands::{
+    list_neurons::ListNeurons,
+    neuron_alias::resolve_neuron_id,
+    neuron_manage::{Command, ManageNeuron, NeuronId, RegisterVote},
+    sign::sign_ingress_with_request_status_query,
+};
+use anyhow::anyhow;
+use candid::{Decode, Encode};
+use clap::Clap;
+use quill_core::{
+    get_agent, governance::ListNeuronsResponse, governance_canister_id,
+    sign::signed_message::IngressWithRequestId, AnyhowResult,
+};
+
+/// Signs a vote on a proposal with one or several neurons.
+#[derive(Clap)]
+pub struct VoteOpts {
+    /// The ids or aliases of the voting neurons.
+    neuron_ids: Vec<String>,
+
+    /// Vote with all neurons the signing principal controls or is a hot key of
+    /// (requires network access to list them).
+    #[clap(long, conflicts_with("neuron-ids"))]
+    all_my_neurons: bool,
+
+    /// The id of the proposal to vote on.
+    #[clap(long)]
+    proposal_id: u64,
+
+    /// Vote to adopt the proposal.
+    #[clap(long, conflicts_with("reject"), required_unless_present("reject"))]
+    approve: bool,
+
+    /// Vote to reject the proposal.
+    #[clap(long)]
+    reject: bool,
+}
+
+pub async fn exec(pem: &Option<String>, opts: VoteOpts) -> AnyhowResult<Vec<IngressWithRequestId>> {
+    let neuron_ids = if opts.all_my_neurons {
+        get_my_neuron_ids(pem).await?
+    } else {
+        opts.neuron_ids
+            .iter()
+            .map(|id| resolve_neuron_id(id))
+            .collect::<AnyhowResult<Vec<_>>>()?
+    };
+    if neuron_ids.is_empty() {
+        return Err(anyhow!("No neurons to vote with"));
+    }
+    // 1 is a "yes" and 2 is a "no" vote.
+    let vote = if opts.approve { 1 } else { 2 };
+
+    let mut generated = Vec::new();
+    for id in neuron_ids {
+        let args = Encode!(&ManageNeuron {
+            id: Some(NeuronId { id }),
+            command: Some(Command::RegisterVote(RegisterVote {
+                vote,
+                proposal: Some(NeuronId {
+                    id: opts.proposal_id
+                }),
+            })),
+        })?;
+        generated.push(
+            sign_ingress_with_request_status_query(
+                pem,
+                governance_canister_id(),
+                "manage_neuron",
+                args,
+            )
+            .await?,
+        );
+    }
+    Ok(generated)
+}
+
+// Queries the governance canister for all neurons readable by the signing principal.
+async fn get_my_neuron_ids(pem: &Option<String>) -> AnyhowResult<Vec<u64>> {
+    let args = Encode!(&ListNeurons {
+        neuron_ids: Vec::new(),
+        include_neurons_readable_by_caller: true,
+    })?;
+    let response = get_agent(pem)?
+        .query(&governance_canister_id(), "list_neurons")
+        .with_arg(&args)
+        .call()
+        .await?;
+    let response = Decode!(&response, ListNeuronsResponse)?;
+    Ok(response
+        .neuron_infos
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect())
+}
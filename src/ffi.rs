@@ -0,0 +1,132 @@
+//! The C interface of the signing functions, for custody systems which link
+//! quill instead of running the binary. The declarations are in
+//! `include/quill.h`, generated with `make header`.
+//!
+//! Every function returns 0 on success, or else the exit code of the binary
+//! for the error, e.g. 5 for an invalid key, and sets `*out` to the result or
+//! the error message; none of them exits the process. The
+//! string is owned by the caller and must be freed with `quill_free_string`.
+
+use crate::api;
+use anyhow::anyhow;
+use quill_core::{
+    error::{exit_code, QuillError},
+    AnyhowResult,
+};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Signs an ICP transfer like `quill transfer` and sets `*out` to the JSON of
+/// the signed messages. `fee` and `memo` may be null for the defaults.
+///
+/// # Safety
+///
+/// The strings must be null or valid null-terminated strings, and `out` must
+/// be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn quill_sign_transfer(
+    pem: *const c_char,
+    to: *const c_char,
+    amount: *const c_char,
+    fee: *const c_char,
+    memo: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    run(out, || {
+        let fee = optional_string(fee, "fee")?;
+        let memo = optional_string(memo, "memo")?;
+        api::sign_transfer(
+            &string(pem, "pem")?,
+            &string(to, "to")?,
+            &string(amount, "amount")?,
+            fee.as_deref(),
+            memo.as_deref(),
+        )
+    })
+}
+
+/// Signs a neuron command like `quill neuron-manage`, given as a JSON object
+/// like `{"neuron_id": "<neuron-id>", "start_dissolving": true}`, and sets
+/// `*out` to the JSON of the signed messages.
+///
+/// # Safety
+///
+/// The strings must be null or valid null-terminated strings, and `out` must
+/// be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn quill_sign_neuron_command(
+    pem: *const c_char,
+    command: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    run(out, || {
+        let command = api::NeuronCommand::from_json(&string(command, "command")?)?;
+        api::sign_neuron_command(&string(pem, "pem")?, &command)
+    })
+}
+
+/// Verifies signed messages like `quill verify` and sets `*out` to one line
+/// per checked envelope, or to the problems found.
+///
+/// # Safety
+///
+/// `message` must be null or a valid null-terminated string, and `out` must be
+/// a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn quill_verify_message(
+    message: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    run(out, || {
+        api::verify_message(&string(message, "message")?).map(|lines| lines.join("\n"))
+    })
+}
+
+/// Frees a string returned by the functions above.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by quill, which wasn't freed before.
+#[no_mangle]
+pub unsafe extern "C" fn quill_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+// Runs the function, catching panics, and passes its result or error to the
+// caller.
+unsafe fn run(out: *mut *mut c_char, f: impl FnOnce() -> AnyhowResult<String>) -> c_int {
+    let result = catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(anyhow!("quill panicked, see STDERR")));
+    let (code, text) = match result {
+        Ok(text) => (0, text),
+        Err(err) => (exit_code(&err), err.to_string()),
+    };
+    if !out.is_null() {
+        *out = CString::new(text.replace('\0', ""))
+            .unwrap_or_default()
+            .into_raw();
+    }
+    code
+}
+
+// Copies a string argument.
+unsafe fn string(s: *const c_char, name: &str) -> AnyhowResult<String> {
+    if s.is_null() {
+        return Err(QuillError::Validation(format!("The argument {} is null", name)).into());
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| QuillError::Validation(format!("The argument {} isn't UTF-8", name)).into())
+}
+
+// Copies a string argument which may be null.
+unsafe fn optional_string(s: *const c_char, name: &str) -> AnyhowResult<Option<String>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    string(s, name).map(Some)
+}
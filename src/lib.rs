@@ -0,0 +1,6 @@
+//! The commands of quill, which the binary runs, and the signing functions
+//! for other languages in [`api`], with their C interface in [`ffi`].
+
+pub mod api;
+pub mod commands;
+pub mod ffi;
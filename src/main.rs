@@ -1,23 +1,156 @@
 #![warn(unused_extern_crates)]
 use clap::{crate_version, AppSettings, Clap};
-mod commands;
-mod lib;
+use quill::commands;
+use quill_core::config::Defaults;
+use quill_core::http_transport::IpVersion;
 
 /// Ledger & Governance ToolKit for cold wallets.
 #[derive(Clap)]
 #[clap(name("quill"), version = crate_version!(), global_setting = AppSettings::ColoredHelp)]
 pub struct CliOpts {
-    /// Path to your PEM file (use "-" for STDIN)
+    /// Path to your PEM file (use "-" for STDIN), default is $QUILL_PEM_FILE
     #[clap(long)]
     pem_file: Option<String>,
 
+    /// The profile of the config file whose defaults are used, default is $QUILL_PROFILE.
+    #[clap(long)]
+    profile: Option<String>,
+
+    /// The URL of the IC, e.g. of a testnet or a local replica, or a
+    /// comma-separated list of endpoints which are failed over in order,
+    /// default is $QUILL_URL or $IC_URL, then https://ic0.app.
+    #[clap(long)]
+    url: Option<String>,
+
+    /// The network, i.e. mainnet, local (http://127.0.0.1:4943, whose root key
+    /// is fetched) or one of the config, instead of its URL; default is
+    /// $QUILL_NETWORK.
+    #[clap(long, conflicts_with("url"))]
+    network: Option<String>,
+
+    /// The proxy of all requests, e.g. http://proxy:3128 or socks5://proxy:1080,
+    /// default is $HTTPS_PROXY or $ALL_PROXY.
+    #[clap(long)]
+    proxy: Option<String>,
+
+    /// A PEM file with the certificate of an additional certificate authority
+    /// to trust, e.g. the internal one of a private deployment.
+    #[clap(long)]
+    ca_cert: Option<String>,
+
+    /// Accepts any TLS certificate. INSECURE: only for test deployments.
+    #[clap(long)]
+    insecure_tls: bool,
+
+    /// Fetches the root key from the replica and trusts it instead of only the
+    /// one of the IC mainnet, e.g. for a local replica or dfx network. UNSAFE:
+    /// refused for the endpoints of the IC mainnet.
+    #[clap(long, global = true, alias = "insecure-local-dev-env")]
+    fetch_root_key: bool,
+
+    /// Connects to the IC only over IPv4.
+    #[clap(long, conflicts_with("ipv6"))]
+    ipv4: bool,
+
+    /// Connects to the IC only over IPv6.
+    #[clap(long)]
+    ipv6: bool,
+
+    /// Connects to the host at the given address without looking it up, as
+    /// <host>=<ip address> (can be repeated).
+    #[clap(long, number_of_values(1))]
+    resolve: Vec<String>,
+
+    /// Logs the requests sent to the IC to STDERR, use twice for more details.
+    #[clap(short, long, parse(from_occurrences))]
+    verbose: u64,
+
+    /// Appends the logs to the given file instead of STDERR.
+    #[clap(long)]
+    log_file: Option<String>,
+
+    #[clap(flatten)]
+    output: commands::OutputOpts,
+
     #[clap(subcommand)]
     command: commands::Command,
 }
 
 fn main() {
-    let opts = CliOpts::parse();
+    let mut opts = CliOpts::parse();
+    if let Err(err) = quill_core::logging::init(opts.verbose, opts.log_file.as_deref()) {
+        eprintln!("{}", err);
+        std::process::exit(quill_core::error::exit_code(&err));
+    }
+    if let Err(err) = quill_core::proxy::configure(opts.proxy.as_deref()) {
+        eprintln!("{}", err);
+        std::process::exit(quill_core::error::exit_code(&err));
+    }
+    let ip_version = match (opts.ipv4, opts.ipv6) {
+        (true, _) => Some(IpVersion::V4),
+        (_, true) => Some(IpVersion::V6),
+        _ => None,
+    };
+    if let Err(err) =
+        quill_core::http_transport::set_tls_options(opts.ca_cert.as_deref(), opts.insecure_tls)
+            .and_then(|_| quill_core::http_transport::set_dns_options(ip_version, &opts.resolve))
+    {
+        eprintln!("{}", err);
+        std::process::exit(quill_core::error::exit_code(&err));
+    }
+    let profile = opts
+        .profile
+        .clone()
+        .or_else(|| std::env::var("QUILL_PROFILE").ok());
+    // Options given as flags take precedence over the environment, which takes
+    // precedence over the config file.
+    let fetch_root_key = opts.fetch_root_key;
+    let defaults = quill_core::config::Config::load()
+        .and_then(|config| {
+            let mut defaults = config
+                .defaults(profile.as_deref())?
+                .merge(Defaults::from_env()?)
+                .merge(Defaults {
+                    url: opts.url.clone(),
+                    network: opts.network.clone(),
+                    ..Defaults::default()
+                });
+            let network = defaults
+                .network
+                .as_deref()
+                .map(|name| config.network(name))
+                .transpose()?;
+            if let Some(network) = &network {
+                defaults.url = Some(network.url.clone());
+            }
+            Ok((defaults, network))
+        })
+        .and_then(|(defaults, network)| {
+            commands::apply_defaults(&defaults, &mut opts.output, &mut opts.command)?;
+            // The root key policy is checked against the URL of the network.
+            if fetch_root_key || network.map_or(false, |network| network.fetch_root_key) {
+                quill_core::root_key::set_fetch_root_key()?;
+            }
+            Ok(defaults)
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(quill_core::error::exit_code(&err));
+        });
+    if opts.pem_file.is_none() {
+        opts.pem_file = defaults.pem_file;
+    }
     let command = opts.command;
+    #[cfg(feature = "tui")]
+    if opts.pem_file.as_deref() == Some("-") && matches!(command, commands::Command::Shell) {
+        let err = anyhow::Error::from(quill_core::error::QuillError::Validation(
+            "The shell reads its commands from STDIN, so it can't read the PEM file from STDIN \
+             too; use the path of the PEM file"
+                .to_string(),
+        ));
+        eprintln!("{}", err);
+        std::process::exit(quill_core::error::exit_code(&err));
+    }
     let pem = opts.pem_file.map(|path| match path.as_str() {
         // read from STDIN
         "-" => {
@@ -25,17 +158,17 @@ fn main() {
             use std::io::Read;
             if let Err(err) = std::io::stdin().read_to_string(&mut buffer) {
                 eprintln!("Couldn't read from STDIN: {:?}", err);
-                std::process::exit(1);
+                std::process::exit(quill_core::error::IDENTITY_EXIT_CODE);
             }
             buffer
         }
         path => std::fs::read_to_string(path).unwrap_or_else(|err| {
             eprintln!("Couldn't read PEM file: {:?}", err);
-            std::process::exit(1);
+            std::process::exit(quill_core::error::IDENTITY_EXIT_CODE);
         }),
     });
-    if let Err(err) = commands::exec(&pem, command) {
+    if let Err(err) = commands::exec(&pem, &opts.output, command) {
         eprintln!("{}", err);
-        std::process::exit(1);
+        std::process::exit(quill_core::error::exit_code(&err));
     }
 }
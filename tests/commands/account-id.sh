@@ -0,0 +1 @@
+../target/debug/quill --pem-file - account-id --subaccount 1
@@ -0,0 +1 @@
+../target/debug/quill --ca-cert identity.pem public-ids 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill --offline canister-info rrkah-fqaaa-aaaaa-aaaaq-cai 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill checksum expired_message.json --expected 9b15279d34ea4577efad8487b7a7da3b6fbb4b44871c26e4be39e9eeb39b23be
@@ -0,0 +1,3 @@
+printf '[defaults]\nfee = "0.0002"\n\n[profiles.cold]\njson = true\npem_file = "identity.pem"\n' > "$QUILL_CONFIG_DIR/config.toml"
+../target/debug/quill --profile cold public-ids
+../target/debug/quill --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ../target/debug/quill send --dry-run -
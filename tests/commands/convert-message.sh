@@ -0,0 +1,6 @@
+PEM=$(cat)
+echo "$PEM" | ../target/debug/quill --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$QUILL_CONFIG_DIR/quill.json"
+../target/debug/quill convert-message "$QUILL_CONFIG_DIR/quill.json" --to dfx > "$QUILL_CONFIG_DIR/dfx.json"
+grep -E '"(version|call_type|sender|canister_id|method_name)"' "$QUILL_CONFIG_DIR/dfx.json"
+../target/debug/quill verify "$QUILL_CONFIG_DIR/dfx.json" | tail -n 1
+../target/debug/quill convert-message "$QUILL_CONFIG_DIR/dfx.json" --to quill | ../target/debug/quill verify - | tail -n 1
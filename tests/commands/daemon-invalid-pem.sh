@@ -0,0 +1,2 @@
+echo "not a key" | ../target/debug/quill --pem-file - daemon --socket "$QUILL_CONFIG_DIR/quill.sock" 2>&1 || echo "exit code $?"
+test -e "$QUILL_CONFIG_DIR/quill.sock" || echo "No socket was created."
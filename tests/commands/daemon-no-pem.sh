@@ -0,0 +1 @@
+../target/debug/quill daemon --socket "$QUILL_CONFIG_DIR/quill.sock" 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill decode --canister-id rkp4c-7iaaa-aaaaa-aaaca-cai --method-name notify_top_up --response 4449444c016b01bc8a017d010000e807
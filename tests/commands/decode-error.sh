@@ -0,0 +1 @@
+../target/debug/quill decode --response 4449444c046c01cbe2b58b08016e026b01a8f7dc32036c0290c6c1960571d19bc28f0e7501000100104e6575726f6e206e6f7420666f756e6404000000
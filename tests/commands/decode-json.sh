@@ -0,0 +1 @@
+../target/debug/quill --json decode ./expired_message.json
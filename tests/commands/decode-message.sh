@@ -0,0 +1 @@
+../target/debug/quill decode ./expired_message.json
@@ -0,0 +1,11 @@
+DIR="$QUILL_CONFIG_DIR"
+cat > "$DIR/identity.pem"
+QUILL="../target/debug/quill --pem-file $DIR/identity.pem --deterministic"
+$QUILL transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$DIR/first.json"
+$QUILL transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$DIR/second.json"
+cmp -s "$DIR/first.json" "$DIR/second.json" && echo "The signed messages are identical."
+QUILL_DETERMINISTIC_NONCE=01 $QUILL transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$DIR/other.json"
+cmp -s "$DIR/first.json" "$DIR/other.json" || echo "Another nonce seed gives other messages."
+../target/debug/quill summarize "$DIR/first.json" | tail -1
+QUILL_DETERMINISTIC_TIME=1640995200 $QUILL transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ../target/debug/quill summarize - | tail -1
+QUILL_DETERMINISTIC_TIME=yesterday $QUILL transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 2>&1 || echo "exit code $?"
@@ -0,0 +1,4 @@
+DIR=$(mktemp -d)
+../target/debug/quill --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > $DIR/message.json
+../target/debug/quill export-cbor $DIR/message.json --output-dir $DIR > /dev/null
+../target/debug/quill send --dry-run $DIR/1-send_dfx.cbor
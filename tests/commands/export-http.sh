@@ -0,0 +1,3 @@
+DIR=$(mktemp -d)
+../target/debug/quill --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > $DIR/message.json
+../target/debug/quill --ipv4 --resolve ic0.app=203.0.113.7 export-http $DIR/message.json --output-dir $DIR 2> /dev/null | sed "s|$DIR|DIR|g"
@@ -0,0 +1,3 @@
+../target/debug/quill fetch-did rdmx6-jaaaa-aaaaa-aaadq-cai --from-file ../quill-core/candid/cmc.did 2>/dev/null | sed "s|$QUILL_CONFIG_DIR|CONFIG|"
+ls "$QUILL_CONFIG_DIR/candid"
+../target/debug/quill fetch-did rdmx6-jaaaa-aaaaa-aaadq-cai --from-file identity.pem 2>&1 | head -c 62; echo
@@ -0,0 +1 @@
+../target/debug/quill --url https://ic0.app send --fetch-root-key --yes message.json 2>&1 || echo "exit code $?"
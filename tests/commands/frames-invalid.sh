@@ -0,0 +1,2 @@
+echo "UR:QUILL/1-99999999999/00000000/00" | ../target/debug/quill send --dry-run - 2>&1 || echo "exit code $?"
+echo "UR:QUILL/1-3/00000000/00" | ../target/debug/quill send --dry-run - 2>&1 || echo "exit code $?"
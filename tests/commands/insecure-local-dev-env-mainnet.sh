@@ -0,0 +1,2 @@
+../target/debug/quill --url https://icp0.io send --insecure-local-dev-env --yes message.json 2>&1 || echo "exit code $?"
+../target/debug/quill --url https://ic0.app --insecure-local-dev-env status 2>&1 || echo "exit code $?"
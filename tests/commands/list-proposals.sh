@@ -0,0 +1 @@
+../target/debug/quill --pem-file - list-proposals --before-proposal 123 --limit 20 | ../target/debug/quill send --dry-run -
@@ -0,0 +1,5 @@
+PEM=$(cat)
+for format in "--message-version 1" "--bundle" "--message-version 2" "--copies 2"; do
+    echo "$format:"
+    echo "$PEM" | ../target/debug/quill --pem-file - $format transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ../target/debug/quill verify - | tail -n 1
+done
@@ -0,0 +1,3 @@
+../target/debug/quill --network nowhere public-ids 2>&1 || echo "exit code $?"
+printf '[networks.spoofed]\nurl = "https://ic0.app"\nfetch_root_key = true\n' > "$QUILL_CONFIG_DIR/config.toml"
+../target/debug/quill --network spoofed public-ids 2>&1 || echo "exit code $?"
@@ -0,0 +1,5 @@
+export QUILL_CONFIG_DIR=$(mktemp -d)
+../target/debug/quill neuron-alias add treasury 2_313_380_519_530_470_538
+../target/debug/quill neuron-alias list
+../target/debug/quill --pem-file - neuron-manage treasury --spawn | ../target/debug/quill send --dry-run -
+../target/debug/quill neuron-alias add 1_000 2_313_380_519_530_470_538 2>&1 || echo "exit code $?"
@@ -0,0 +1,2 @@
+../target/debug/quill config set-maturity-destination fdsgv-62ihb-nbiqv-xgic5-iefsv-3cscz-tmbzv-63qd5-vh43v-dqfrt-pae
+../target/debug/quill --pem-file - neuron-manage 2313380519530470538 --spawn | ../target/debug/quill send --dry-run -
@@ -0,0 +1,2 @@
+../target/debug/quill --pem-file - neuron-manage 2313380519530470538 --split 100 --stake 100.5 2>&1 || echo "exit code $?"
+../target/debug/quill --pem-file - neuron-manage 2313380519530470538 --split 184467440738 --stake 100 2>&1 || echo "exit code $?"
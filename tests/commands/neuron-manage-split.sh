@@ -1 +1 @@
-../target/debug/quill --pem-file - neuron-manage 2313380519530470538 --split 100 | ../target/debug/quill send --dry-run -
+../target/debug/quill --pem-file - neuron-manage 2313380519530470538 --split 100 --stake 200 | ../target/debug/quill send --dry-run -
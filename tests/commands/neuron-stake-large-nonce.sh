@@ -0,0 +1 @@
+../target/debug/quill --pem-file - neuron-stake --nonce 12345678901 | ../target/debug/quill send --dry-run -
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - neuron-top-up --amount 12 --nonce 777 | ../target/debug/quill send --dry-run -
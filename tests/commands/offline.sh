@@ -0,0 +1,2 @@
+../target/debug/quill --offline --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ../target/debug/quill --offline send --dry-run -
+../target/debug/quill --offline --pem-file identity.pem vote --all-my-neurons --proposal-id 1 --approve 2>&1 || echo "exit code $?"
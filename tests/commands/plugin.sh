@@ -0,0 +1,18 @@
+mkdir -p "$QUILL_CONFIG_DIR/plugins"
+cat > "$QUILL_CONFIG_DIR/plugins/vault.toml" <<'EOT'
+canister_id = "qoctq-giaaa-aaaaa-aaaea-cai"
+candid = "vault.did"
+
+[operations.deposit]
+method = "deposit"
+args = '(${amount} : nat64)'
+
+[operations.note]
+method = "note"
+args = '("${memo}")'
+EOT
+echo 'service : { deposit : (nat64) -> (); note : (text) -> () }' > "$QUILL_CONFIG_DIR/plugins/vault.did"
+../target/debug/quill --pem-file - --dry-run vault deposit --amount 5 2>/dev/null
+../target/debug/quill --pem-file identity.pem --dry-run vault note --memo 'x"); ("${memo}' 2>/dev/null
+../target/debug/quill --pem-file identity.pem --dry-run vault deposit --amount '5 : nat64); (6' 2>&1 || echo "exit code $?"
+../target/debug/quill vaults 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill --proxy proxy:3128 public-ids 2>&1 || echo "exit code $?"
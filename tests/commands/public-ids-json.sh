@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --json public-ids
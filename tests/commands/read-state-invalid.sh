@@ -0,0 +1,2 @@
+../target/debug/quill read-state canister/0xzz/module_hash 2>&1 || echo "exit code $?"
+../target/debug/quill --offline read-state time 2>&1 || echo "exit code $?"
@@ -0,0 +1,2 @@
+../target/debug/quill --resolve ic0.app public-ids 2>&1 || echo "exit code $?"
+../target/debug/quill --resolve ic0.app=localhost public-ids 2>&1 || echo "exit code $?"
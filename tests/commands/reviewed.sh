@@ -0,0 +1,5 @@
+DIR=$(mktemp -d)
+TO=345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752
+../target/debug/quill --pem-file identity.pem --file $DIR/reviewed.json transfer $TO --amount 0.000123 2> /dev/null
+../target/debug/quill --pem-file identity.pem --file $DIR/resigned.json --reviewed $DIR/reviewed.json transfer $TO --amount 0.000123 2>&1 | grep "changed"
+../target/debug/quill --pem-file identity.pem --file $DIR/changed.json --reviewed $DIR/reviewed.json --strict transfer $TO --amount 0.000124 2>&1 | grep "differ" | sed "s|$DIR/||"
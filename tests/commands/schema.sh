@@ -0,0 +1,2 @@
+../target/debug/quill schema
+../target/debug/quill schema envelope 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 | ../target/debug/quill --url https://ic0.app/ send --dry-run - 2>&1 >/dev/null | sed -E 's/0x[0-9a-f]+/0x<request id>/; s/in [0-9]+ seconds/in <n> seconds/'
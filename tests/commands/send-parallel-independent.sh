@@ -0,0 +1,9 @@
+QUILL="$PWD/../target/debug/quill"
+PEM_FILE="$PWD/identity.pem"
+cd "$QUILL_CONFIG_DIR"
+"$QUILL" --pem-file "$PEM_FILE" transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > 1.json
+"$QUILL" --pem-file "$PEM_FILE" transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000456 > 2.json
+"$QUILL" combine --independent 1.json 2.json > independent.json
+"$QUILL" send --dry-run --parallel 2 independent.json 2>&1 >/dev/null | grep -v "^Dry run"
+"$QUILL" combine 1.json 2.json > chained.json
+"$QUILL" send --dry-run --parallel 2 chained.json 2>&1 >/dev/null | grep -v "^Dry run"
@@ -0,0 +1,5 @@
+QUILL="$PWD/../target/debug/quill"
+cd "$QUILL_CONFIG_DIR"
+"$QUILL" --pem-file - --bundle transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > bundle.json
+echo '{"checksum":"00","sent":[0]}' > bundle.json.progress
+"$QUILL" send --resume --dry-run bundle.json 2>&1 || echo "exit code $?"
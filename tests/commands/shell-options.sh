@@ -0,0 +1,5 @@
+DIR=$(mktemp -d)
+printf -- "--deterministic --expire-after 60 --file $DIR/first.json transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 1\n--deterministic --file $DIR/second.json transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 1\nexit\n" | ../target/debug/quill --pem-file identity.pem shell >/dev/null 2>&1
+../target/debug/quill summarize $DIR/first.json | grep expired
+../target/debug/quill summarize $DIR/second.json | grep expired
+../target/debug/quill --pem-file - shell 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+printf 'public-ids\n--json public-ids\nexit\n' | ../target/debug/quill --pem-file identity.pem shell
@@ -0,0 +1,3 @@
+echo d9d9f7a167636f6e74656e74a66c726571756573745f747970656571756572796673656e64657241046b63616e69737465725f69644a000000000000000201016b6d6574686f645f6e616d65736163636f756e745f62616c616e63655f6466786361726758504449444c016c01adf9e78a0a71010040333435663732336539653631393933346461616336616530663462653133613762306261353764366136303865353131613030666430646564353836363735326e696e67726573735f6578706972791b17979cfe362a0000 > "$QUILL_CONFIG_DIR/payload.hex"
+../target/debug/quill --pem-file - --dry-run sign-payload "$QUILL_CONFIG_DIR/payload.hex" 2>/dev/null
+echo 'not a payload' | ../target/debug/quill sign-payload - 2>&1 || echo "exit code $?"
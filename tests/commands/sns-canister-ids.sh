@@ -0,0 +1 @@
+../target/debug/quill sns --canister-ids-file ./sns_canister_ids.json canister-ids
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - sns --canister-ids-file ./sns_canister_ids.json claim-swap-neurons --finalize | ../target/debug/quill send --dry-run -
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - sns --canister-ids-file ./sns_canister_ids.json get-swap-refund | ../target/debug/quill send --dry-run -
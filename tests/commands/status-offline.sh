@@ -0,0 +1 @@
+../target/debug/quill --offline status 2>&1 || echo "exit code $?"
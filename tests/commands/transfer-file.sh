@@ -0,0 +1,3 @@
+DIR=$(mktemp -d)
+../target/debug/quill --pem-file - --file $DIR/message.json transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123
+../target/debug/quill send --dry-run $DIR/message.json
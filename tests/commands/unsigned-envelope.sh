@@ -0,0 +1,20 @@
+DIR="$QUILL_CONFIG_DIR"
+cat > "$DIR/key.pem"
+openssl pkey -in "$DIR/key.pem" -pubout > "$DIR/public.pem"
+../target/debug/quill --unsigned-envelope "$DIR/public.pem" transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123 > "$DIR/unsigned.json"
+grep -o '"sender_pubkey":"[0-9a-f]*"' "$DIR/unsigned.json"
+# The external signer signs the hashes with the key.
+ids=$(grep -o '"request_id":"[0-9a-f]*"' "$DIR/unsigned.json" | cut -d'"' -f4)
+hashes=$(grep -o '"sha256_to_sign":"[0-9a-f]*"' "$DIR/unsigned.json" | cut -d'"' -f4)
+signatures=""
+set -- $hashes
+for id in $ids; do
+    echo "$1" | xxd -r -p > "$DIR/hash.bin"
+    signature=$(openssl pkeyutl -sign -inkey "$DIR/key.pem" -in "$DIR/hash.bin" | xxd -p | tr -d '\n')
+    signatures="$signatures${signatures:+,}\"$id\":\"$signature\""
+    shift
+done
+echo "{$signatures}" > "$DIR/signatures.json"
+../target/debug/quill attach-signatures "$DIR/unsigned.json" --signatures "$DIR/signatures.json" | ../target/debug/quill verify - | tail -n 1
+echo '{}' > "$DIR/signatures.json"
+../target/debug/quill attach-signatures "$DIR/unsigned.json" --signatures "$DIR/signatures.json" 2>&1 | sed 's/0x[0-9a-f]*/0x<id>/'
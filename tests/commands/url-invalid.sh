@@ -0,0 +1,2 @@
+../target/debug/quill --url 127.0.0.1:8000 public-ids 2>&1 || echo "exit code $?"
+../target/debug/quill --url https://ic0.app,boundary.ic0.app public-ids 2>&1 || echo "exit code $?"
@@ -0,0 +1 @@
+../target/debug/quill verify ./expired_message.json 2> /dev/null || echo "Verification failed."
@@ -0,0 +1,55 @@
+/* Calls the C interface of quill like a custody system linking it would, and
+ * prints the return codes, which are compared with abi.txt. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "quill.h"
+
+static char pem[4096];
+
+static void check(const char *name, int code, char *out) {
+    printf("%s: %d\n", name, code);
+    quill_free_string(out);
+}
+
+int main(int argc, char **argv) {
+    FILE *file = fopen(argv[1], "r");
+    size_t len = fread(pem, 1, sizeof(pem) - 1, file);
+    pem[len] = '\0';
+    fclose(file);
+
+    char *out = NULL;
+    int code = quill_sign_transfer(pem, "345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752",
+                                   "0.000123", NULL, NULL, &out);
+    printf("sign transfer: %d\n", code);
+    char *verified = NULL;
+    check("verify transfer", quill_verify_message(out, &verified), verified);
+    quill_free_string(out);
+
+    code = quill_sign_neuron_command(pem, "{\"neuron_id\": \"2313380519530470538\", \"start_dissolving\": true}", &out);
+    printf("sign neuron command: %d\n", code);
+    check("verify neuron command", quill_verify_message(out, &verified), verified);
+    quill_free_string(out);
+
+    code = quill_sign_transfer(pem, NULL, "1", NULL, NULL, &out);
+    printf("sign transfer without destination: %d (%s)\n", code, out);
+    quill_free_string(out);
+
+    code = quill_sign_neuron_command(pem, "{\"neuron_id\": \"2313380519530470538\", \"no_such_option\": true}", &out);
+    printf("sign invalid neuron command: %d\n", code);
+    quill_free_string(out);
+
+    code = quill_sign_transfer("not a key", "345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752",
+                               "0.000123", NULL, NULL, &out);
+    printf("sign transfer with invalid key: %d (%s)\n", code, out);
+    quill_free_string(out);
+
+    code = quill_sign_neuron_command("not a key", "{\"neuron_id\": \"2313380519530470538\", \"start_dissolving\": true}", &out);
+    printf("sign neuron command with invalid key: %d (%s)\n", code, out);
+    quill_free_string(out);
+
+    check("verify invalid message", quill_verify_message("not a message", &out), out);
+    quill_free_string(NULL);
+    return 0;
+}
@@ -7,6 +7,7 @@ tests=0
 for f in `ls -1 ./commands/| sort -n`; do
     expected="outputs/${f/sh/txt}"
     out=$(mktemp)
+    export QUILL_CONFIG_DIR=$(mktemp -d)
     echo "$PEM" | sh "commands/$f" > "$out"
     if ! diff -r --ignore-all-space "$expected" "$out" >/dev/null; then
         >&2 echo "Test case $f failed." 
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Signs the inputs of the test vectors in the deterministic mode and compares
+# the messages byte for byte with the published outputs; `--fix` writes the
+# outputs instead, e.g. after an intended change of the format.
+cd "$(dirname "$0")/.."
+PEM=`cat ./identity.pem`
+
+set -euo pipefail
+
+unset QUILL_DETERMINISTIC_TIME QUILL_DETERMINISTIC_NONCE
+failed=0
+for f in `ls -1 ./vectors/inputs/ | sort`; do
+    expected="vectors/outputs/${f/sh/txt}"
+    out=$(mktemp)
+    export QUILL_CONFIG_DIR=$(mktemp -d)
+    echo "$PEM" | sh "vectors/inputs/$f" > "$out"
+    if [ "${1:-}" = "--fix" ]; then
+        cp "$out" "$expected"
+    elif [ ! -f "$expected" ]; then
+        >&2 echo "Test vector $f has no published output, run make vectors-fix and commit it."
+        failed=$((failed + 1))
+    elif ! cmp -s "$expected" "$out"; then
+        >&2 echo "Test vector $f changed:"
+        >&2 diff "$expected" "$out" || true
+        failed=$((failed + 1))
+    fi
+done
+
+if [ "$failed" -gt 0 ]; then
+    >&2 echo "$failed test vectors changed."
+    exit 1
+fi
+echo "✅ All test vectors match!"
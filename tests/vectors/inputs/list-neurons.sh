@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --deterministic list-neurons
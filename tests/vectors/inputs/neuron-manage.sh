@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --deterministic neuron-manage 2313380519530470538 --disburse --stop-dissolving
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --deterministic neuron-stake --amount 12 --nonce 777
@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --deterministic sns --canister-ids-file ./sns_canister_ids.json transfer fdsgv-62ihb-nbiqv-xgic5-iefsv-3cscz-tmbzv-63qd5-vh43v-dqfrt-pae --amount 0.000001
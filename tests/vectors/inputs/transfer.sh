@@ -0,0 +1 @@
+../target/debug/quill --pem-file - --deterministic transfer 345f723e9e619934daac6ae0f4be13a7b0ba57d6a608e511a00fd0ded5866752 --amount 0.000123